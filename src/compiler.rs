@@ -1,29 +1,39 @@
 extern crate chrono;
+use crate::docs::coverage::DocsCoverage;
+use crate::docs::html::HtmlSite;
+use crate::docs::item::DocsModule;
+use crate::docs::json::render_json;
 use crate::docs::module::DocumentationModule;
+use crate::docs::DocsFormat;
 use crate::get_version;
 use crate::modules::block::Block;
+use crate::modules::builtin::lock::Lock;
 use crate::modules::prelude::{BlockFragment, FragmentKind, FragmentRenderable, RawFragment};
 use crate::modules::typecheck::TypeCheckModule;
 use crate::optimizer::optimize_fragments;
 use crate::rules;
 use crate::translate::check_all_blocks;
 use crate::translate::module::TranslateModule;
+use crate::utils::function_cache::EntryPoint;
 use crate::utils::{pluralize, ParserMetadata, ShellType, TranslateMetadata};
 use colored::Colorize;
+use config::ProjectConfig;
 use heraclitus_compiler::prelude::*;
 use itertools::Itertools;
-use postprocessor::PostProcessor;
+use postprocessor::{FailurePolicy, PostProcessor};
 use std::fs;
 use std::fs::File;
 use std::io::{ErrorKind, Write};
 use std::iter::once;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{exit, ExitStatus};
 use std::time::Instant;
 use wildmatch::WildMatchPattern;
 
+pub mod config;
 pub mod postprocessor;
 pub mod shell_resolve;
+pub mod source_map;
 
 /// Escapes a string for safe use as a shell argument in double quotes.
 /// Handles shell-special characters: $ ` " \ !
@@ -49,6 +59,14 @@ pub struct CompilerOptions {
     pub no_optimize: bool,
     pub header_path: Option<String>,
     pub footer_path: Option<String>,
+    /// Marks the code of every statement with its position in the Amber source
+    pub source_map: bool,
+    /// Prints the Amber functions and positions a failure propagated through
+    pub stack_trace: bool,
+    /// Warns about public functions and constants without a doc comment
+    pub warn_missing_docs: bool,
+    /// Runs the postprocessors declared in the project config
+    pub project_config: bool,
 }
 
 impl Default for CompilerOptions {
@@ -65,6 +83,10 @@ impl Default for CompilerOptions {
             no_optimize: false,
             header_path: None,
             footer_path: None,
+            source_map: false,
+            stack_trace: false,
+            warn_missing_docs: false,
+            project_config: false,
         }
     }
 }
@@ -88,6 +110,10 @@ impl CompilerOptions {
             no_optimize: false,
             header_path: None,
             footer_path: None,
+            source_map: false,
+            stack_trace: false,
+            warn_missing_docs: false,
+            project_config: false,
         }
     }
 
@@ -116,6 +142,26 @@ impl CompilerOptions {
         self.target = target;
         self
     }
+
+    pub fn with_source_map(mut self, source_map: bool) -> Self {
+        self.source_map = source_map;
+        self
+    }
+
+    pub fn with_stack_trace(mut self, stack_trace: bool) -> Self {
+        self.stack_trace = stack_trace;
+        self
+    }
+
+    pub fn with_warn_missing_docs(mut self, warn_missing_docs: bool) -> Self {
+        self.warn_missing_docs = warn_missing_docs;
+        self
+    }
+
+    pub fn with_project_config(mut self, project_config: bool) -> Self {
+        self.project_config = project_config;
+        self
+    }
 }
 
 pub struct AmberCompiler {
@@ -178,6 +224,7 @@ impl AmberCompiler {
     pub fn parse(&self, tokens: Vec<Token>) -> Result<(Block, ParserMetadata), Message> {
         let code = self.cc.code.as_ref().expect(NO_CODE_PROVIDED).clone();
         let mut meta = ParserMetadata::new(tokens, self.path.clone(), Some(code));
+        meta.warn_missing_docs = self.options.warn_missing_docs;
         if let Err(Failure::Loud(err)) = check_all_blocks(&meta) {
             return Err(err);
         }
@@ -260,15 +307,24 @@ impl AmberCompiler {
         footer_template.replace("{{ version }}", get_version())
     }
 
-    fn gen_preamble(
+    pub(crate) fn gen_preamble(
         &self,
         sudo_used: bool,
         shell_metadata_used: bool,
+        defer_used: bool,
         target_shell: &ShellType,
     ) -> FragmentKind {
         let mut preamble = Vec::new();
         match target_shell {
             ShellType::BashModern | ShellType::BashLegacy => (),
+            ShellType::Posix => {
+                // if the shell is POSIX sh:
+                // - define helpers for arrays stored as strings of unit separated elements
+                preamble.push(
+                    RawFragment::new(include_str!("preambles/posix-arrays.sh").trim_end())
+                        .to_frag(),
+                );
+            }
             ShellType::Zsh => {
                 // if the shell is ZSH:
                 // - emulate ksh (ksh arrays, word splitting, ...) which matches more with bash
@@ -284,35 +340,125 @@ impl AmberCompiler {
             }
         }
         if sudo_used {
-            preamble.push(RawFragment::new(include_str!("preambles/sudo.sh").trim_end()).to_frag());
+            let sudo = match target_shell {
+                ShellType::Posix => include_str!("preambles/sudo-posix.sh"),
+                _ => include_str!("preambles/sudo.sh"),
+            };
+            preamble.push(RawFragment::new(sudo.trim_end()).to_frag());
         }
         if shell_metadata_used {
+            let shell_metadata = match target_shell {
+                ShellType::Posix => include_str!("preambles/shellname-shellversion-posix.sh"),
+                _ => include_str!("preambles/shellname-shellversion.sh"),
+            };
+            preamble.push(RawFragment::new(shell_metadata.trim_end()).to_frag());
+        }
+        if defer_used {
+            // Deferred blocks that are left when the script exits are run by the exit trap.
+            // It takes over the cleanup of the lock files as there can be only one exit trap.
+            preamble
+                .push(RawFragment::new(include_str!("preambles/defer.sh").trim_end()).to_frag());
+            preamble.push(RawFragment::new("__amber_cleanup_trap_installed=1").to_frag());
+            let cleanup = Lock::cleanup_command(target_shell);
             preamble.push(
-                RawFragment::new(include_str!("preambles/shellname-shellversion.sh").trim_end())
+                RawFragment::new(&format!("trap '__amber_defers_run \"\"; {cleanup}' EXIT"))
                     .to_frag(),
             );
         }
+        if self.options.stack_trace {
+            preamble.push(
+                RawFragment::new(include_str!("preambles/stack-trace.sh").trim_end()).to_frag(),
+            );
+        }
         BlockFragment::new(preamble, false).to_frag()
     }
 
-    pub fn translate(&self, block: Block, meta: ParserMetadata) -> Result<String, Message> {
-        let sudo_used = meta.sudo_used;
-        let shellname_used = meta.shellname_used;
-        let shellversion_used = meta.shellversion_used;
+    /// Rejects the features that cannot be expressed in the target shell
+    pub(crate) fn check_target_support(
+        meta: &mut ParserMetadata,
+        shell: ShellType,
+    ) -> Result<(), Message> {
+        let target = shell.canonical_name();
+        if shell.is_bash_legacy() || shell.is_posix() {
+            if let Some(pos) = meta.map_usage.take() {
+                let comment = if shell.is_posix() {
+                    "POSIX shells have no associative arrays. Use 'bash-4.3', 'zsh' or 'ksh' instead"
+                } else {
+                    "Associative arrays require Bash 4.0 or newer. Use 'bash-4.3', 'zsh' or 'ksh' instead"
+                };
+                return Err(Message::new_err_at_position(meta, pos)
+                    .message(format!("Maps are not supported by the '{target}' target"))
+                    .comment(comment));
+            }
+        }
+        if shell.is_posix() {
+            if let Some(pos) = meta.nested_array_usage.take() {
+                return Err(Message::new_err_at_position(meta, pos)
+                    .message(format!("Nested arrays are not supported by the '{target}' target"))
+                    .comment("Rows of nested arrays are encoded with 'printf %q' which POSIX shells lack"));
+            }
+            if let Some(pos) = meta.struct_array_usage.take() {
+                return Err(Message::new_err_at_position(meta, pos)
+                    .message(format!("Arrays of structs are not supported by the '{target}' target"))
+                    .comment("Items of arrays of structs are encoded with 'printf %q' which POSIX shells lack"));
+            }
+        }
+        if shell.is_posix() {
+            if let Some((pos, name)) = meta.bash_only_usage.take() {
+                return Err(Message::new_err_at_position(meta, pos)
+                    .message(format!("Function '{name}' of the standard library is not supported by the '{target}' target"))
+                    .comment("It runs commands written in Bash syntax that POSIX shells cannot parse. Use 'bash-4.3', 'zsh' or 'ksh' instead"));
+            }
+        }
+        if matches!(shell, ShellType::Ksh) {
+            if let Some(pos) = meta.capture_usage.take() {
+                return Err(Message::new_err_at_position(meta, pos)
+                    .message(format!("Lambdas capturing local variables are not supported by the '{target}' target"))
+                    .comment("Functions in ksh are statically scoped. Pass the value as an argument instead"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Lists the code that is run by the compiled script
+    fn get_entry_points(&self, meta: &ParserMetadata) -> Vec<EntryPoint> {
+        let mut entry_points = vec![EntryPoint::TopLevel];
+        if !self.options.test_mode {
+            entry_points.push(EntryPoint::Main);
+        } else if let Some(name) = &self.options.test_name {
+            entry_points.push(EntryPoint::Test(name.clone()));
+        } else {
+            let entries = meta.fun_cache.entry_points.keys();
+            let tests = entries.filter(|entry| matches!(entry, EntryPoint::Test(_)));
+            entry_points.extend(tests.cloned());
+        }
+        entry_points
+    }
+
+    pub fn translate(&self, block: Block, mut meta: ParserMetadata) -> Result<String, Message> {
+        Self::check_target_support(&mut meta, Self::resolve_target_shell(self.options.target))?;
         let ast_forest = self.get_sorted_ast_forest(block, &meta);
+        // Function calls that are left over were made in the global scope
+        let invoked = std::mem::take(&mut meta.invoked_functions);
+        meta.fun_cache
+            .add_entry_point(EntryPoint::TopLevel, invoked);
+        let entry_points = self.get_entry_points(&meta);
+        let reachable = meta.fun_cache.get_reachable_instances(&entry_points);
         let mut meta_translate = TranslateMetadata::new(meta, &self.options);
+        meta_translate.reachable_functions = Some(reachable);
         let time = Instant::now();
         let mut result = BlockFragment::new(Vec::new(), false);
-        // Add preamble that contains all code that should be executed before the main code
-        result.append(self.gen_preamble(
-            sudo_used,
-            shellname_used || shellversion_used,
-            &meta_translate.target.shell,
-        ));
-
         for (_path, block) in ast_forest {
             result.append(block.translate(&mut meta_translate));
         }
+        // Add preamble that contains all code that should be executed before the main code
+        let preamble = self.gen_preamble(
+            meta_translate.sudo_used,
+            meta_translate.shell_metadata_used,
+            meta_translate.defer_used,
+            &meta_translate.target.shell,
+        );
+        result.statements.insert(0, preamble);
         if self.options.debug_time {
             let pathname = self.path.clone().unwrap_or(String::from("unknown"));
             println!(
@@ -334,10 +480,10 @@ impl AmberCompiler {
             .no_proc
             .iter()
             .map(|x| WildMatchPattern::new(x))
-            .collect();
-        let postprocessors = PostProcessor::filter_default(filters);
+            .collect_vec();
+        let postprocessors = PostProcessor::filter(self.get_postprocessors()?, &filters);
         for postprocessor in postprocessors {
-            result = match postprocessor.execute(result) {
+            result = match postprocessor.execute(result.clone()) {
                 Ok(result) => result,
                 Err(error) => {
                     let error = format!(
@@ -345,7 +491,12 @@ impl AmberCompiler {
                         postprocessor.name,
                         error.to_string().trim_end(),
                     );
-                    return Err(Message::new_err_msg(error));
+                    match postprocessor.on_failure {
+                        FailurePolicy::Error => return Err(Message::new_err_msg(error)),
+                        FailurePolicy::Warn => Message::new_warn_msg(error).show(),
+                        FailurePolicy::Ignore => {}
+                    }
+                    result
                 }
             };
         }
@@ -358,7 +509,35 @@ impl AmberCompiler {
         ))
     }
 
-    pub fn document(&self, block: Block, meta: ParserMetadata, output: Option<String>) {
+    /// Default postprocessors extended with the ones declared in the project config
+    fn get_postprocessors(&self) -> Result<Vec<PostProcessor>, Message> {
+        // Skip looking for the config when every postprocessor is disabled
+        if self.options.no_proc.iter().any(|filter| filter == "*") {
+            return Ok(vec![]);
+        }
+        let declared = match ProjectConfig::find(self.path.as_deref()) {
+            // Declared postprocessors run arbitrary commands, so they need an opt-in
+            Some(file) if !self.options.project_config => {
+                Message::new_warn_msg(format!(
+                    "Postprocessors declared in '{}' were not run",
+                    file.display()
+                ))
+                .comment("Pass '--project-config' to run them")
+                .show();
+                vec![]
+            }
+            Some(file) => {
+                ProjectConfig::load(&file)
+                    .map_err(Message::new_err_msg)?
+                    .postprocessors
+            }
+            None => vec![],
+        };
+        Ok(PostProcessor::get_pipeline(declared))
+    }
+
+    /// Directory of the documented file that the module ids are relative to
+    fn get_docs_base_dir(meta: &ParserMetadata) -> PathBuf {
         let base_path = meta
             .get_path()
             .map(PathBuf::from)
@@ -369,13 +548,28 @@ impl AmberCompiler {
                 .to_owned()
                 .clone()
         });
-        let base_dir = base_dir.unwrap_or_else(|err| {
+        base_dir.unwrap_or_else(|err| {
             Message::new_err_msg("Couldn't get the absolute path to the provided input file")
                 .comment(err.to_string())
                 .show();
             std::process::exit(1);
-        });
+        })
+    }
+
+    pub fn document(
+        &self,
+        block: Block,
+        meta: ParserMetadata,
+        output: Option<String>,
+        format: DocsFormat,
+    ) {
+        let base_dir = Self::get_docs_base_dir(&meta);
         let ast_forest = self.get_sorted_ast_forest(block, &meta);
+        match format {
+            DocsFormat::Markdown => {}
+            DocsFormat::Html => return self.document_html(ast_forest, &meta, &base_dir, output),
+            DocsFormat::Json => return self.document_json(ast_forest, &meta, &base_dir, output),
+        }
         let mut paths = vec![];
         for (path, block) in ast_forest {
             let dep_path = {
@@ -460,6 +654,27 @@ impl AmberCompiler {
         Ok((messages, code))
     }
 
+    /// Collects the paths of all files imported by the code, including the transitive imports.
+    /// Files imported before a compilation error are collected as well.
+    pub fn import_paths(&self) -> Vec<String> {
+        let Ok(tokens) = self.tokenize() else {
+            return vec![];
+        };
+        let code = self.cc.code.as_ref().expect(NO_CODE_PROVIDED).clone();
+        let mut meta = ParserMetadata::new(tokens, self.path.clone(), Some(code));
+        let mut block = Block::new().with_no_syntax();
+        // Imports are resolved by the type checker, so it also runs on partially parsed code
+        let _ = block.parse(&mut meta);
+        let _ = block.typecheck(&mut meta);
+        meta.import_cache
+            .files
+            .into_iter()
+            .skip(1)
+            .map(|file| file.path)
+            .filter(|path| !path.starts_with("std/"))
+            .collect()
+    }
+
     pub fn execute(code: String, args: Vec<String>) -> Result<ExitStatus, std::io::Error> {
         Self::execute_with_target(code, args, None)
     }
@@ -485,15 +700,158 @@ impl AmberCompiler {
         }
     }
 
-    pub fn generate_docs(&self, output: Option<String>, usage: bool) -> Result<(), Message> {
+    fn document_html(
+        &self,
+        ast_forest: Vec<(String, Block)>,
+        meta: &ParserMetadata,
+        base_dir: &Path,
+        output: Option<String>,
+    ) {
+        let Some(output) = output else {
+            Message::new_err_msg("HTML documentation can't be written to the standard output")
+                .comment("Pass an output directory instead of '-'")
+                .show();
+            std::process::exit(1);
+        };
+        let modules = Self::get_docs_modules(ast_forest, meta, base_dir);
+        let dir_path = base_dir.join(output);
+        for (path, content) in HtmlSite::new(&modules).render() {
+            let path = dir_path.join(path);
+            let file_dir = path.parent().unwrap();
+            if let Err(err) = fs::create_dir_all(file_dir) {
+                let message = format!(
+                    "Couldn't create directory `{}`. Do you have sufficient permissions?",
+                    file_dir.display()
+                );
+                Message::new_err_msg(message)
+                    .comment(err.to_string())
+                    .show();
+                std::process::exit(1);
+            }
+            let mut file = File::create(path).unwrap();
+            file.write_all(content.as_bytes()).unwrap();
+        }
+        let index = dir_path.join("index.html");
+        let message = format!("Documentation generated at:\n{}", index.display());
+        Message::new_info_msg(message).show();
+    }
+
+    fn document_json(
+        &self,
+        ast_forest: Vec<(String, Block)>,
+        meta: &ParserMetadata,
+        base_dir: &Path,
+        output: Option<String>,
+    ) {
+        let modules = Self::get_docs_modules(ast_forest, meta, base_dir);
+        let document = render_json(&modules);
+        let Some(output) = output else {
+            std::io::stdout().write_all(document.as_bytes()).unwrap();
+            return;
+        };
+        let dir_path = base_dir.join(output);
+        if let Err(err) = fs::create_dir_all(&dir_path) {
+            let message = format!(
+                "Couldn't create directory `{}`. Do you have sufficient permissions?",
+                dir_path.display()
+            );
+            Message::new_err_msg(message)
+                .comment(err.to_string())
+                .show();
+            std::process::exit(1);
+        }
+        let path = dir_path.join("docs.json");
+        let mut file = File::create(&path).unwrap();
+        file.write_all(document.as_bytes()).unwrap();
+        let message = format!("Documentation generated at:\n{}", path.display());
+        Message::new_info_msg(message).show();
+    }
+
+    /// Assigns ids and paths to the modules of the import graph. Modules
+    /// outside of the documented directory are placed in the `deps` directory.
+    fn get_docs_module_ids(
+        ast_forest: Vec<(String, Block)>,
+        base_dir: &Path,
+    ) -> Vec<(String, String, Block)> {
+        let mut modules: Vec<(String, String, Block)> = vec![];
+        for (path, block) in ast_forest {
+            let (mut id, path) = if path.starts_with("std/") {
+                (path.trim_end_matches(".ab").to_string(), path)
+            } else {
+                let Ok(dep_path) = fs::canonicalize(&path) else {
+                    continue;
+                };
+                let id = match dep_path.strip_prefix(base_dir) {
+                    Ok(file_path) => file_path.with_extension("").to_string_lossy().to_string(),
+                    Err(_) => {
+                        let filename = dep_path.file_stem().unwrap().to_string_lossy();
+                        format!("deps/{filename}")
+                    }
+                };
+                (id, dep_path.to_string_lossy().to_string())
+            };
+            let base_id = id.clone();
+            let mut index = 1;
+            while modules.iter().any(|(module_id, _, _)| *module_id == id) {
+                index += 1;
+                id = format!("{base_id}_{index}");
+            }
+            modules.push((id, path, block));
+        }
+        modules
+    }
+
+    /// Collects the documentation of every module in the import graph
+    pub fn get_docs_modules(
+        ast_forest: Vec<(String, Block)>,
+        meta: &ParserMetadata,
+        base_dir: &Path,
+    ) -> Vec<DocsModule> {
+        Self::get_docs_module_ids(ast_forest, base_dir)
+            .into_iter()
+            .map(|(id, path, block)| DocsModule {
+                id,
+                path,
+                functions: block.get_docs_functions(meta),
+            })
+            .collect()
+    }
+
+    /// Coverage of the modules in the directory of the documented file
+    pub fn get_docs_coverage(
+        ast_forest: Vec<(String, Block)>,
+        base_dir: &Path,
+    ) -> Vec<DocsCoverage> {
+        Self::get_docs_module_ids(ast_forest, base_dir)
+            .into_iter()
+            .filter(|(_, path, _)| Path::new(path).starts_with(base_dir))
+            .map(|(id, _, block)| DocsCoverage::new(id, block.get_public_items()))
+            .collect()
+    }
+
+    pub fn generate_docs(
+        &self,
+        output: Option<String>,
+        usage: bool,
+        format: DocsFormat,
+    ) -> Result<(), Message> {
         let tokens = self.tokenize()?;
         let (block, meta) = self.parse(tokens)?;
         let (block, mut meta) = self.typecheck(block, meta)?;
         meta.doc_usage = usage;
-        self.document(block, meta, output);
+        self.document(block, meta, output, format);
         Ok(())
     }
 
+    pub fn generate_docs_coverage(&self) -> Result<Vec<DocsCoverage>, Message> {
+        let tokens = self.tokenize()?;
+        let (block, meta) = self.parse(tokens)?;
+        let (block, meta) = self.typecheck(block, meta)?;
+        let base_dir = Self::get_docs_base_dir(&meta);
+        let ast_forest = self.get_sorted_ast_forest(block, &meta);
+        Ok(Self::get_docs_coverage(ast_forest, &base_dir))
+    }
+
     #[cfg(test)]
     pub fn test_eval(&mut self) -> Result<(String, ExitStatus), Message> {
         self.options.no_proc = vec!["*".into()];
@@ -0,0 +1,139 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use toml::{Table, Value};
+
+use super::postprocessor::{FailurePolicy, PostProcessor};
+
+pub const CONFIG_FILE: &str = "amber.toml";
+
+/// Settings of an Amber project read from the `amber.toml` file.
+///
+/// ```toml
+/// [[postprocessor]]
+/// name = "shellcheck"
+/// command = "shellcheck"
+/// args = ["-s", "bash", "-"]
+/// check = true
+/// on-failure = "error"
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProjectConfig {
+    /// Postprocessors in the order they are run after the default ones
+    pub postprocessors: Vec<PostProcessor>,
+}
+
+impl ProjectConfig {
+    /// Looks for the config file in the directory of the compiled file and then
+    /// in the working directory. Parent directories are not searched, so that
+    /// a file of a project does not pick up the config of an enclosing tree.
+    pub fn find(path: Option<&str>) -> Option<PathBuf> {
+        let file_dir = path
+            .and_then(|path| fs::canonicalize(path).ok())
+            .and_then(|path| path.parent().map(Path::to_path_buf));
+        file_dir
+            .into_iter()
+            .chain(std::env::current_dir().ok())
+            .map(|dir| dir.join(CONFIG_FILE))
+            .find(|file| file.is_file())
+    }
+
+    pub fn load(file: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(file)
+            .map_err(|err| format!("Couldn't read '{}': {err}", file.display()))?;
+        let base_dir = file.parent().unwrap_or(Path::new("."));
+        Self::parse(&content, base_dir)
+            .map_err(|err| format!("Invalid '{}': {err}", file.display()))
+    }
+
+    /// Parses the config. Relative command paths are resolved against `base_dir`.
+    pub fn parse(content: &str, base_dir: &Path) -> Result<Self, String> {
+        let table = content
+            .parse::<Table>()
+            .map_err(|err| err.message().to_string())?;
+        let mut config = ProjectConfig::default();
+        for (key, value) in table {
+            match key.as_str() {
+                "postprocessor" => {
+                    let Value::Array(entries) = value else {
+                        return Err("'postprocessor' must be an array of tables".to_string());
+                    };
+                    for entry in entries {
+                        let Value::Table(entry) = entry else {
+                            return Err("'postprocessor' must be an array of tables".to_string());
+                        };
+                        config
+                            .postprocessors
+                            .push(parse_postprocessor(entry, base_dir)?);
+                    }
+                }
+                _ => return Err(format!("Unknown key '{key}'")),
+            }
+        }
+        Ok(config)
+    }
+}
+
+fn parse_postprocessor(entry: Table, base_dir: &Path) -> Result<PostProcessor, String> {
+    let get_str = |key: &str| match entry.get(key) {
+        Some(Value::String(value)) => Ok(Some(value.clone())),
+        Some(_) => Err(format!("Postprocessor field '{key}' must be a string")),
+        None => Ok(None),
+    };
+    let name = get_str("name")?.ok_or("Postprocessor is missing the 'name' field")?;
+    let command = get_str("command")?.unwrap_or_else(|| name.clone());
+    let args = match entry.get("args") {
+        Some(Value::Array(args)) => args
+            .iter()
+            .map(|arg| match arg {
+                Value::String(arg) => Ok(arg.clone()),
+                _ => Err(format!(
+                    "Arguments of postprocessor '{name}' must be strings"
+                )),
+            })
+            .collect::<Result<Vec<String>, String>>()?,
+        Some(_) => {
+            return Err(format!(
+                "Field 'args' of postprocessor '{name}' must be an array"
+            ))
+        }
+        None => vec![],
+    };
+    let check_only = match entry.get("check") {
+        Some(Value::Boolean(value)) => *value,
+        Some(_) => {
+            return Err(format!(
+                "Field 'check' of postprocessor '{name}' must be a boolean"
+            ))
+        }
+        None => false,
+    };
+    let on_failure = match get_str("on-failure")?.as_deref() {
+        Some("error") | None => FailurePolicy::Error,
+        Some("warn") => FailurePolicy::Warn,
+        Some("ignore") => FailurePolicy::Ignore,
+        Some(policy) => {
+            return Err(format!(
+                "Unknown failure policy '{policy}' of postprocessor '{name}'. Expected 'error', 'warn' or 'ignore'"
+            ))
+        }
+    };
+    if let Some(key) = entry
+        .keys()
+        .find(|key| !["name", "command", "args", "check", "on-failure"].contains(&key.as_str()))
+    {
+        return Err(format!("Unknown field '{key}' of postprocessor '{name}'"));
+    }
+    // Scripts next to the config are referred to with a relative path
+    let bin = if command.contains('/') {
+        base_dir.join(command)
+    } else {
+        PathBuf::from(command)
+    };
+    let postprocessor = PostProcessor::new(name, bin)
+        .with_check_only(check_only)
+        .with_failure_policy(on_failure)
+        .with_optional(false);
+    postprocessor.cmd().borrow_mut().args(args);
+    Ok(postprocessor)
+}
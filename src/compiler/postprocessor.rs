@@ -1,4 +1,5 @@
 use std::cell::RefCell;
+use std::env;
 use std::io::{BufWriter, Write};
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
@@ -7,10 +8,26 @@ use std::rc::Rc;
 use itertools::Itertools;
 use wildmatch::WildMatchPattern;
 
+/// Decides what happens to the compilation when a postprocessor fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailurePolicy {
+    /// Stop the compilation with an error
+    Error,
+    /// Show a warning and continue with the unprocessed code
+    Warn,
+    /// Silently continue with the unprocessed code
+    Ignore,
+}
+
 #[derive(Debug, Clone)]
 pub struct PostProcessor {
     pub name: String,
     pub bin: PathBuf,
+    /// Only verifies the code and leaves it unchanged
+    pub check_only: bool,
+    pub on_failure: FailurePolicy,
+    /// Whether the postprocessor is skipped when it is not installed
+    pub optional: bool,
     command: Rc<RefCell<Command>>,
 }
 
@@ -23,23 +40,52 @@ impl PostProcessor {
         command.stdout(Stdio::piped());
         command.stderr(Stdio::piped());
         let command = Rc::new(RefCell::new(command));
-        Self { name, bin, command }
+        Self {
+            name,
+            bin,
+            check_only: false,
+            on_failure: FailurePolicy::Error,
+            optional: true,
+            command,
+        }
+    }
+
+    pub fn with_check_only(mut self, check_only: bool) -> Self {
+        self.check_only = check_only;
+        self
+    }
+
+    pub fn with_failure_policy(mut self, on_failure: FailurePolicy) -> Self {
+        self.on_failure = on_failure;
+        self
+    }
+
+    pub fn with_optional(mut self, optional: bool) -> Self {
+        self.optional = optional;
+        self
     }
 
     pub fn cmd(&self) -> Rc<RefCell<Command>> {
         self.command.clone()
     }
 
+    /// Looks the binary up without running it, so that a declared command
+    /// is only ever run with the code to process
     pub fn is_available(&self) -> bool {
-        match Command::new(self.bin.clone()).spawn() {
-            Ok(mut v) => v.kill().is_ok(),
-            Err(_) => false,
+        if self.bin.components().count() > 1 {
+            return self.bin.is_file();
         }
+        env::var_os("PATH")
+            .map(|paths| env::split_paths(&paths).any(|dir| dir.join(&self.bin).is_file()))
+            .unwrap_or(false)
     }
 
     pub fn execute(&self, code: String) -> Result<String, Box<dyn std::error::Error>> {
         if !self.is_available() {
-            return Ok(code);
+            if self.optional {
+                return Ok(code);
+            }
+            return Err(format!("Command '{}' was not found", self.bin.display()).into());
         }
 
         let mut spawned = self.cmd().borrow_mut().spawn()?;
@@ -56,6 +102,9 @@ impl PostProcessor {
         // read from stdout or stderr
         let res = spawned.wait_with_output()?;
         if res.status.success() {
+            if self.check_only {
+                return Ok(code);
+            }
             let stdout = String::from_utf8(res.stdout)?;
             Ok(stdout)
         } else {
@@ -74,13 +123,24 @@ impl PostProcessor {
         postprocessors
     }
 
-    pub fn filter_default(filters: Vec<WildMatchPattern<'*', '?'>>) -> Vec<Self> {
-        let default = Self::get_default();
+    /// Builds the pipeline from the default postprocessors followed by the
+    /// ones declared in the project config. A declared postprocessor replaces
+    /// the default one of the same name.
+    pub fn get_pipeline(declared: Vec<Self>) -> Vec<Self> {
+        let mut pipeline = Self::get_default();
+        for postprocessor in declared {
+            match pipeline.iter_mut().find(|x| x.name == postprocessor.name) {
+                Some(existing) => *existing = postprocessor,
+                None => pipeline.push(postprocessor),
+            }
+        }
+        pipeline
+    }
 
-        default
-            .iter()
+    pub fn filter(postprocessors: Vec<Self>, filters: &[WildMatchPattern<'*', '?'>]) -> Vec<Self> {
+        postprocessors
+            .into_iter()
             .filter(|x| filters.iter().all(|xx| !xx.matches(&x.name)))
-            .cloned()
             .collect_vec()
     }
 }
@@ -78,6 +78,11 @@ impl AmberCompiler {
             Some(ShellType::Ksh)
         } else if shell.contains("bash") {
             Some(ShellType::BashModern)
+        } else if matches!(
+            shell.rsplit('/').next(),
+            Some("sh" | "dash" | "ash" | "busybox")
+        ) {
+            Some(ShellType::Posix)
         } else {
             None
         }
@@ -85,7 +90,7 @@ impl AmberCompiler {
 
     #[cfg(not(windows))]
     fn find_runtime_shell_name() -> Option<String> {
-        ["bash", "zsh", "ksh"]
+        ["bash", "zsh", "ksh", "sh"]
             .into_iter()
             .find(|shell| {
                 env::var_os("PATH").is_some_and(|paths| {
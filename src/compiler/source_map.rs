@@ -0,0 +1,114 @@
+use crate::modules::prelude::{CommentFragment, FragmentKind, FragmentRenderable};
+use clap::ValueEnum;
+use heraclitus_compiler::prelude::*;
+use serde_json::json;
+
+const MARKER_PREFIX: &str = "# amber:";
+
+/// Determines where the positions of the Amber source are written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum SourceMapMode {
+    /// Write the positions to a `.map` file next to the output
+    #[default]
+    File,
+    /// Keep the `# amber:file:line:column` markers in the output
+    Inline,
+}
+
+/// Generated line of the script along with the position in Amber code that produced it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceMapping {
+    pub line: usize,
+    pub source: usize,
+    pub source_line: usize,
+    pub source_column: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    pub sources: Vec<String>,
+    pub mappings: Vec<SourceMapping>,
+}
+
+/// Creates a comment that marks the position of the statement that produces the following lines.
+pub fn source_marker(position: &PositionInfo) -> FragmentKind {
+    match (&position.path, &position.position) {
+        (Some(path), Position::Pos(row, col)) => {
+            CommentFragment::new(&format!("amber:{path}:{row}:{col}")).to_frag()
+        }
+        _ => FragmentKind::Empty,
+    }
+}
+
+/// Parses the `file`, `line` and `column` of a source marker.
+/// The file is parsed last as it can contain colons.
+fn parse_marker(line: &str) -> Option<(&str, usize, usize)> {
+    let marker = line.trim_start().strip_prefix(MARKER_PREFIX)?;
+    let mut parts = marker.rsplitn(3, ':');
+    let column = parts.next()?.parse().ok()?;
+    let row = parts.next()?.parse().ok()?;
+    let path = parts.next()?;
+    Some((path, row, column))
+}
+
+impl SourceMap {
+    /// Removes the source markers from the generated code and
+    /// maps every line that follows a marker to its position.
+    pub fn extract(code: &str) -> (String, SourceMap) {
+        let mut source_map = SourceMap::default();
+        let mut lines = vec![];
+        let mut current = None;
+        for line in code.lines() {
+            if let Some((path, row, column)) = parse_marker(line) {
+                let source = match source_map.sources.iter().position(|item| item == path) {
+                    Some(index) => index,
+                    None => {
+                        source_map.sources.push(path.to_string());
+                        source_map.sources.len() - 1
+                    }
+                };
+                current = Some((source, row, column));
+                continue;
+            }
+            lines.push(line);
+            if let Some((source, source_line, source_column)) = current {
+                if !line.trim().is_empty() {
+                    source_map.mappings.push(SourceMapping {
+                        line: lines.len(),
+                        source,
+                        source_line,
+                        source_column,
+                    });
+                }
+            }
+        }
+        let mut code = lines.join("\n");
+        if !code.is_empty() {
+            code.push('\n');
+        }
+        (code, source_map)
+    }
+
+    /// Renders the source map of the given generated file as JSON.
+    pub fn to_json(&self, file: &str) -> String {
+        let mappings = self
+            .mappings
+            .iter()
+            .map(|mapping| {
+                json!({
+                    "line": mapping.line,
+                    "source": mapping.source,
+                    "source_line": mapping.source_line,
+                    "source_column": mapping.source_column,
+                })
+            })
+            .collect::<Vec<_>>();
+        let json = json!({
+            "version": 1,
+            "file": file,
+            "sources": self.sources,
+            "mappings": mappings,
+        });
+        serde_json::to_string_pretty(&json).expect("Source map is always valid JSON")
+    }
+}
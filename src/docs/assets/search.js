@@ -0,0 +1,45 @@
+// Filters the functions listed in `search-index.js` by the typed query
+(function () {
+    const input = document.getElementById("search");
+    const results = document.getElementById("search-results");
+    const main = document.querySelector("main");
+    const root = document.body.dataset.root;
+    const index = window.searchIndex || [];
+
+    function escape(text) {
+        const element = document.createElement("span");
+        element.textContent = text;
+        return element.innerHTML;
+    }
+
+    input.addEventListener("input", function () {
+        const query = input.value.trim().toLowerCase();
+        if (!query) {
+            results.hidden = true;
+            main.hidden = false;
+            return;
+        }
+        const matches = index
+            .filter(function (item) {
+                return item.name.toLowerCase().includes(query)
+                    || item.module.toLowerCase().includes(query)
+                    || item.summary.toLowerCase().includes(query);
+            })
+            // Functions whose name matches come first
+            .sort(function (a, b) {
+                const aName = a.name.toLowerCase().includes(query) ? 0 : 1;
+                const bName = b.name.toLowerCase().includes(query) ? 0 : 1;
+                return aName - bName || a.name.localeCompare(b.name);
+            })
+            .slice(0, 50);
+        results.innerHTML = matches.length
+            ? matches.map(function (item) {
+                return '<li><a href="' + root + item.url + '"><code>' + escape(item.signature)
+                    + '</code></a> <span class="count">' + escape(item.module) + '</span>'
+                    + '<p>' + escape(item.summary) + '</p></li>';
+            }).join("")
+            : "<li>No functions found</li>";
+        results.hidden = false;
+        main.hidden = true;
+    });
+})();
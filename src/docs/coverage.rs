@@ -0,0 +1,88 @@
+/// Documentation coverage of the public functions and constants of a module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocsCoverage {
+    pub id: String,
+    pub documented: usize,
+    pub total: usize,
+    /// Names of the public items without a doc comment
+    pub missing: Vec<String>,
+}
+
+impl DocsCoverage {
+    pub fn new(id: String, items: Vec<(String, bool)>) -> Self {
+        let total = items.len();
+        let missing = items
+            .into_iter()
+            .filter(|(_, is_documented)| !is_documented)
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        DocsCoverage {
+            id,
+            documented: total - missing.len(),
+            total,
+            missing,
+        }
+    }
+}
+
+/// Share of the documented items of all modules in percent.
+/// Modules without any public items are fully documented.
+pub fn get_total_coverage(modules: &[DocsCoverage]) -> f64 {
+    let documented = modules
+        .iter()
+        .map(|module| module.documented)
+        .sum::<usize>();
+    let total = modules.iter().map(|module| module.total).sum::<usize>();
+    if total == 0 {
+        return 100.0;
+    }
+    documented as f64 * 100.0 / total as f64
+}
+
+fn get_percentage(documented: usize, total: usize) -> String {
+    if total == 0 {
+        return "-".to_string();
+    }
+    format!("{:.1}%", documented as f64 * 100.0 / total as f64)
+}
+
+/// Renders a table with the coverage of every module and lists the undocumented items
+pub fn render_coverage(modules: &[DocsCoverage]) -> String {
+    let documented = modules
+        .iter()
+        .map(|module| module.documented)
+        .sum::<usize>();
+    let total = modules.iter().map(|module| module.total).sum::<usize>();
+    let mut rows = vec![(
+        "Module".to_string(),
+        "Documented".to_string(),
+        "Coverage".to_string(),
+    )];
+    for module in modules {
+        rows.push((
+            module.id.clone(),
+            format!("{}/{}", module.documented, module.total),
+            get_percentage(module.documented, module.total),
+        ));
+    }
+    rows.push((
+        "Total".to_string(),
+        format!("{documented}/{total}"),
+        get_percentage(documented, total),
+    ));
+    let width = rows.iter().map(|row| row.0.len()).max().unwrap_or_default();
+    let mut result = rows
+        .iter()
+        .map(|(id, count, percentage)| format!("{id:width$}  {count:>10}  {percentage:>8}\n"))
+        .collect::<String>();
+    let missing = modules
+        .iter()
+        .filter(|module| !module.missing.is_empty())
+        .map(|module| format!("{}: {}\n", module.id, module.missing.join(", ")))
+        .collect::<String>();
+    if !missing.is_empty() {
+        result.push_str("\nMissing documentation:\n");
+        result.push_str(&missing);
+    }
+    result
+}
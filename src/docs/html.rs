@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use pulldown_cmark::{html, CowStr, Event, Parser};
+use serde_json::json;
+
+use super::item::{DocsFunction, DocsModule};
+
+const STYLE: &str = include_str!("assets/style.css");
+const SEARCH: &str = include_str!("assets/search.js");
+
+/// Renders the documentation as a static site that can be browsed without a server
+pub struct HtmlSite<'a> {
+    modules: &'a [DocsModule],
+    /// Modules that define a function of the given name
+    definitions: HashMap<&'a str, Vec<&'a str>>,
+}
+
+impl<'a> HtmlSite<'a> {
+    pub fn new(modules: &'a [DocsModule]) -> Self {
+        let mut definitions: HashMap<&str, Vec<&str>> = HashMap::new();
+        for module in modules {
+            for function in &module.functions {
+                definitions
+                    .entry(function.name.as_str())
+                    .or_default()
+                    .push(module.id.as_str());
+            }
+        }
+        HtmlSite {
+            modules,
+            definitions,
+        }
+    }
+
+    /// Returns the files of the site with paths relative to the output directory
+    pub fn render(&self) -> Vec<(PathBuf, String)> {
+        let mut files = vec![
+            (PathBuf::from("index.html"), self.render_index()),
+            (PathBuf::from("style.css"), STYLE.to_string()),
+            (PathBuf::from("search.js"), SEARCH.to_string()),
+            (PathBuf::from("search-index.js"), self.render_search_index()),
+        ];
+        for module in self.modules {
+            files.push((
+                PathBuf::from(module_url(&module.id)),
+                self.render_module(module),
+            ));
+            for function in &module.functions {
+                files.push((
+                    PathBuf::from(function_url(&module.id, &function.name)),
+                    self.render_function(module, function),
+                ));
+            }
+        }
+        files
+    }
+
+    fn render_index(&self) -> String {
+        let items = self
+            .modules
+            .iter()
+            .map(|module| {
+                format!(
+                    "<li><a href=\"{}\">{}</a> <span class=\"count\">{}</span></li>",
+                    module_url(&module.id),
+                    escape_html(&module.id),
+                    module.functions.len()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let body = format!("<h1>Modules</h1>\n<ul class=\"modules\">\n{items}\n</ul>");
+        render_page("Documentation", "", &body)
+    }
+
+    fn render_module(&self, module: &DocsModule) -> String {
+        let root = get_root(&module.id);
+        let items = module
+            .functions
+            .iter()
+            .map(|function| {
+                format!(
+                    "<li><a href=\"{}\"><code>{}</code></a>{}</li>",
+                    function_file(&function.name),
+                    escape_html(&function.signature()),
+                    self.render_markdown(&function.summary(), &module.id, &root)
+                        .trim_end()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let body = format!(
+            "<nav><a href=\"{root}index.html\">Modules</a></nav>\n<h1>{}</h1>\n<ul class=\"functions\">\n{items}\n</ul>",
+            escape_html(&module.id)
+        );
+        render_page(&module.id, &root, &body)
+    }
+
+    fn render_function(&self, module: &DocsModule, function: &DocsFunction) -> String {
+        let root = get_root(&module.id);
+        let mut body = vec![
+            format!(
+                "<nav><a href=\"{root}index.html\">Modules</a> / <a href=\"index.html\">{}</a></nav>",
+                escape_html(&module.id)
+            ),
+            format!("<h1>{}</h1>", escape_html(&function.name)),
+            format!(
+                "<pre class=\"signature\"><code>{}</code></pre>",
+                escape_html(&function.signature())
+            ),
+        ];
+        if !function.args.is_empty() {
+            let rows = function
+                .args
+                .iter()
+                .map(|arg| {
+                    let kind = arg.kind.as_deref().unwrap_or("Any");
+                    let default = arg
+                        .default
+                        .as_deref()
+                        .map(|default| format!("<code>{}</code>", escape_html(default)))
+                        .unwrap_or_default();
+                    let name = if arg.is_ref {
+                        format!("ref {}", arg.name)
+                    } else {
+                        arg.name.clone()
+                    };
+                    format!(
+                        "<tr><td><code>{}</code></td><td><code>{}</code></td><td>{default}</td></tr>",
+                        escape_html(&name),
+                        escape_html(kind)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            body.push(format!(
+                "<table class=\"arguments\">\n<tr><th>Argument</th><th>Type</th><th>Default</th></tr>\n{rows}\n</table>"
+            ));
+        }
+        if let Some(comment) = &function.comment {
+            body.push(self.render_markdown(comment, &module.id, &root));
+        }
+        render_page(&function.name, &root, &body.join("\n"))
+    }
+
+    /// Renders Markdown and links the inline code that names a documented function.
+    /// HTML in the comments is shown as text, since it usually stands for a placeholder.
+    fn render_markdown(&self, markdown: &str, module_id: &str, root: &str) -> String {
+        let events = Parser::new(markdown).map(|event| match event {
+            Event::Code(code) => match self.get_link(&code, module_id, root) {
+                Some(url) => Event::InlineHtml(CowStr::from(format!(
+                    "<a href=\"{url}\"><code>{}</code></a>",
+                    escape_html(&code)
+                ))),
+                None => Event::Code(code),
+            },
+            Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+            event => event,
+        });
+        let mut result = String::new();
+        html::push_html(&mut result, events);
+        result
+    }
+
+    /// Finds the page of the referenced function, preferring the current module
+    fn get_link(&self, code: &str, module_id: &str, root: &str) -> Option<String> {
+        let name = code.strip_suffix("()").unwrap_or(code);
+        let modules = self.definitions.get(name)?;
+        let target = modules
+            .iter()
+            .find(|id| **id == module_id)
+            .or_else(|| modules.first())?;
+        Some(format!("{root}{}", function_url(target, name)))
+    }
+
+    fn render_search_index(&self) -> String {
+        let entries = self
+            .modules
+            .iter()
+            .flat_map(|module| {
+                module.functions.iter().map(|function| {
+                    json!({
+                        "name": function.name,
+                        "module": module.id,
+                        "signature": function.signature(),
+                        "summary": function.summary(),
+                        "url": function_url(&module.id, &function.name),
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        // Loaded with a script tag, because browsers block fetching local files
+        format!("window.searchIndex = {};\n", json!(entries))
+    }
+}
+
+fn render_page(title: &str, root: &str, body: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<link rel="stylesheet" href="{root}style.css">
+<script src="{root}search-index.js"></script>
+<script src="{root}search.js" defer></script>
+</head>
+<body data-root="{root}">
+<header><input id="search" type="search" placeholder="Search functions" autocomplete="off"></header>
+<ul id="search-results" hidden></ul>
+<main>
+{body}
+</main>
+</body>
+</html>
+"#,
+        title = escape_html(title)
+    )
+}
+
+fn module_url(id: &str) -> String {
+    format!("{id}/index.html")
+}
+
+fn function_file(name: &str) -> String {
+    format!("fn.{name}.html")
+}
+
+fn function_url(module_id: &str, name: &str) -> String {
+    format!("{module_id}/{}", function_file(name))
+}
+
+/// Relative path from the pages of a module to the root of the site
+fn get_root(module_id: &str) -> String {
+    "../".repeat(module_id.split('/').count())
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
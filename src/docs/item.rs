@@ -0,0 +1,77 @@
+/// Documented argument of a function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocsArgument {
+    pub name: String,
+    /// Type of the argument or `None` if it is generic
+    pub kind: Option<String>,
+    pub is_ref: bool,
+    /// Source code of the default value
+    pub default: Option<String>,
+}
+
+/// Documented function of a module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocsFunction {
+    pub name: String,
+    pub args: Vec<DocsArgument>,
+    /// Return type or `None` if it is inferred from the body
+    pub returns: Option<String>,
+    pub is_public: bool,
+    pub is_failable: bool,
+    /// Markdown contents of the doc comment
+    pub comment: Option<String>,
+    /// Line and column of the function name
+    pub position: Option<(usize, usize)>,
+}
+
+impl DocsFunction {
+    /// Renders the signature from the argument and return types
+    pub fn signature(&self) -> String {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| {
+                let mut result = String::new();
+                if arg.is_ref {
+                    result.push_str("ref ");
+                }
+                result.push_str(&arg.name);
+                if let Some(kind) = &arg.kind {
+                    result.push_str(&format!(": {kind}"));
+                }
+                if let Some(default) = &arg.default {
+                    result.push_str(&format!(" = {default}"));
+                }
+                result
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let visibility = if self.is_public { "pub " } else { "" };
+        let returns = match &self.returns {
+            Some(kind) if self.is_failable && !kind.ends_with('?') => format!(": {kind}?"),
+            Some(kind) => format!(": {kind}"),
+            None => String::new(),
+        };
+        format!("{visibility}fun {}({args}){returns}", self.name)
+    }
+
+    /// First paragraph of the doc comment
+    pub fn summary(&self) -> String {
+        self.comment
+            .as_deref()
+            .and_then(|comment| comment.split("\n\n").next())
+            .filter(|paragraph| !paragraph.starts_with('#') && !paragraph.starts_with("```"))
+            .map(|paragraph| paragraph.split_whitespace().collect::<Vec<_>>().join(" "))
+            .unwrap_or_default()
+    }
+}
+
+/// Documented Amber source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocsModule {
+    /// Path of the module relative to the documented file without the extension
+    pub id: String,
+    /// Absolute path of the source file or the import path of a standard library module
+    pub path: String,
+    pub functions: Vec<DocsFunction>,
+}
@@ -0,0 +1,53 @@
+use serde_json::{json, Value};
+
+use super::item::{DocsArgument, DocsFunction, DocsModule};
+
+/// Exports the public API of the modules for editor plugins and other tools
+pub fn render_json(modules: &[DocsModule]) -> String {
+    let modules = modules
+        .iter()
+        .map(|module| {
+            let functions = module
+                .functions
+                .iter()
+                .filter(|function| function.is_public)
+                .map(|function| function_to_json(module, function))
+                .collect::<Vec<_>>();
+            json!({
+                "id": module.id,
+                "path": module.path,
+                "functions": functions,
+            })
+        })
+        .collect::<Vec<_>>();
+    let result = json!({ "modules": modules });
+    serde_json::to_string_pretty(&result).expect("Documentation is always valid JSON") + "\n"
+}
+
+fn function_to_json(module: &DocsModule, function: &DocsFunction) -> Value {
+    let location = function.position.map(|(line, column)| {
+        json!({
+            "file": module.path,
+            "line": line,
+            "column": column,
+        })
+    });
+    json!({
+        "name": function.name,
+        "signature": function.signature(),
+        "args": function.args.iter().map(argument_to_json).collect::<Vec<_>>(),
+        "returns": function.returns,
+        "failable": function.is_failable,
+        "doc": function.comment,
+        "location": location,
+    })
+}
+
+fn argument_to_json(arg: &DocsArgument) -> Value {
+    json!({
+        "name": arg.name,
+        "type": arg.kind,
+        "default": arg.default,
+        "ref": arg.is_ref,
+    })
+}
@@ -1 +1,18 @@
+use clap::ValueEnum;
+
+pub mod coverage;
+pub mod html;
+pub mod item;
+pub mod json;
 pub mod module;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum DocsFormat {
+    /// One Markdown file per module
+    #[default]
+    Markdown,
+    /// Static site with an index, function pages and search
+    Html,
+    /// Public functions of every module as a single JSON document
+    Json,
+}
@@ -0,0 +1,256 @@
+use heraclitus_compiler::prelude::*;
+
+const OPENERS: [&str; 3] = ["(", "[", "{"];
+const CLOSERS: [&str; 3] = [")", "]", "}"];
+
+/// Keywords that are separated with a space from the following parenthesis or bracket
+const KEYWORDS: [&str; 26] = [
+    "if", "else", "then", "return", "fail", "and", "or", "not", "in", "is", "as", "let", "const",
+    "for", "while", "loop", "ref", "pub", "import", "from", "silent", "trust", "unsafe", "sudo",
+    "main", "defer",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AtomKind {
+    Word,
+    Symbol,
+    /// String or command literal copied as it is
+    Region,
+    Comment,
+    Flag,
+}
+
+/// Smallest formatted unit of the source code
+#[derive(Debug, Clone)]
+struct Atom {
+    text: String,
+    kind: AtomKind,
+    start_line: usize,
+    end_line: usize,
+}
+
+impl Atom {
+    fn is(&self, word: &str) -> bool {
+        matches!(self.kind, AtomKind::Symbol | AtomKind::Word) && self.text == word
+    }
+
+    fn is_opener(&self) -> bool {
+        self.kind == AtomKind::Symbol && OPENERS.contains(&self.text.as_str())
+    }
+
+    fn is_closer(&self) -> bool {
+        self.kind == AtomKind::Symbol && CLOSERS.contains(&self.text.as_str())
+    }
+
+    fn is_keyword(&self) -> bool {
+        self.kind == AtomKind::Word && KEYWORDS.contains(&self.text.as_str())
+    }
+}
+
+/// Finds the end of a string or command literal starting at the given index
+fn region_end(chars: &[char], start: usize) -> usize {
+    let delimiter = chars[start];
+    let mut index = start + 1;
+    while index < chars.len() {
+        match chars[index] {
+            '\\' => index += 2,
+            '{' => index = interpolation_end(chars, index + 1),
+            letter if letter == delimiter => return index + 1,
+            _ => index += 1,
+        }
+    }
+    chars.len()
+}
+
+/// Finds the end of an interpolation that starts right after the opening brace
+fn interpolation_end(chars: &[char], mut index: usize) -> usize {
+    let mut depth = 1;
+    while index < chars.len() {
+        match chars[index] {
+            '"' | '$' => index = region_end(chars, index),
+            '{' => {
+                depth += 1;
+                index += 1;
+            }
+            '}' => {
+                depth -= 1;
+                index += 1;
+                if depth == 0 {
+                    return index;
+                }
+            }
+            _ => index += 1,
+        }
+    }
+    chars.len()
+}
+
+/// Groups tokens into atoms, keeping string and command literals intact
+fn collect_atoms(code: &str, tokens: &[Token]) -> Vec<Atom> {
+    let chars = code.chars().collect::<Vec<char>>();
+    let line_starts = std::iter::once(0)
+        .chain(
+            chars
+                .iter()
+                .enumerate()
+                .filter(|(_, letter)| **letter == '\n')
+                .map(|(index, _)| index + 1),
+        )
+        .collect::<Vec<usize>>();
+    let line_of = |index: usize| line_starts.partition_point(|start| *start <= index);
+    let mut atoms: Vec<Atom> = vec![];
+    let mut skip_until = 0;
+    for token in tokens {
+        if token.start < skip_until || token.word.starts_with('\n') {
+            continue;
+        }
+        let (kind, text, end) = if token.word.starts_with("//") {
+            let text = token.word.trim_end().to_string();
+            let end = token.start + text.chars().count();
+            (AtomKind::Comment, text, end)
+        } else if token.word.starts_with("#[") {
+            let end = token.start + token.word.chars().count();
+            (AtomKind::Flag, token.word.clone(), end)
+        } else if token.word.starts_with(['"', '$']) {
+            let end = region_end(&chars, token.start);
+            let text = chars[token.start..end].iter().collect();
+            (AtomKind::Region, text, end)
+        } else if token
+            .word
+            .starts_with(|letter: char| letter.is_alphanumeric() || letter == '_')
+        {
+            let end = token.start + token.word.chars().count();
+            (AtomKind::Word, token.word.clone(), end)
+        } else {
+            let end = token.start + token.word.chars().count();
+            (AtomKind::Symbol, token.word.clone(), end)
+        };
+        skip_until = end;
+        atoms.push(Atom {
+            text,
+            kind,
+            start_line: line_of(token.start),
+            end_line: line_of(end.saturating_sub(1).max(token.start)),
+        });
+    }
+    atoms
+}
+
+/// Determines if the minus sign at the given position is an unary operator
+fn is_unary_minus(atoms: &[Atom], index: usize) -> bool {
+    if !atoms[index].is("-") {
+        return false;
+    }
+    match index.checked_sub(1).map(|before| &atoms[before]) {
+        None => true,
+        Some(before) if before.end_line != atoms[index].start_line => true,
+        Some(before) => match before.kind {
+            AtomKind::Symbol => !before.is_closer() && !before.is("?"),
+            AtomKind::Word => before.is_keyword(),
+            AtomKind::Flag | AtomKind::Comment => true,
+            AtomKind::Region => false,
+        },
+    }
+}
+
+/// Determines if the atoms should be separated with a space
+fn needs_space(atoms: &[Atom], index: usize) -> bool {
+    let (left, right) = (&atoms[index - 1], &atoms[index]);
+    if right.kind == AtomKind::Comment {
+        return true;
+    }
+    if left.is("(") || left.is("[") || right.is(")") || right.is("]") {
+        return false;
+    }
+    if [",", ";", ":", "?", "."]
+        .iter()
+        .any(|symbol| right.is(symbol))
+        || left.is(".")
+    {
+        return false;
+    }
+    // Ranges are written without spaces: `0..10` and `0..=10`
+    if left.is("..") || right.is("..") {
+        return false;
+    }
+    if left.is("=") && index >= 2 && atoms[index - 2].is("..") {
+        return false;
+    }
+    if is_unary_minus(atoms, index - 1) {
+        return false;
+    }
+    if right.is("(") || right.is("[") {
+        return match left.kind {
+            AtomKind::Word => left.is_keyword(),
+            AtomKind::Symbol => !left.is_closer() || !right.is("["),
+            _ => true,
+        };
+    }
+    if left.is("{") {
+        return !right.is("}");
+    }
+    true
+}
+
+/// Renders the code from the token stream with canonical indentation and spacing
+pub fn layout(code: &str, tokens: &[Token]) -> String {
+    let atoms = collect_atoms(code, tokens);
+    // Split atoms into lines of the output
+    let mut lines: Vec<(usize, usize)> = vec![];
+    for (index, atom) in atoms.iter().enumerate() {
+        match lines.last_mut() {
+            Some((_, end)) if atoms[*end - 1].end_line == atom.start_line => *end = index + 1,
+            _ => lines.push((index, index + 1)),
+        }
+    }
+    let mut result = String::new();
+    // Stack of open brackets with the line they were opened on and whether they indent
+    let mut brackets: Vec<(usize, bool)> = vec![];
+    // Number of preceding lines ending with a colon, like `if x:` followed by its statement
+    let mut statements = 0;
+    for (line_index, (begin, end)) in lines.iter().enumerate() {
+        let line = &atoms[*begin..*end];
+        let leading_closers = line.iter().take_while(|atom| atom.is_closer()).count();
+        for _ in 0..leading_closers {
+            brackets.pop();
+        }
+        // Keep at most one empty line, but not at the edges of blocks
+        if line_index > 0 {
+            let previous = &atoms[*begin - 1];
+            let is_gap = line[0].start_line > previous.end_line + 1;
+            if is_gap && !previous.is_opener() && leading_closers == 0 {
+                result.push('\n');
+            }
+        }
+        let indent = brackets.iter().filter(|(_, indents)| *indents).count() + statements;
+        result.push_str(&"    ".repeat(indent));
+        for (offset, atom) in line.iter().enumerate() {
+            let index = begin + offset;
+            if offset > 0 && needs_space(&atoms, index) {
+                result.push(' ');
+            }
+            result.push_str(&atom.text);
+            if offset < leading_closers {
+                continue;
+            }
+            if atom.is_opener() {
+                let indents = !brackets
+                    .iter()
+                    .any(|(opened, indents)| *opened == line_index && *indents);
+                brackets.push((line_index, indents));
+            } else if atom.is_closer() {
+                brackets.pop();
+            }
+        }
+        result.push('\n');
+        let last = line
+            .iter()
+            .rev()
+            .find(|atom| atom.kind != AtomKind::Comment);
+        match last {
+            Some(atom) if atom.is(":") => statements += 1,
+            _ => statements = 0,
+        }
+    }
+    result
+}
@@ -0,0 +1,156 @@
+pub mod layout;
+
+use crate::compiler::{AmberCompiler, CompilerOptions};
+use crate::testing::find_amber_files;
+use crate::utils::ParenlessCall;
+use crate::FmtCommand;
+use colored::Colorize;
+use heraclitus_compiler::prelude::*;
+use itertools::Itertools;
+use std::error::Error;
+use std::fs;
+use std::io::{stdin, Read};
+use std::path::PathBuf;
+
+/// Upper bound of rewriting passes for nested deprecated builtin calls
+const MAX_REWRITE_PASSES: usize = 16;
+
+/// Formats Amber source code.
+/// Fails if the code cannot be parsed.
+pub fn format_code(code: &str, path: Option<String>) -> Result<String, Message> {
+    let code = code.replace("\r\n", "\n").replace('\r', "\n");
+    // Treat the shebang as a comment of the same length so that positions stay intact
+    let shebang = code
+        .starts_with("#!")
+        .then(|| code.lines().next().unwrap_or_default().to_string());
+    let mut code = match &shebang {
+        Some(line) => {
+            let padding = " ".repeat(line.chars().count() - 2);
+            format!("//{padding}{}", &code[line.len()..])
+        }
+        None => code,
+    };
+    for _ in 0..MAX_REWRITE_PASSES {
+        let (tokens, calls) = parse(&code, &path)?;
+        if calls.is_empty() {
+            let result = layout::layout(&code, &tokens);
+            check_tokens(&tokens, &result, &path)?;
+            return Ok(match shebang {
+                Some(line) => {
+                    let (_, rest) = result.split_once('\n').unwrap_or_default();
+                    format!("{line}\n{rest}")
+                }
+                None => result,
+            });
+        }
+        code = rewrite_parenless_calls(&code, calls);
+    }
+    Err(Message::new_err_msg(
+        "Could not rewrite deprecated builtin calls",
+    ))
+}
+
+fn parse(code: &str, path: &Option<String>) -> Result<(Vec<Token>, Vec<ParenlessCall>), Message> {
+    let compiler = AmberCompiler::new(code.to_string(), path.clone(), CompilerOptions::default());
+    let tokens = compiler.tokenize()?;
+    let (_, meta) = compiler.parse(tokens.clone())?;
+    Ok((tokens, meta.parenless_calls))
+}
+
+/// Adds parentheses to deprecated builtin calls, like `echo x` to `echo(x)`.
+/// Nested calls are rewritten in the following passes.
+fn rewrite_parenless_calls(code: &str, calls: Vec<ParenlessCall>) -> String {
+    let chars = code.chars().collect::<Vec<char>>();
+    let text = |(start, end): (usize, usize)| chars[start..end].iter().collect::<String>();
+    let mut result = String::new();
+    let mut index = 0;
+    for call in calls.into_iter().sorted_by_key(|call| call.name.0) {
+        let end = call.args.last().map_or(call.name.1, |arg| arg.1);
+        if call.name.0 < index || end > chars.len() {
+            continue;
+        }
+        let args = call.args.iter().map(|arg| text(*arg)).join(", ");
+        result.push_str(&text((index, call.name.0)));
+        result.push_str(&format!("{}({args})", text(call.name)));
+        index = end;
+    }
+    result.push_str(&text((index, chars.len())));
+    result
+}
+
+/// Makes sure that formatting did not change the meaning of the code
+fn check_tokens(tokens: &[Token], result: &str, path: &Option<String>) -> Result<(), Message> {
+    let compiler = AmberCompiler::new(result.to_string(), path.clone(), CompilerOptions::default());
+    let words = |tokens: &[Token]| {
+        tokens
+            .iter()
+            .filter(|token| !token.word.starts_with('\n'))
+            .map(|token| token.word.trim_end().to_string())
+            .collect::<Vec<String>>()
+    };
+    if words(tokens) == words(&compiler.tokenize()?) {
+        Ok(())
+    } else {
+        let path = path.clone().unwrap_or(String::from("unknown"));
+        let message = format!("Formatting would change the meaning of the code in '{path}'");
+        Err(Message::new_err_msg(message).comment("Please report this issue."))
+    }
+}
+
+pub fn handle_fmt(command: FmtCommand) -> Result<i32, Box<dyn Error>> {
+    if command.inputs.iter().any(|input| input.as_os_str() == "-") {
+        let mut code = String::new();
+        stdin().read_to_string(&mut code)?;
+        return match format_code(&code, None) {
+            Ok(formatted) if command.check => Ok((formatted != code) as i32),
+            Ok(formatted) => {
+                print!("{formatted}");
+                Ok(0)
+            }
+            Err(err) => {
+                err.show();
+                Ok(1)
+            }
+        };
+    }
+    let mut files = vec![];
+    for input in command.inputs.iter() {
+        if input.is_dir() {
+            find_amber_files(input, &mut files)?;
+        } else {
+            files.push(input.clone());
+        }
+    }
+    files.sort();
+    let mut changed: Vec<PathBuf> = vec![];
+    let mut failed = false;
+    for file in files {
+        let path = file.to_string_lossy().to_string();
+        let code = match fs::read_to_string(&file) {
+            Ok(code) => code,
+            Err(err) => {
+                Message::new_err_msg(format!("Failed to read file {path}: {err}")).show();
+                failed = true;
+                continue;
+            }
+        };
+        match format_code(&code, Some(path.clone())) {
+            Ok(formatted) if formatted == code => {}
+            Ok(formatted) => {
+                if command.check {
+                    println!("{} {path}", "Unformatted".yellow().bold());
+                } else {
+                    fs::write(&file, formatted)?;
+                    println!("{} {path}", "Formatted".green().bold());
+                }
+                changed.push(file);
+            }
+            Err(err) => {
+                err.show();
+                failed = true;
+            }
+        }
+    }
+    let unformatted = command.check && !changed.is_empty();
+    Ok((failed || unformatted) as i32)
+}
@@ -0,0 +1,78 @@
+use crate::compiler::{AmberCompiler, CompilerOptions};
+use crate::stdlib;
+use crate::utils::symbol_table::{SymbolDefinition, SymbolTable};
+use heraclitus_compiler::prelude::*;
+
+/// Result of analyzing a single document
+pub struct Analysis {
+    /// Errors, warnings and infos reported by the compiler
+    pub messages: Vec<Message>,
+    /// Symbols of the document (missing if the document did not compile)
+    pub symbols: Option<SymbolTable>,
+}
+
+/// Runs the frontend of the compiler on the document without translating it
+pub fn analyze(code: &str, path: &str) -> Analysis {
+    let compiler = AmberCompiler::new(
+        code.to_string(),
+        Some(path.to_string()),
+        CompilerOptions::default(),
+    );
+    let result = compiler
+        .tokenize()
+        .and_then(|tokens| compiler.parse(tokens))
+        .and_then(|(block, meta)| compiler.typecheck(block, meta));
+    match result {
+        Ok((_, meta)) => Analysis {
+            messages: meta.messages,
+            symbols: Some(meta.symbol_table),
+        },
+        Err(message) => Analysis {
+            messages: vec![message],
+            symbols: None,
+        },
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Public function exported by a standard library module
+pub struct StdlibSymbol {
+    /// Import path of the module, like `std/text`
+    pub module: String,
+    pub definition: SymbolDefinition,
+}
+
+/// Collects public functions of all standard library modules
+pub fn stdlib_symbols() -> Vec<StdlibSymbol> {
+    let mut symbols = vec![];
+    for file in stdlib::STDLIB.files() {
+        let Some(name) = file.path().file_stem() else {
+            continue;
+        };
+        let module = format!("std/{}", name.to_string_lossy());
+        let Some(code) = file.contents_utf8() else {
+            continue;
+        };
+        let compiler = AmberCompiler::new(
+            code.to_string(),
+            Some(module.clone()),
+            CompilerOptions::default(),
+        );
+        let Ok((_, meta)) = compiler
+            .tokenize()
+            .and_then(|tokens| compiler.parse(tokens))
+            .and_then(|(block, meta)| compiler.typecheck(block, meta))
+        else {
+            continue;
+        };
+        for fun in meta.context.pub_funs.iter() {
+            let definition = meta.symbol_table.resolve_function(fun);
+            symbols.push(StdlibSymbol {
+                module: module.clone(),
+                definition,
+            });
+        }
+    }
+    symbols.sort_by(|a, b| a.definition.name.cmp(&b.definition.name));
+    symbols
+}
@@ -0,0 +1,340 @@
+pub mod analysis;
+pub mod protocol;
+
+use crate::utils::diagnostic::span;
+use crate::utils::symbol_table::{SymbolDefinition, SymbolKind, SymbolTable};
+use analysis::{analyze, stdlib_symbols, StdlibSymbol};
+use heraclitus_compiler::prelude::*;
+use protocol::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+/// Document opened in the editor
+struct Document {
+    text: String,
+    /// Symbols of the last version of the document that compiled
+    symbols: SymbolTable,
+}
+
+/// Language server speaking the Language Server Protocol over JSON-RPC
+pub struct LanguageServer {
+    documents: HashMap<String, Document>,
+    stdlib: Option<Vec<StdlibSymbol>>,
+    is_shutdown: bool,
+    is_exit: bool,
+}
+
+impl Default for LanguageServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageServer {
+    pub fn new() -> Self {
+        LanguageServer {
+            documents: HashMap::new(),
+            stdlib: None,
+            is_shutdown: false,
+            is_exit: false,
+        }
+    }
+
+    /// Returns the exit code once the client requested the server to exit
+    pub fn exit_code(&self) -> Option<i32> {
+        self.is_exit.then_some(if self.is_shutdown { 0 } else { 1 })
+    }
+
+    /// Handles a single incoming message and returns messages that should be sent back
+    pub fn handle(&mut self, message: Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or_default().to_string();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        match message.get("id").cloned() {
+            // Requests
+            Some(id) => {
+                if self.is_shutdown {
+                    return vec![error_response(id, INVALID_REQUEST, "Server is shut down")];
+                }
+                let result = match method.as_str() {
+                    "initialize" => Some(Self::initialize()),
+                    "shutdown" => {
+                        self.is_shutdown = true;
+                        Some(Value::Null)
+                    }
+                    "textDocument/hover" => Some(self.hover(&params)),
+                    "textDocument/definition" => Some(self.definition(&params)),
+                    "textDocument/completion" => Some(self.completion(&params)),
+                    _ => None,
+                };
+                match result {
+                    Some(result) => vec![response(id, result)],
+                    None => {
+                        let message = format!("Method not found: {method}");
+                        vec![error_response(id, METHOD_NOT_FOUND, &message)]
+                    }
+                }
+            }
+            // Notifications
+            None => match method.as_str() {
+                "exit" => {
+                    self.is_exit = true;
+                    vec![]
+                }
+                "textDocument/didOpen" => {
+                    let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                    let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                    self.update_document(uri, text.to_string())
+                }
+                "textDocument/didChange" => {
+                    let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                    // The server requests full document synchronization
+                    let text = params["contentChanges"]
+                        .as_array()
+                        .and_then(|changes| changes.last())
+                        .and_then(|change| change["text"].as_str());
+                    match text {
+                        Some(text) => self.update_document(uri, text.to_string()),
+                        None => vec![],
+                    }
+                }
+                "textDocument/didSave" => {
+                    let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                    match params["text"].as_str() {
+                        Some(text) => self.update_document(uri, text.to_string()),
+                        None => match self.documents.get(uri) {
+                            Some(document) => self.update_document(uri, document.text.clone()),
+                            None => vec![],
+                        },
+                    }
+                }
+                "textDocument/didClose" => {
+                    let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                    self.documents.remove(uri);
+                    vec![publish_diagnostics(uri, vec![])]
+                }
+                _ => vec![],
+            },
+        }
+    }
+
+    fn initialize() -> Value {
+        json!({
+            "capabilities": {
+                "textDocumentSync": {
+                    "openClose": true,
+                    "change": 1,
+                    "save": { "includeText": true }
+                },
+                "hoverProvider": true,
+                "definitionProvider": true,
+                "completionProvider": {}
+            },
+            "serverInfo": {
+                "name": "amber",
+                "version": crate::get_version()
+            }
+        })
+    }
+
+    /// Recompiles the document and publishes its diagnostics
+    fn update_document(&mut self, uri: &str, text: String) -> Vec<Value> {
+        let path = uri_to_path(uri);
+        let analysis = analyze(&text, &path);
+        let diagnostics = analysis
+            .messages
+            .iter()
+            .map(|message| to_diagnostic(message, &path, &text))
+            .collect();
+        let symbols = match (analysis.symbols, self.documents.remove(uri)) {
+            (Some(symbols), _) => symbols,
+            (None, Some(document)) => document.symbols,
+            (None, None) => SymbolTable::new(),
+        };
+        self.documents
+            .insert(uri.to_string(), Document { text, symbols });
+        vec![publish_diagnostics(uri, diagnostics)]
+    }
+
+    /// Finds the symbol under the cursor of a text document position request
+    fn symbol_at(&self, params: &Value) -> Option<&SymbolDefinition> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let document = self.documents.get(uri)?;
+        let line = params["position"]["line"].as_u64()? as usize;
+        let character = params["position"]["character"].as_u64()? as usize;
+        document
+            .symbols
+            .find_at(&uri_to_path(uri), line + 1, character + 1)
+    }
+
+    fn hover(&self, params: &Value) -> Value {
+        let Some(symbol) = self.symbol_at(params) else {
+            return Value::Null;
+        };
+        let mut contents = format!("```ab\n{}\n```", symbol.detail);
+        if let Some(doc) = symbol.doc.as_ref().filter(|doc| !doc.is_empty()) {
+            contents.push_str("\n\n");
+            contents.push_str(doc);
+        }
+        json!({ "contents": { "kind": "markdown", "value": contents } })
+    }
+
+    fn definition(&self, params: &Value) -> Value {
+        let Some(position) = self
+            .symbol_at(params)
+            .and_then(|symbol| symbol.position.as_ref())
+        else {
+            return Value::Null;
+        };
+        // Standard library modules do not exist on disk
+        let Some(path) = position
+            .path
+            .as_ref()
+            .filter(|path| Path::new(path).exists())
+        else {
+            return Value::Null;
+        };
+        let uri = path_to_uri(&absolute_path(path));
+        let text = self
+            .documents
+            .get(&uri)
+            .map(|document| document.text.clone())
+            .or_else(|| std::fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        json!({ "uri": uri, "range": to_range(position, &text) })
+    }
+
+    fn completion(&mut self, params: &Value) -> Value {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let path = uri_to_path(uri);
+        let mut items = vec![];
+        if let Some(document) = self.documents.get(uri) {
+            let mut seen = vec![];
+            for definition in document.symbols.definitions.iter() {
+                let is_local = definition
+                    .position
+                    .as_ref()
+                    .is_some_and(|position| position.path.as_deref() == Some(path.as_str()));
+                if is_local && !seen.contains(&definition.name) {
+                    seen.push(definition.name.clone());
+                    items.push(completion_item(definition, None));
+                }
+            }
+        }
+        let stdlib = self.stdlib.get_or_insert_with(stdlib_symbols);
+        for symbol in stdlib.iter() {
+            let import = format!(
+                "import {{ {} }} from \"{}\"\n",
+                symbol.definition.name, symbol.module
+            );
+            items.push(completion_item(&symbol.definition, Some(import)));
+        }
+        json!({ "isIncomplete": false, "items": items })
+    }
+}
+
+fn completion_item(definition: &SymbolDefinition, import: Option<String>) -> Value {
+    let kind = match definition.kind {
+        SymbolKind::Function => 3,
+        SymbolKind::Variable => 6,
+        SymbolKind::Constant => 21,
+    };
+    let mut item = json!({
+        "label": definition.name,
+        "kind": kind,
+        "detail": definition.detail,
+    });
+    if let Some(doc) = definition.doc.as_ref().filter(|doc| !doc.is_empty()) {
+        item["documentation"] = json!({ "kind": "markdown", "value": doc });
+    }
+    if let Some(import) = import {
+        item["additionalTextEdits"] = json!([{
+            "range": {
+                "start": { "line": 0, "character": 0 },
+                "end": { "line": 0, "character": 0 }
+            },
+            "newText": import
+        }]);
+    }
+    item
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    notification(
+        "textDocument/publishDiagnostics",
+        json!({ "uri": uri, "diagnostics": diagnostics }),
+    )
+}
+
+fn absolute_path(path: &str) -> String {
+    std::path::absolute(path)
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+/// Converts a compiler message into a diagnostic of the given document.
+/// Messages that originate in imported files are reported at the import statement.
+pub fn to_diagnostic(message: &Message, path: &str, text: &str) -> Value {
+    let severity = match message.kind {
+        MessageType::Error => 1,
+        MessageType::Warning => 2,
+        MessageType::Info => 3,
+    };
+    let mut content = message.message.clone().unwrap_or_default();
+    if let Some(comment) = &message.comment {
+        content.push('\n');
+        content.push_str(comment);
+    }
+    let position = message
+        .trace
+        .iter()
+        .find(|position| position.path.as_deref() == Some(path));
+    let range = match position {
+        Some(position) => to_range(position, text),
+        None => json!({
+            "start": { "line": 0, "character": 0 },
+            "end": { "line": 0, "character": 0 }
+        }),
+    };
+    json!({
+        "range": range,
+        "severity": severity,
+        "source": "amber",
+        "message": content
+    })
+}
+
+/// Converts a compiler position into a zero based range in the text
+fn to_range(position: &PositionInfo, text: &str) -> Value {
+    let ((line, column), (end_line, end_column)) = span(position, Some(text));
+    json!({
+        "start": { "line": line.saturating_sub(1), "character": column.saturating_sub(1) },
+        "end": { "line": end_line.saturating_sub(1), "character": end_column.saturating_sub(1) }
+    })
+}
+
+/// Runs the language server on standard input and output until the client exits
+pub fn run_server() -> io::Result<i32> {
+    let mut server = LanguageServer::new();
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let mut writer = io::stdout().lock();
+    serve(&mut server, &mut reader, &mut writer)
+}
+
+pub fn serve(
+    server: &mut LanguageServer,
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+) -> io::Result<i32> {
+    while let Some(message) = read_message(reader)? {
+        for response in server.handle(message) {
+            write_message(writer, &response)?;
+        }
+        if let Some(code) = server.exit_code() {
+            return Ok(code);
+        }
+    }
+    Ok(1)
+}
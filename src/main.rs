@@ -1,11 +1,15 @@
 mod compiler;
 mod docs;
+mod formatter;
+mod lsp;
 mod modules;
 mod optimizer;
+mod repl;
 mod rules;
 mod stdlib;
 mod translate;
 mod utils;
+mod watch;
 
 pub use crate::utils::grammar_ebnf;
 mod testing;
@@ -17,7 +21,12 @@ pub mod built_info {
 #[cfg(test)]
 pub mod tests;
 
+use crate::compiler::source_map::{SourceMap, SourceMapMode};
 use crate::compiler::{AmberCompiler, CompilerOptions};
+use crate::docs::coverage::{get_total_coverage, render_coverage};
+use crate::docs::DocsFormat;
+use crate::testing::reporter::TestReporter;
+use crate::utils::diagnostic::MessageFormat;
 use crate::utils::ShellType;
 use clap::{Args, CommandFactory, Parser, Subcommand};
 use clap_complete::Shell;
@@ -47,7 +56,7 @@ struct Cli {
     args: Vec<String>,
 
     /// Disable a postprocessor
-    /// Available postprocessors: 'bshchk'
+    /// Available postprocessors: 'bshchk' and the ones declared in 'amber.toml'
     /// To select multiple, pass multiple times with different values
     /// Argument also supports a wildcard match, like "*" or "b*chk"
     #[arg(long, verbatim_doc_comment)]
@@ -62,6 +71,8 @@ struct Cli {
 enum CommandKind {
     /// Execute Amber code fragment
     Eval(EvalCommand),
+    /// Start an interactive Amber session
+    Repl(ReplCommand),
     /// Execute Amber script
     Run(RunCommand),
     /// Check Amber script for errors
@@ -74,8 +85,12 @@ enum CommandKind {
     Completion,
     /// Run Amber tests
     Test(TestCommand),
+    /// Format Amber source files
+    Fmt(FmtCommand),
     /// Generate EBNF grammar
     GrammarEbnf,
+    /// Start the Amber language server on standard input and output
+    Lsp,
 }
 
 #[derive(Args, Clone, Debug)]
@@ -88,6 +103,13 @@ struct EvalCommand {
     target: Option<ShellType>,
 }
 
+#[derive(Args, Clone, Debug)]
+pub struct ReplCommand {
+    /// Code generation target shell
+    #[arg(long)]
+    pub target: Option<ShellType>,
+}
+
 #[derive(Args, Clone, Debug)]
 struct RunCommand {
     /// Input filename ('-' to read from stdin)
@@ -98,15 +120,32 @@ struct RunCommand {
     args: Vec<String>,
 
     /// Disable a postprocessor
-    /// Available postprocessors: 'bshchk'
+    /// Available postprocessors: 'bshchk' and the ones declared in 'amber.toml'
     /// To select multiple, pass multiple times with different values
     /// Argument also supports a wildcard match, like "*" or "b*chk"
     #[arg(long, verbatim_doc_comment)]
     no_proc: Vec<String>,
 
+    /// Run the postprocessors declared in the 'amber.toml' next to the input file
+    /// or in the working directory
+    #[arg(long, verbatim_doc_comment)]
+    project_config: bool,
+
     /// Code generation target shell
     #[arg(long)]
     target: Option<ShellType>,
+
+    /// Format of the compiler messages
+    #[arg(long, value_enum, default_value_t)]
+    message_format: MessageFormat,
+
+    /// Recompile and run again whenever the input file or any of its imports changes
+    #[arg(long)]
+    watch: bool,
+
+    /// Print the Amber functions and positions an uncaught failure propagated through
+    #[arg(long)]
+    stack_trace: bool,
 }
 
 #[derive(Args, Clone, Debug)]
@@ -115,15 +154,28 @@ struct CheckCommand {
     input: PathBuf,
 
     /// Disable a postprocessor
-    /// Available postprocessors: 'bshchk'
+    /// Available postprocessors: 'bshchk' and the ones declared in 'amber.toml'
     /// To select multiple, pass multiple times with different values
     /// Argument also supports a wildcard match, like "*" or "b*chk"
     #[arg(long, verbatim_doc_comment)]
     no_proc: Vec<String>,
 
+    /// Run the postprocessors declared in the 'amber.toml' next to the input file
+    /// or in the working directory
+    #[arg(long, verbatim_doc_comment)]
+    project_config: bool,
+
     /// Code generation target shell
     #[arg(long)]
     target: Option<ShellType>,
+
+    /// Format of the compiler messages
+    #[arg(long, value_enum, default_value_t)]
+    message_format: MessageFormat,
+
+    /// Warn about public functions and constants without a doc comment
+    #[arg(long)]
+    warn_missing_docs: bool,
 }
 
 #[derive(Args, Clone, Debug)]
@@ -135,12 +187,17 @@ struct BuildCommand {
     output: Option<PathBuf>,
 
     /// Disable a postprocessor
-    /// Available postprocessors: 'bshchk'
+    /// Available postprocessors: 'bshchk' and the ones declared in 'amber.toml'
     /// To select multiple, pass multiple times with different values
     /// Argument also supports a wildcard match, like "*" or "b*chk"
     #[arg(long, verbatim_doc_comment)]
     no_proc: Vec<String>,
 
+    /// Run the postprocessors declared in the 'amber.toml' next to the input file
+    /// or in the working directory
+    #[arg(long, verbatim_doc_comment)]
+    project_config: bool,
+
     /// Minify the output file
     #[arg(long)]
     minify: bool,
@@ -148,6 +205,34 @@ struct BuildCommand {
     /// Code generation target shell
     #[arg(long)]
     target: Option<ShellType>,
+
+    /// Format of the compiler messages
+    #[arg(long, value_enum, default_value_t)]
+    message_format: MessageFormat,
+
+    /// Recompile whenever the input file or any of its imports changes
+    #[arg(long)]
+    watch: bool,
+
+    /// Record which Amber file, line and column produced each generated line
+    /// Writes '<output>.map' by default, 'inline' keeps the markers in the script
+    #[arg(
+        long,
+        value_enum,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "file",
+        verbatim_doc_comment
+    )]
+    source_map: Option<SourceMapMode>,
+
+    /// Print the Amber functions and positions an uncaught failure propagated through
+    #[arg(long)]
+    stack_trace: bool,
+
+    /// Warn about public functions and constants without a doc comment
+    #[arg(long)]
+    warn_missing_docs: bool,
 }
 
 #[derive(Args, Clone, Debug)]
@@ -161,6 +246,19 @@ struct DocsCommand {
     /// Show standard library usage in documentation
     #[arg(long)]
     usage: bool,
+
+    /// Format of the generated documentation
+    #[arg(long, value_enum, default_value_t)]
+    format: DocsFormat,
+
+    /// Print the share of documented public functions and constants of every module
+    /// instead of generating the documentation
+    #[arg(long, verbatim_doc_comment)]
+    coverage: bool,
+
+    /// Exit with an error when the total coverage is below the given percentage
+    #[arg(long, value_name = "PERCENT", requires = "coverage")]
+    fail_under: Option<f64>,
 }
 
 #[derive(Args, Clone, Debug)]
@@ -178,15 +276,47 @@ pub struct TestCommand {
     pub args: Vec<String>,
 
     /// Disable a postprocessor
-    /// Available postprocessors: 'bshchk'
+    /// Available postprocessors: 'bshchk' and the ones declared in 'amber.toml'
     /// To select multiple, pass multiple times with different values
     /// Argument also supports a wildcard match, like "*" or "b*chk"
     #[arg(long, verbatim_doc_comment)]
     pub no_proc: Vec<String>,
 
+    /// Run the postprocessors declared in the 'amber.toml' next to the input file
+    /// or in the working directory
+    #[arg(long, verbatim_doc_comment)]
+    pub project_config: bool,
+
     /// Code generation target shell
     #[arg(long)]
     pub target: Option<ShellType>,
+
+    /// Run the tests again whenever the tested files or any of their imports change
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Run the Amber code blocks from the doc comments of public functions instead of the tests
+    #[arg(long)]
+    pub doc: bool,
+
+    /// Format of the test results
+    #[arg(long, value_enum, default_value_t)]
+    pub reporter: TestReporter,
+
+    /// Write the report to a file instead of the standard output
+    #[arg(long, requires = "reporter")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct FmtCommand {
+    /// Input filenames or directories ('-' to read from stdin)
+    #[arg(default_value = ".")]
+    pub inputs: Vec<PathBuf>,
+
+    /// Check if the files are formatted instead of rewriting them
+    #[arg(long)]
+    pub check: bool,
 }
 
 fn create_output(command: &BuildCommand) -> PathBuf {
@@ -210,35 +340,45 @@ pub(crate) fn set_file_permission(file: &fs::File, path: String) {
     file.set_permissions(perm).unwrap();
 }
 
-fn compile_input(input: PathBuf, options: CompilerOptions) -> (String, bool) {
+fn compile_input(
+    input: PathBuf,
+    options: CompilerOptions,
+    format: MessageFormat,
+) -> (String, bool) {
+    try_compile_input(input, options, format).unwrap_or_else(|| std::process::exit(1))
+}
+
+/// Compiles the input and shows the compiler messages.
+/// Returns `None` if the input could not be read or compiled.
+fn try_compile_input(
+    input: PathBuf,
+    options: CompilerOptions,
+    format: MessageFormat,
+) -> Option<(String, bool)> {
     let input = input.to_string_lossy().to_string();
     let amber_code = if input == "-" {
         let mut code = String::new();
-        match stdin().read_to_string(&mut code) {
-            Ok(_) => code,
-            Err(err) => handle_err(err),
-        }
+        stdin().read_to_string(&mut code).map(|_| code)
     } else {
-        match fs::read_to_string(&input) {
-            Ok(code) => code,
-            Err(err) => handle_err(err),
+        fs::read_to_string(&input)
+    };
+    let amber_code = match amber_code {
+        Ok(code) => code,
+        Err(err) => {
+            format.show(&Message::new_err_msg(err.to_string()));
+            return None;
         }
     };
     let compiler = AmberCompiler::new(amber_code, Some(input), options);
     let (messages, bash_code) = match compiler.compile() {
         Ok(result) => result,
         Err(err) => {
-            err.show();
-            std::process::exit(1);
+            format.show(&err);
+            return None;
         }
     };
-    messages.iter().for_each(|m| m.show());
-    (bash_code, !messages.is_empty())
-}
-
-fn handle_err(err: std::io::Error) -> ! {
-    Message::new_err_msg(err.to_string()).show();
-    std::process::exit(1);
+    messages.iter().for_each(|m| format.show(m));
+    Some((bash_code, !messages.is_empty()))
 }
 
 #[inline]
@@ -289,6 +429,32 @@ pub(crate) fn write_output(output: PathBuf, code: String) {
     }
 }
 
+/// Writes the compiled script and moves its source markers to a map file if requested.
+fn write_build_output(output: PathBuf, code: String, source_map: Option<SourceMapMode>) {
+    if source_map != Some(SourceMapMode::File) {
+        write_output(output, code);
+        return;
+    }
+    if output.as_os_str() == "-" {
+        let message = "Source map file cannot be written when the output is stdout";
+        Message::new_err_msg(message)
+            .comment("Use '--source-map=inline' to keep the source markers in the output")
+            .show();
+        std::process::exit(1);
+    }
+    let (code, source_map) = SourceMap::extract(&code);
+    let file = output
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let map_output = PathBuf::from(format!("{}.map", output.display()));
+    if let Err(err) = fs::write(&map_output, source_map.to_json(&file)) {
+        Message::new_err_msg(err.to_string()).show();
+        std::process::exit(1);
+    }
+    write_output(output, code);
+}
+
 #[cfg(test)]
 fn handle_eval(command: EvalCommand) -> Result<i32, Box<dyn Error>> {
     handle_eval_with_target(command, None)
@@ -328,10 +494,30 @@ fn handle_docs(command: DocsCommand) -> Result<(), Box<dyn Error>> {
     };
     let options = CompilerOptions::default().with_env_vars();
     let compiler = AmberCompiler::new(code, Some(input), options);
+    if command.coverage {
+        match compiler.generate_docs_coverage() {
+            Ok(modules) => {
+                print!("{}", render_coverage(&modules));
+                let coverage = get_total_coverage(&modules);
+                if let Some(min) = command.fail_under.filter(|min| coverage < *min) {
+                    Message::new_err_msg(format!(
+                        "Documentation coverage {coverage:.1}% is below the required {min}%"
+                    ))
+                    .show();
+                    std::process::exit(1);
+                }
+            }
+            Err(err) => {
+                err.show();
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
     let output = command.output.unwrap_or_else(|| PathBuf::from("docs"));
     let output = output.to_string_lossy().to_string();
     let output = if output != "-" { Some(output) } else { None };
-    match compiler.generate_docs(output, command.usage) {
+    match compiler.generate_docs(output, command.usage, command.format) {
         Ok(_) => Ok(()),
         Err(err) => {
             err.show();
@@ -388,7 +574,7 @@ fn handle_bad_command_name(
     let options = CompilerOptions::from_args(no_proc, false, false, None)
         .with_target(target)
         .with_env_vars();
-    let (code, messages) = compile_input(input.to_path_buf(), options);
+    let (code, messages) = compile_input(input.to_path_buf(), options, MessageFormat::Human);
     execute_output(code, args, messages, target)
 }
 
@@ -415,30 +601,83 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let exit_code = match command {
         CommandKind::Eval(command) => handle_eval_with_target(command, cli.target)?,
+        CommandKind::Repl(mut command) => {
+            command.target = resolve_command_target(command.target, cli.target);
+            repl::handle_repl(command)?
+        }
+        CommandKind::Run(command) if command.watch => {
+            let target = resolve_command_target(command.target, cli.target);
+            let format = command.message_format;
+            watch::watch_input(&command.input, || {
+                let options = CompilerOptions::from_args(&command.no_proc, false, false, None)
+                    .with_target(target)
+                    .with_stack_trace(command.stack_trace)
+                    .with_project_config(command.project_config)
+                    .with_env_vars();
+                let Some((code, messages)) =
+                    try_compile_input(command.input.clone(), options, format)
+                else {
+                    return;
+                };
+                let separate = messages && format == MessageFormat::Human;
+                if let Err(err) = execute_output(code, command.args.clone(), separate, target) {
+                    format.show(&Message::new_err_msg(err.to_string()));
+                }
+            })?
+        }
         CommandKind::Run(command) => {
             let target = resolve_command_target(command.target, cli.target);
             let options = CompilerOptions::from_args(&command.no_proc, false, false, None)
                 .with_target(target)
+                .with_stack_trace(command.stack_trace)
+                .with_project_config(command.project_config)
                 .with_env_vars();
-            let (code, messages) = compile_input(command.input, options);
-            execute_output(code, command.args, messages, target)?
+            let format = command.message_format;
+            let (code, messages) = compile_input(command.input, options, format);
+            // Separator between messages and the output only makes sense for humans
+            let separate = messages && format == MessageFormat::Human;
+            execute_output(code, command.args, separate, target)?
         }
         CommandKind::Check(command) => {
             let target = resolve_command_target(command.target, cli.target);
             let options = CompilerOptions::from_args(&command.no_proc, false, false, None)
                 .with_target(target)
+                .with_warn_missing_docs(command.warn_missing_docs)
+                .with_project_config(command.project_config)
                 .with_env_vars();
-            compile_input(command.input, options);
+            compile_input(command.input, options, command.message_format);
             0
         }
+        CommandKind::Build(command) if command.watch => {
+            let target = resolve_command_target(command.target, cli.target);
+            let output = create_output(&command);
+            watch::watch_input(&command.input, || {
+                let options =
+                    CompilerOptions::from_args(&command.no_proc, command.minify, false, None)
+                        .with_target(target)
+                        .with_source_map(command.source_map.is_some())
+                        .with_stack_trace(command.stack_trace)
+                        .with_warn_missing_docs(command.warn_missing_docs)
+                        .with_project_config(command.project_config)
+                        .with_env_vars();
+                let format = command.message_format;
+                if let Some((code, _)) = try_compile_input(command.input.clone(), options, format) {
+                    write_build_output(output.clone(), code, command.source_map);
+                }
+            })?
+        }
         CommandKind::Build(command) => {
             let target = resolve_command_target(command.target, cli.target);
             let output = create_output(&command);
             let options = CompilerOptions::from_args(&command.no_proc, command.minify, false, None)
                 .with_target(target)
+                .with_source_map(command.source_map.is_some())
+                .with_stack_trace(command.stack_trace)
+                .with_warn_missing_docs(command.warn_missing_docs)
+                .with_project_config(command.project_config)
                 .with_env_vars();
-            let (code, _) = compile_input(command.input, options);
-            write_output(output, code);
+            let (code, _) = compile_input(command.input, options, command.message_format);
+            write_build_output(output, code, command.source_map);
             0
         }
         CommandKind::Docs(command) => {
@@ -455,6 +694,16 @@ fn main() -> Result<(), Box<dyn Error>> {
             std::fs::write(&output_path, output)?;
             0
         }
+        CommandKind::Fmt(command) => formatter::handle_fmt(command)?,
+        CommandKind::Lsp => lsp::run_server()?,
+        CommandKind::Test(mut command) if command.watch => {
+            command.target = resolve_command_target(command.target, cli.target);
+            watch::watch_input(&command.input.clone(), || {
+                if let Err(err) = testing::handle_test(command.clone()) {
+                    Message::new_err_msg(err.to_string()).show();
+                }
+            })?
+        }
         CommandKind::Test(mut command) => {
             command.target = resolve_command_target(command.target, cli.target);
             testing::handle_test(command)?
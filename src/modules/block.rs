@@ -2,6 +2,8 @@ use std::collections::VecDeque;
 use std::ops::Index;
 
 use super::statement::stmt::Statement;
+use crate::compiler::source_map::source_marker;
+use crate::docs::item::DocsFunction;
 use crate::modules::prelude::*;
 use heraclitus_compiler::prelude::*;
 use itertools::Itertools;
@@ -112,6 +114,11 @@ impl TranslateModule for Block {
         let result = {
             let mut statements = vec![];
             for statement in &self.statements {
+                if meta.source_map {
+                    if let Some(position) = &statement.position {
+                        statements.push(source_marker(position));
+                    }
+                }
                 let statement = statement.translate(meta);
                 statements.extend(meta.stmt_queue.drain(..));
                 statements.push(statement);
@@ -127,6 +134,26 @@ impl TranslateModule for Block {
     }
 }
 
+impl Block {
+    /// Documentation of the functions declared in the block, sorted by name
+    pub fn get_docs_functions(&self, meta: &ParserMetadata) -> Vec<DocsFunction> {
+        self.statements
+            .iter()
+            .filter_map(Statement::get_docs_function)
+            .sorted_by(|a, b| a.name.cmp(&b.name))
+            .map(|function| function.get_docs_item(meta))
+            .collect()
+    }
+
+    /// Public functions and constants in the declaration order and whether they are documented
+    pub fn get_public_items(&self) -> Vec<(String, bool)> {
+        self.statements
+            .iter()
+            .filter_map(Statement::get_public_item)
+            .collect()
+    }
+}
+
 impl DocumentationModule for Block {
     fn document(&self, meta: &ParserMetadata) -> String {
         let indices = self
@@ -4,6 +4,7 @@ use crate::modules::expression::expr::Expr;
 
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
+use crate::translate::compare::translate_truthy;
 use crate::{fragments, raw_fragment};
 use amber_meta::AutoKeyword;
 use heraclitus_compiler::prelude::*;
@@ -126,9 +127,8 @@ impl TranslateModule for Cp {
                 .with_global_id(force_id);
             let force_expr = meta.push_ephemeral_variable(force_var_stmt);
             meta.stmt_queue.extend([fragments!(
-                "(( ",
-                force_translate,
-                " )) && ",
+                translate_truthy(meta, force_translate),
+                " && ",
                 raw_fragment!(
                     "{}=\"-f\" || {}=\"\"",
                     force_expr.get_name(),
@@ -71,7 +71,10 @@ impl TranslateModule for Disown {
         let pids_var = meta.push_ephemeral_variable(pids_var_stmt.clone());
         let pids_var_name = pids_var.get_name();
 
-        let iter_value = if pids_type.is_array() {
+        let iter_value = if pids_type.is_array() && meta.target.shell.is_posix() {
+            // Process ids never contain spaces, so they can be split on the joined array
+            format!("$(__amber_join \"${{{pids_var_name}}}\")")
+        } else if pids_type.is_array() {
             format!("\"${{{pids_var_name}[@]}}\"")
         } else {
             format!("\"${{{pids_var_name}}}\"")
@@ -10,6 +10,15 @@ pub struct Echo {
     value: Box<Expr>,
 }
 
+impl Echo {
+    /// Creates an echo of an already parsed expression
+    pub fn from_expr(value: Expr) -> Self {
+        Echo {
+            value: Box::new(value),
+        }
+    }
+}
+
 impl SyntaxModule<ParserMetadata> for Echo {
     syntax_name!("Log");
 
@@ -31,7 +40,9 @@ impl SyntaxModule<ParserMetadata> for Echo {
             let warning = Message::new_warn_at_token(meta, tok)
                 .message("Calling a builtin without parentheses is deprecated");
             meta.add_message(warning);
+            let begin = meta.get_index();
             syntax(meta, &mut *self.value)?;
+            meta.add_parenless_call(position, &[(begin, meta.get_index())]);
         }
         Ok(())
     }
@@ -48,7 +59,7 @@ impl TranslateModule for Echo {
         let value = self.value.translate(meta);
         // If the variable is an array, it's always passed as a variable expression
         let value = match value {
-            FragmentKind::VarExpr(var) if var.kind.is_array() => {
+            FragmentKind::VarExpr(var) if var.kind.is_array() || var.kind.is_map() => {
                 FragmentKind::VarExpr(var.with_array_to_string(true))
             }
             other => other,
@@ -35,8 +35,12 @@ impl SyntaxModule<ParserMetadata> for Exit {
             meta.add_message(warning);
 
             let mut code_expr = Expr::new();
+            let begin = meta.get_index();
             if syntax(meta, &mut code_expr).is_ok() {
                 self.code = Some(code_expr);
+                meta.add_parenless_call(position, &[(begin, meta.get_index())]);
+            } else {
+                meta.add_parenless_call(position, &[]);
             }
         }
         Ok(())
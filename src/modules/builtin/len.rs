@@ -53,11 +53,12 @@ impl TypeCheckModule for Len {
         self.value.typecheck(meta)?;
 
         let accepted_types = Type::Union(vec![Type::Text, Type::array_of(Type::Generic)]);
-        if !self.value.get_type().is_allowed_in(&accepted_types) {
+        let value_type = self.value.get_type();
+        if !value_type.is_map() && !value_type.is_allowed_in(&accepted_types) {
             let msg = self
                 .value
                 .get_error_message(meta)
-                .message("Length can only be applied to text or array types");
+                .message("Length can only be applied to text, array or map types");
             return Err(Failure::Loud(msg));
         }
         Ok(())
@@ -67,6 +68,12 @@ impl TypeCheckModule for Len {
 impl TranslateModule for Len {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
         let value = self.value.translate(meta);
+        // Maps cannot be copied, so their entries are counted in place
+        if let FragmentKind::VarExpr(var) = &value {
+            if var.kind.is_map() {
+                return var.clone().with_length_getter(true).to_frag();
+            }
+        }
         let id = meta.gen_value_id();
         let var_stmt =
             VarStmtFragment::new("__length", self.value.get_type(), value).with_global_id(id);
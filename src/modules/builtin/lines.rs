@@ -107,6 +107,13 @@ impl TranslateModule for LinesInvocation {
             |meta| meta.gen_suppress().to_frag(),
         );
 
+        // POSIX sh has no arrays, so every line is appended with a trailing unit separator
+        let append = if meta.target.shell.is_posix() {
+            let name = var_expr.get_name();
+            raw_fragment!("{indent}{name}=\"${{{name}}}${temp}$__US\"")
+        } else {
+            raw_fragment!("{indent}{}+=(\"${}\")", var_expr.get_name(), temp)
+        };
         let fifo_var = format!("__AMBER_FIFO_{}", meta.gen_value_id());
         let pid_var = format!("__AMBER_PID_{}", meta.gen_value_id());
 
@@ -142,7 +149,7 @@ impl TranslateModule for LinesInvocation {
             // NOTE: The same read-loop pattern also exists in iter_loop.rs (IterLoop::translate).
             // If you change this, update that one too.
             raw_fragment!("while IFS= read -r {temp} || [ -n \"${temp}\" ]; do"),
-            raw_fragment!("{indent}{}+=(\"${}\")", var_expr.get_name(), temp),
+            append,
             raw_fragment!("done <\"${fifo_var}\""),
             raw_fragment!("wait ${pid_var}"),
             raw_fragment!("rm -f \"${fifo_var}\""),
@@ -6,6 +6,7 @@ use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
 use crate::raw_fragment;
 use crate::translate::fragments::var_stmt::VarStmtFragment;
+use crate::utils::ShellType;
 use heraclitus_compiler::prelude::*;
 
 #[derive(Debug, Clone, AutoKeyword)]
@@ -59,7 +60,9 @@ impl SyntaxModule<ParserMetadata> for Lock {
                     .message("Calling a builtin without parentheses is deprecated");
                 meta.add_message(warning);
                 let mut expr = Expr::new();
+                let begin = meta.get_index();
                 syntax(meta, &mut expr)?;
+                meta.add_parenless_call(position, &[(begin, meta.get_index())]);
                 self.path = Some(expr);
             }
 
@@ -101,6 +104,51 @@ impl TypeCheckModule for Lock {
     }
 }
 
+/// Removes the registered lock files
+const CLEANUP_COMMAND: &str = r#"for __amber_cleanup_file in "${__amber_cleanup_files[@]}"; do rm -f -- "$__amber_cleanup_file"; done"#;
+const POSIX_CLEANUP_COMMAND: &str = r#"while [ -n "$__amber_cleanup_files" ]; do rm -f -- "${__amber_cleanup_files%%"$__US"*}"; __amber_cleanup_files="${__amber_cleanup_files#*"$__US"}"; done"#;
+
+impl Lock {
+    /// Command of the exit trap that removes the lock files
+    pub fn cleanup_command(shell: &ShellType) -> &'static str {
+        if shell.is_posix() {
+            POSIX_CLEANUP_COMMAND
+        } else {
+            CLEANUP_COMMAND
+        }
+    }
+
+    /// Registers the lock file for removal on exit
+    fn translate_cleanup(lock_var_frag: FragmentKind) -> (FragmentKind, FragmentKind) {
+        let cleanup_array_update = fragments!(
+            "if [ -z \"${__amber_cleanup_files+x}\" ]; then __amber_cleanup_files=( \"",
+            lock_var_frag.clone(),
+            "\" ); else __amber_cleanup_files+=( \"",
+            lock_var_frag,
+            "\" ); fi\n"
+        );
+
+        let cleanup_trap_setup = raw_fragment!(
+            "if [ -z \"${{__amber_cleanup_trap_installed+x}}\" ]; then __amber_cleanup_trap_installed=1; trap '{CLEANUP_COMMAND}' EXIT; fi"
+        );
+        (cleanup_array_update, cleanup_trap_setup)
+    }
+
+    /// Same as `translate_cleanup` but keeps the files in a unit separated string
+    fn translate_posix_cleanup(lock_var_frag: FragmentKind) -> (FragmentKind, FragmentKind) {
+        let cleanup_array_update = fragments!(
+            "__amber_cleanup_files=\"${__amber_cleanup_files}",
+            lock_var_frag,
+            "${__US}\"\n"
+        );
+
+        let cleanup_trap_setup = raw_fragment!(
+            "if [ -z \"${{__amber_cleanup_trap_installed+x}}\" ]; then __amber_cleanup_trap_installed=1; trap '{POSIX_CLEANUP_COMMAND}' EXIT; fi"
+        );
+        (cleanup_array_update, cleanup_trap_setup)
+    }
+}
+
 impl TranslateModule for Lock {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
         let lock_var_id = meta.gen_value_id();
@@ -120,17 +168,11 @@ impl TranslateModule for Lock {
         let lock_var_expr = meta.push_ephemeral_variable(lock_var_stmt);
         let lock_var_frag = lock_var_expr.with_quotes(false).to_frag();
 
-        let cleanup_array_update = fragments!(
-            "if [ -z \"${__amber_cleanup_files+x}\" ]; then __amber_cleanup_files=( \"",
-            lock_var_frag.clone(),
-            "\" ); else __amber_cleanup_files+=( \"",
-            lock_var_frag.clone(),
-            "\" ); fi\n"
-        );
-
-        let cleanup_trap_setup = raw_fragment!(
-            "if [ -z \"${{__amber_cleanup_trap_installed+x}}\" ]; then __amber_cleanup_trap_installed=1; trap 'for __amber_cleanup_file in \"${{__amber_cleanup_files[@]}}\"; do rm -f -- \"$__amber_cleanup_file\"; done' EXIT; fi"
-        );
+        let (cleanup_array_update, cleanup_trap_setup) = if meta.target.shell.is_posix() {
+            Self::translate_posix_cleanup(lock_var_frag.clone())
+        } else {
+            Self::translate_cleanup(lock_var_frag.clone())
+        };
 
         let blocker = BlockFragment::new(
             vec![
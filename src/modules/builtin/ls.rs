@@ -4,6 +4,7 @@ use crate::modules::expression::expr::Expr;
 
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
+use crate::translate::compare::translate_truthy;
 use crate::utils::ParserMetadata;
 use crate::utils::ShellType;
 use crate::{fragments, raw_fragment};
@@ -143,20 +144,27 @@ impl TranslateModule for Ls {
             VarStmtFragment::new("__ls_path", Type::Text, path_fragment).with_global_id(id);
         let path_expr = meta.push_ephemeral_variable(path_var_stmt);
         // Escape backslashes
-        meta.stmt_queue.push_back(raw_fragment!(
-            "{}=\"${{{}//\\\\/\\\\\\\\}}\"",
-            path_expr.get_name(),
-            path_expr.get_name()
-        ));
+        if meta.target.shell.is_posix() {
+            meta.stmt_queue.push_back(raw_fragment!(
+                "{}=\"$(printf '%s' \"${{{}}}\" | sed 's/\\\\/\\\\\\\\/g')\"",
+                path_expr.get_name(),
+                path_expr.get_name()
+            ));
+        } else {
+            meta.stmt_queue.push_back(raw_fragment!(
+                "{}=\"${{{}//\\\\/\\\\\\\\}}\"",
+                path_expr.get_name(),
+                path_expr.get_name()
+            ));
+        }
 
         // Only create variables for all/recursive when expressions are provided
         let all_frag = if let Some(all_expr) = &*self.all {
             let all_translate = all_expr.translate(meta);
             let all_var_name = format!("__ls_all_{}", id);
             meta.stmt_queue.push_back(fragments!(
-                "(( ",
-                all_translate,
-                " )) && ",
+                translate_truthy(meta, all_translate),
+                " && ",
                 raw_fragment!("{}=\"-A\" || {}=\"\"", all_var_name, all_var_name)
             ));
             raw_fragment!("${{{}}}", all_var_name)
@@ -168,9 +176,8 @@ impl TranslateModule for Ls {
             let recursive_translate = recursive_expr.translate(meta);
             let recursive_var_name = format!("__ls_rec_{}", id);
             meta.stmt_queue.push_back(fragments!(
-                "(( ",
-                recursive_translate,
-                " )) && ",
+                translate_truthy(meta, recursive_translate),
+                " && ",
                 raw_fragment!(
                     "{}=\"-R\" || {}=\"\"",
                     recursive_var_name,
@@ -207,12 +214,27 @@ impl TranslateModule for Ls {
                 "while read -r __ls_line; do {}+=(\"${{__ls_line}}\"); done < <(",
                 var_expr.get_name()
             ),
+            // POSIX sh has no process substitution, so the lines are split after the listing
+            ShellType::Posix => raw_fragment!("{}=$(", var_expr.get_name()),
+        };
+        let split_command = match meta.target.shell {
+            ShellType::Posix => raw_fragment!(
+                "{}=$(printf '%s' \"${{{}}}\" | __amber_lines)",
+                var_expr.get_name(),
+                var_expr.get_name()
+            ),
+            _ => FragmentKind::Empty,
+        };
+        let ifs = match meta.target.shell {
+            // POSIX sh has no `$'...'` quoting, so the newline is written literally
+            ShellType::Posix => raw_fragment!("IFS='\n';"),
+            _ => raw_fragment!("IFS=$'\\n';"),
         };
-        meta.stmt_queue.extend([
+        meta.stmt_queue.push_back(
             ListFragment::new(
                 vec![
                     read_command,
-                    raw_fragment!("IFS=$'\\n';"),
+                    ifs,
                     sudo_prefix,
                     fragments!("LC_ALL=C ls -1"),
                     all_frag,
@@ -223,9 +245,14 @@ impl TranslateModule for Ls {
             )
                 .with_spaces()
                 .to_frag(),
-            handler,
-            fragments!(");"),
-        ]);
+        );
+        // The status of the listing is only available after the command substitution ends
+        if meta.target.shell.is_posix() {
+            meta.stmt_queue
+                .extend([fragments!(");"), handler, split_command]);
+        } else {
+            meta.stmt_queue.extend([handler, fragments!(");")]);
+        }
         if matches!(&meta.target.shell, ShellType::Zsh) {
             meta.stmt_queue.extend([fragments!(raw_fragment!(
                 // in ZSH, null characters are appended to the array, it's the simplest option to remove them
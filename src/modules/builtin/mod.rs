@@ -17,4 +17,5 @@ pub mod shellname;
 pub mod shellversion;
 pub mod sleep;
 pub mod touch;
+pub mod unset;
 pub mod wait;
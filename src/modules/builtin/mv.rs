@@ -47,8 +47,12 @@ impl SyntaxModule<ParserMetadata> for Mv {
                     .message("Calling a builtin without parentheses is deprecated");
                 meta.add_message(warning);
 
+                let source = meta.get_index();
                 syntax(meta, &mut *self.source)?;
+                let destination = meta.get_index();
                 syntax(meta, &mut *self.destination)?;
+                let end = meta.get_index();
+                meta.add_parenless_call(position, &[(source, destination), (destination, end)]);
             }
 
             // Handle optional failure handler (failed/succeeded/exited blocks)
@@ -3,7 +3,6 @@ use crate::modules::function::invocation_utils::run_function_with_args;
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
 use crate::modules::variable::variable_name_extensions;
-use crate::raw_fragment;
 use crate::translate::module::TranslateModule;
 use crate::utils::{ParserMetadata, TranslateMetadata};
 use heraclitus_compiler::prelude::*;
@@ -49,7 +48,9 @@ impl SyntaxModule<ParserMetadata> for Nameof {
             let warning = Message::new_warn_at_token(meta, tok)
                 .message("Calling a builtin without parentheses is deprecated");
             meta.add_message(warning);
+            let begin = meta.get_index();
             self.name = variable(meta, variable_name_extensions())?;
+            meta.add_parenless_call(position, &[(begin, meta.get_index())]);
         }
         Ok(())
     }
@@ -106,6 +107,7 @@ impl TypeCheckModule for Nameof {
                             }
                         };
 
+                        meta.add_invoked_function(fun_decl.id, variant_id);
                         self.function_variant = Some((fun_decl.id, variant_id));
                     }
                     None => {
@@ -127,7 +129,9 @@ impl TranslateModule for Nameof {
         if let Some((id, variant)) = self.function_variant {
             let prefix = meta.gen_variable_prefix(&self.name);
             let name = format!("{prefix}{}__{id}_v{variant}", self.name);
-            raw_fragment!("{name}")
+            RawFragment::new(&name)
+                .with_function((id, variant))
+                .to_frag()
         } else {
             VarExprFragment::new(&self.name, Type::Text)
                 .with_global_id(self.global_id)
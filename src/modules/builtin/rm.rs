@@ -4,6 +4,7 @@ use crate::modules::expression::expr::Expr;
 
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
+use crate::translate::compare::translate_truthy;
 use crate::utils::ParserMetadata;
 use crate::{fragments, raw_fragment};
 use heraclitus_compiler::prelude::*;
@@ -113,9 +114,8 @@ impl TranslateModule for Rm {
                 .with_global_id(recursive_id);
             let recursive_expr = meta.push_ephemeral_variable(recursive_var_stmt);
             meta.stmt_queue.extend([fragments!(
-                "(( ",
-                recursive_translate,
-                " )) && ",
+                translate_truthy(meta, recursive_translate),
+                " && ",
                 raw_fragment!(
                     "{}=\"-r\" || {}=\"\"",
                     recursive_expr.get_name(),
@@ -136,9 +136,8 @@ impl TranslateModule for Rm {
                 .with_global_id(force_id);
             let force_expr = meta.push_ephemeral_variable(force_var_stmt);
             meta.stmt_queue.extend([fragments!(
-                "(( ",
-                force_translate,
-                " )) && ",
+                translate_truthy(meta, force_translate),
+                " && ",
                 raw_fragment!(
                     "{}=\"-f\" || {}=\"\"",
                     force_expr.get_name(),
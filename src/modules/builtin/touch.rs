@@ -41,7 +41,9 @@ impl SyntaxModule<ParserMetadata> for Touch {
                 let warning = Message::new_warn_at_token(meta, tok)
                     .message("Calling a builtin without parentheses is deprecated");
                 meta.add_message(warning);
+                let begin = meta.get_index();
                 syntax(meta, &mut self.value)?;
+                meta.add_parenless_call(position, &[(begin, meta.get_index())]);
             }
 
             if let Err(e) = syntax(meta, &mut self.failure_handler) {
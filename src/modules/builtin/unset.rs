@@ -0,0 +1,77 @@
+use crate::modules::expression::expr::{Expr, ExprType};
+use crate::modules::prelude::*;
+use crate::modules::types::Typed;
+use crate::utils::ShellType;
+use crate::{fragments, raw_fragment};
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "unset"]
+#[kind = "builtin_stmt"]
+pub struct Unset {
+    value: Expr,
+}
+
+impl SyntaxModule<ParserMetadata> for Unset {
+    syntax_name!("Unset");
+
+    fn new() -> Self {
+        Unset { value: Expr::new() }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        token(meta, "unset")?;
+        token(meta, "(")?;
+        syntax(meta, &mut self.value)?;
+        token(meta, ")")?;
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for Unset {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.value.typecheck(meta)?;
+        if let Some(ExprType::Access(access)) = &self.value.value {
+            if let (Some(ExprType::VariableGet(var)), Some(_)) =
+                (&access.left.value, access.index.as_ref())
+            {
+                if access.left.get_type().is_map() {
+                    meta.mark_var_modified(&var.name);
+                    return Ok(());
+                }
+            }
+        }
+        let position = self.value.get_position();
+        error_pos!(meta, position => {
+            message: "Builtin function `unset` can only remove entries of map variables",
+            comment: "Use it like 'unset(map[key])'"
+        })
+    }
+}
+
+impl TranslateModule for Unset {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let Some(ExprType::Access(access)) = &self.value.value else {
+            unreachable!("Only map entries can be unset")
+        };
+        let FragmentKind::VarExpr(var) = access.left.translate(meta) else {
+            unreachable!("Maps are always represented as variable expressions")
+        };
+        let key = access
+            .index
+            .as_ref()
+            .as_ref()
+            .map(|key| key.translate(meta).with_quotes(false))
+            .unwrap_or_default();
+        let name = var.get_name();
+        // In zsh the reference holds the name of the map
+        let name = if var.is_ref && meta.target.shell == ShellType::Zsh {
+            format!("${{{name}}}")
+        } else {
+            name
+        };
+        fragments!(raw_fragment!("unset \"{name}["), key, "]\"")
+    }
+}
+
+crate::impl_documentation_noop!(Unset);
@@ -8,6 +8,11 @@ use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
 use heraclitus_compiler::prelude::*;
 
+/// Syntax used by the commands of the standard library that POSIX shells cannot parse
+const BASH_ONLY_SYNTAX: [&str; 11] = [
+    "< <(", "<<<", "$'", "[@]", "[*]", "${!", "+=(", "[[", "declare ", "typeset ", "read -rd",
+];
+
 #[derive(Debug, Clone)]
 pub struct Command {
     parts: Vec<TextPart>,
@@ -58,8 +63,26 @@ impl SyntaxModule<ParserMetadata> for Command {
     }
 }
 
+impl Command {
+    /// Checks if the command uses syntax that is specific to Bash and similar shells
+    fn is_bash_only(&self) -> bool {
+        self.parts.iter().any(|part| match part {
+            TextPart::String(value) => BASH_ONLY_SYNTAX.iter().any(|syntax| value.contains(syntax)),
+            TextPart::Expr(_) => false,
+        })
+    }
+}
+
 impl TypeCheckModule for Command {
     fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let is_std = meta
+            .context
+            .path
+            .as_ref()
+            .is_some_and(|path| path.starts_with("std/"));
+        if is_std && self.is_bash_only() {
+            meta.bash_only_command = true;
+        }
         self.modifier.use_modifiers(meta, |modifier, meta| {
             for part in self.parts.iter_mut() {
                 part.typecheck(meta)?;
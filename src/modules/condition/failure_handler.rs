@@ -1,10 +1,12 @@
+use crate::fragments;
 use crate::modules::block::Block;
+use crate::modules::function::defer::translate_defer_return;
 use crate::modules::prelude::*;
 use crate::modules::types::Type;
 use crate::modules::variable::variable_name_extensions;
+use crate::translate::stack_trace::{translate_trace_exit, translate_trace_step, TraceStep};
 use crate::utils::context::{VariableDecl, VariableDeclWarn};
 use crate::utils::metadata::ParserMetadata;
-use crate::{fragments, raw_fragment};
 use heraclitus_compiler::compiling::failing::position_info::PositionInfo;
 use heraclitus_compiler::prelude::*;
 
@@ -97,8 +99,18 @@ impl SyntaxModule<ParserMetadata> for FailureHandler {
                     "The '?' operator can only be used in the main block or inside a function body"
                 );
             }
+            if meta.context.is_defer_ctx {
+                return error!(meta, tok => {
+                    message: "The '?' operator cannot be used inside of a defer block",
+                    comment: "Deferred blocks run while the function is already returning. Use 'failed' block to handle the failure"
+                });
+            }
             self.is_question_mark = true;
             self.failure_type = FailureType::Failed;
+            // Builtins do not report their position so the operator itself is used
+            if self.error_position.is_none() {
+                self.error_position = Some(PositionInfo::from_token(meta, tok));
+            }
         } else {
             let keyword = ["failed", "succeeded", "exited"]
                 .iter()
@@ -274,16 +286,23 @@ impl TranslateModule for FailureHandler {
             } else {
                 FragmentKind::Empty
             };
-            let ret = if self.is_main { "exit" } else { "return" };
-            let ret = fragments!(
-                raw_fragment!("{ret} "),
-                status_variable_expr.clone().to_frag()
-            );
+            // A failed function call has already started the trace
+            let step = if self.function_name.is_some() {
+                TraceStep::Push
+            } else {
+                TraceStep::Start
+            };
+            let trace = translate_trace_step(meta, step, self.error_position.as_ref());
+            let ret = if self.is_main {
+                translate_trace_exit(meta, status_variable_expr.clone().to_frag())
+            } else {
+                translate_defer_return(meta, status_variable_expr.clone().to_frag())
+            };
             return BlockFragment::new(
                 vec![
                     status_variable_stmt.to_frag(),
                     fragments!("if [ ", status_variable_expr.to_frag(), " != 0 ]; then"),
-                    BlockFragment::new(vec![clear_return, ret], true).to_frag(),
+                    BlockFragment::new(vec![trace, clear_return, ret], true).to_frag(),
                     fragments!("fi"),
                 ],
                 false,
@@ -1,4 +1,3 @@
-use crate::fragments;
 use crate::modules::block::Block;
 use crate::modules::expression::expr::Expr;
 use crate::modules::prelude::*;
@@ -84,7 +83,7 @@ impl SyntaxModule<ParserMetadata> for IfChain {
                         meta,
                         meta.get_current_token(),
                         "Expected `else` condition to be the last in the if chain"
-                    )?;
+                    );
                 }
                 return Ok(());
             }
@@ -200,29 +199,16 @@ impl TranslateModule for IfChain {
             }
         }
 
-        let mut result = vec![];
-        let mut is_first = true;
+        let mut result = ConditionalFragment::new();
         for (comments, cond, block) in self.cond_blocks.iter() {
-            for comment in comments {
-                result.push(comment.translate(meta));
-            }
-            if is_first {
-                result.push(fragments!("if [ ", cond.translate(meta), " != 0 ]; then"));
-                result.push(block.translate(meta));
-                is_first = false;
-            } else {
-                result.push(fragments!("elif [ ", cond.translate(meta), " != 0 ]; then"));
-                result.push(block.translate(meta));
-            }
+            let comments = comments.iter().map(|c| c.translate(meta)).collect();
+            let cond = cond.translate(meta);
+            result.add_branch(comments, cond, block.translate(meta));
         }
         if let Some((comments, false_block)) = &self.false_block {
-            for comment in comments {
-                result.push(comment.translate(meta));
-            }
-            result.push(fragments!("else"));
-            result.push(false_block.translate(meta));
+            let comments = comments.iter().map(|c| c.translate(meta)).collect();
+            result.set_otherwise(comments, false_block.translate(meta));
         }
-        result.push(fragments!("fi"));
-        BlockFragment::new(result, false).to_frag()
+        result.to_frag()
     }
 }
@@ -1,4 +1,3 @@
-use crate::fragments;
 use crate::modules::block::Block;
 use crate::modules::expression::expr::Expr;
 use crate::modules::prelude::*;
@@ -151,21 +150,18 @@ impl TranslateModule for IfCondition {
                 .map(|b| b.translate(meta))
                 .unwrap_or(FragmentKind::Empty),
             None => {
-                let mut result = vec![];
-                result.push(fragments!(
-                    "if [ ",
-                    self.expr.translate(meta),
-                    " != 0 ]; then"
-                ));
-                if let Some(true_block) = &self.true_block {
-                    result.push(true_block.translate(meta));
-                }
+                let mut result = ConditionalFragment::new();
+                let cond = self.expr.translate(meta);
+                let true_block = self
+                    .true_block
+                    .as_ref()
+                    .map(|b| b.translate(meta))
+                    .unwrap_or(FragmentKind::Empty);
+                result.add_branch(vec![], cond, true_block);
                 if let Some(false_block) = &self.false_block {
-                    result.push(fragments!("else"));
-                    result.push(false_block.translate(meta));
+                    result.set_otherwise(vec![], false_block.translate(meta));
                 }
-                result.push(fragments!("fi"));
-                BlockFragment::new(result, false).to_frag()
+                result.to_frag()
             }
         }
     }
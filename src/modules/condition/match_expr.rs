@@ -0,0 +1,183 @@
+use crate::modules::condition::pattern::{
+    translate_arm_condition, translate_match_subject, typecheck_match_arms, MatchArm, MatchPattern,
+};
+use crate::modules::expression::expr::Expr;
+use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed};
+use crate::{fragments, raw_fragment};
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct MatchExpression {
+    subject: Box<Expr>,
+    arms: Vec<MatchArm<Box<Expr>>>,
+    else_arm: Option<(Box<Expr>, Option<Token>)>,
+    kind: Type,
+    tok: Option<Token>,
+}
+
+impl Typed for MatchExpression {
+    fn get_type(&self) -> Type {
+        self.kind.clone()
+    }
+}
+
+impl MatchExpression {
+    fn parse_value(meta: &mut ParserMetadata) -> Result<Box<Expr>, Failure> {
+        if token(meta, ":").is_err() {
+            return error!(
+                meta,
+                meta.get_current_token(),
+                "Expected ':' after the match pattern"
+            );
+        }
+        let mut value = Box::new(Expr::new());
+        syntax(meta, &mut *value)?;
+        Ok(value)
+    }
+
+    fn typecheck_values(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let values = self
+            .arms
+            .iter()
+            .map(|arm| &arm.body)
+            .chain(self.else_arm.as_ref().map(|(value, _)| value));
+        let mut kind: Option<Type> = None;
+        for value in values {
+            let value_type = value.get_type();
+            if value_type.is_map() {
+                let pos = value.get_position();
+                return error_pos!(meta, pos => {
+                    message: "Match expression cannot evaluate to a map",
+                    comment: "Maps cannot be copied. Use a 'match' statement instead."
+                });
+            }
+            match &kind {
+                Some(kind) if *kind != value_type => {
+                    let pos = value.get_position();
+                    return error_pos!(meta, pos => {
+                        message: "Match expression can only evaluate to value of one type.",
+                        comment: format!("Provided arms of type '{kind}' and '{value_type}'.")
+                    });
+                }
+                Some(_) => {}
+                None => kind = Some(value_type),
+            }
+        }
+        self.kind = kind.unwrap_or_default();
+        Ok(())
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for MatchExpression {
+    syntax_name!("Match Expression");
+
+    fn new() -> Self {
+        MatchExpression {
+            subject: Box::new(Expr::new()),
+            arms: vec![],
+            else_arm: None,
+            kind: Type::Null,
+            tok: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.tok = meta.get_current_token();
+        token(meta, "match")?;
+        syntax(meta, &mut *self.subject)?;
+        token(meta, "{")?;
+        loop {
+            // Skip comments and newlines
+            if token_by(meta, |token| {
+                token.starts_with("//") || token.starts_with('\n')
+            })
+            .is_ok()
+            {
+                continue;
+            }
+            let tok = meta.get_current_token();
+            if token(meta, "else").is_ok() {
+                self.else_arm = Some((Self::parse_value(meta)?, tok));
+                let _ = token_by(meta, |token| token.starts_with('\n'));
+                if token(meta, "}").is_err() {
+                    return error!(
+                        meta,
+                        meta.get_current_token(),
+                        "Expected `else` arm to be the last in the match"
+                    );
+                }
+                return Ok(());
+            }
+            if token(meta, "}").is_ok() {
+                return Ok(());
+            }
+            let patterns = MatchPattern::parse_patterns(meta)?;
+            let body = Self::parse_value(meta)?;
+            self.arms.push(MatchArm { patterns, body });
+        }
+    }
+}
+
+impl TypeCheckModule for MatchExpression {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.subject.typecheck(meta)?;
+        if self.subject.get_type().is_map() {
+            let pos = self.subject.get_position();
+            return error_pos!(meta, pos, "Maps cannot be matched");
+        }
+        let coverage =
+            typecheck_match_arms(meta, &self.subject, &mut self.arms, &mut self.else_arm)?;
+        if self.else_arm.is_none() {
+            let missing = coverage.get_missing_values(&self.subject);
+            let comment = if missing.is_empty() {
+                "Add an 'else' arm".to_string()
+            } else {
+                format!("Add arms for {} or an 'else' arm", missing.join(", "))
+            };
+            return error!(meta, self.tok.clone() => {
+                message: "Match expression must handle every possible value",
+                comment: comment
+            });
+        }
+        self.typecheck_values(meta)
+    }
+}
+
+impl TranslateModule for MatchExpression {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let Some((else_value, _)) = &self.else_arm else {
+            unreachable!("Match expression always has an else arm after typechecking")
+        };
+        if self.arms.is_empty() {
+            return else_value.translate(meta);
+        }
+        let value = translate_match_subject(meta, &self.subject);
+        let mut result = vec![];
+        for (index, arm) in self.arms.iter().enumerate() {
+            let keyword = if index == 0 { "if" } else { "; elif" };
+            let cond = translate_arm_condition(meta, &self.subject, &value, &arm.patterns);
+            let arm_value = arm.body.translate(meta);
+            result.push(fragments!(
+                raw_fragment!("{keyword} [ "),
+                cond,
+                " != 0 ]; then echo ",
+                arm_value
+            ));
+        }
+        let else_value = else_value.translate(meta);
+        result.push(fragments!("; else echo ", else_value, "; fi"));
+        let expr = ListFragment::new(result).to_frag();
+        if self.kind.is_array() || self.kind.is_struct() {
+            let id = meta.gen_value_id();
+            let value = SubprocessFragment::new(expr).with_quotes(false).to_frag();
+            let var_stmt =
+                VarStmtFragment::new("match", self.kind.clone(), value).with_global_id(id);
+            meta.push_ephemeral_variable(var_stmt).to_frag()
+        } else {
+            SubprocessFragment::new(expr).to_frag()
+        }
+    }
+}
+
+crate::impl_documentation_noop!(MatchExpression);
@@ -0,0 +1,125 @@
+use crate::modules::block::Block;
+use crate::modules::condition::pattern::{
+    translate_arm_condition, translate_match_subject, typecheck_match_arms, MatchArm, MatchPattern,
+};
+use crate::modules::expression::expr::Expr;
+use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed};
+use crate::{fragments, raw_fragment};
+use amber_meta::AutoKeyword;
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "match"]
+#[kind = "stmt"]
+pub struct MatchStatement {
+    subject: Box<Expr>,
+    arms: Vec<MatchArm<Block>>,
+    else_arm: Option<(Block, Option<Token>)>,
+}
+
+impl SyntaxModule<ParserMetadata> for MatchStatement {
+    syntax_name!("Match Statement");
+
+    fn new() -> Self {
+        MatchStatement {
+            subject: Box::new(Expr::new()),
+            arms: vec![],
+            else_arm: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        token(meta, "match")?;
+        syntax(meta, &mut *self.subject)?;
+        token(meta, "{")?;
+        loop {
+            // Skip comments and newlines
+            if token_by(meta, |token| {
+                token.starts_with("//") || token.starts_with('\n')
+            })
+            .is_ok()
+            {
+                continue;
+            }
+            let tok = meta.get_current_token();
+            if token(meta, "else").is_ok() {
+                let mut block = Block::new().with_needs_noop().with_condition();
+                syntax(meta, &mut block)?;
+                self.else_arm = Some((block, tok));
+                let _ = token_by(meta, |token| token.starts_with('\n'));
+                if token(meta, "}").is_err() {
+                    return error!(
+                        meta,
+                        meta.get_current_token(),
+                        "Expected `else` arm to be the last in the match"
+                    );
+                }
+                return Ok(());
+            }
+            if token(meta, "}").is_ok() {
+                return Ok(());
+            }
+            let patterns = MatchPattern::parse_patterns(meta)?;
+            let mut body = Block::new().with_needs_noop().with_condition();
+            syntax(meta, &mut body)?;
+            self.arms.push(MatchArm { patterns, body });
+        }
+    }
+}
+
+impl TypeCheckModule for MatchStatement {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.subject.typecheck(meta)?;
+        let subject_type = self.subject.get_type();
+        if subject_type.is_map() {
+            let pos = self.subject.get_position();
+            return error_pos!(meta, pos, "Maps cannot be matched");
+        }
+        let coverage =
+            typecheck_match_arms(meta, &self.subject, &mut self.arms, &mut self.else_arm)?;
+        if let (Type::Enum(def), None) = (&subject_type, &self.else_arm) {
+            let pos = self.subject.get_position();
+            return error_pos!(meta, pos => {
+                message: format!("Match over enum '{}' is not exhaustive", def.name),
+                comment: format!(
+                    "Add arms for {} or an 'else' arm",
+                    coverage.get_missing_values(&self.subject).join(", ")
+                )
+            });
+        }
+        Ok(())
+    }
+}
+
+impl TranslateModule for MatchStatement {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        if self.arms.is_empty() {
+            return self
+                .else_arm
+                .as_ref()
+                .map(|(block, _)| block.translate(meta))
+                .unwrap_or(FragmentKind::Empty);
+        }
+        let value = translate_match_subject(meta, &self.subject);
+        let mut result = vec![];
+        for (index, arm) in self.arms.iter().enumerate() {
+            let keyword = if index == 0 { "if" } else { "elif" };
+            let cond = translate_arm_condition(meta, &self.subject, &value, &arm.patterns);
+            result.push(fragments!(
+                raw_fragment!("{keyword} [ "),
+                cond,
+                " != 0 ]; then"
+            ));
+            result.push(arm.body.translate(meta));
+        }
+        if let Some((block, _)) = &self.else_arm {
+            result.push(fragments!("else"));
+            result.push(block.translate(meta));
+        }
+        result.push(fragments!("fi"));
+        BlockFragment::new(result, false).to_frag()
+    }
+}
+
+crate::impl_documentation_noop!(MatchStatement);
@@ -1,3 +1,6 @@
 pub mod failure_handler;
 pub mod ifchain;
 pub mod ifcond;
+pub mod match_expr;
+pub mod match_stmt;
+pub mod pattern;
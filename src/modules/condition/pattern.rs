@@ -0,0 +1,364 @@
+use crate::fragments;
+use crate::modules::expression::expr::{Expr, ExprType};
+use crate::modules::expression::typeop::{is::Is, TypeOp};
+use crate::modules::prelude::*;
+use crate::modules::types::{try_parse_type, Type, Typed};
+use crate::translate::compute::{translate_float_computation, ArithOp};
+use crate::utils::cc_flags::{get_ccflag_name, CCFlags};
+use heraclitus_compiler::prelude::*;
+
+use std::collections::HashMap;
+
+/// Pattern of a single arm in the `match` statement or expression
+#[derive(Debug, Clone)]
+pub enum MatchPattern {
+    /// Literal or enum variant that is compared with the matched value
+    Value(Box<Expr>),
+    /// Type that narrows down the matched value the same way as the `is` operator
+    Type(Type, Option<Token>),
+}
+
+impl MatchPattern {
+    fn get_position(&self, meta: &ParserMetadata) -> PositionInfo {
+        match self {
+            MatchPattern::Value(expr) => expr.get_position(),
+            MatchPattern::Type(_, tok) => PositionInfo::from_token(meta, tok.clone()),
+        }
+    }
+
+    // Creates the `is` operator that checks the type of the matched value
+    fn as_type_check(subject: &Expr, kind: &Type) -> Is {
+        let mut is = Is::new();
+        is.set_left(subject.clone());
+        is.set_right(kind.clone());
+        is
+    }
+
+    fn is_type_pattern(meta: &ParserMetadata) -> bool {
+        let index = meta.get_index();
+        let Some(tok) = meta.context.expr.get(index) else {
+            return false;
+        };
+        let is_user_type = meta.context.structs.contains_key(&tok.word)
+            || meta.context.enums.contains_key(&tok.word);
+        let next = meta
+            .context
+            .expr
+            .get(index + 1)
+            .map(|tok| tok.word.as_str());
+        match tok.word.as_str() {
+            "Text" | "Num" | "Int" | "Bool" | "Null" | "[" => true,
+            // Variants of enums are accessed with a dot like `Mode.Dev`
+            _ => is_user_type && next != Some("."),
+        }
+    }
+
+    /// Parses the comma separated patterns of a single arm
+    pub fn parse_patterns(meta: &mut ParserMetadata) -> Result<Vec<MatchPattern>, Failure> {
+        let mut patterns = vec![];
+        loop {
+            if Self::is_type_pattern(meta) {
+                let tok = meta.get_current_token();
+                let kind = try_parse_type(meta)?;
+                patterns.push(MatchPattern::Type(kind, tok));
+            } else {
+                let mut expr = Expr::new();
+                syntax(meta, &mut expr)?;
+                if !matches!(
+                    expr.value,
+                    Some(
+                        ExprType::Text(_)
+                            | ExprType::Integer(_)
+                            | ExprType::Number(_)
+                            | ExprType::Bool(_)
+                            | ExprType::Neg(_)
+                            | ExprType::EnumVariant(_)
+                    )
+                ) {
+                    let pos = expr.get_position();
+                    return error_pos!(meta, pos => {
+                        message: "Match patterns can only be literals, enum variants or types",
+                        comment: "Use an 'if' chain to match values against arbitrary conditions"
+                    });
+                }
+                patterns.push(MatchPattern::Value(Box::new(expr)));
+            }
+            if token(meta, ",").is_err() {
+                return Ok(patterns);
+            }
+        }
+    }
+
+    /// Translates the condition that checks whether the matched value fits the pattern
+    pub fn translate_condition(
+        &self,
+        meta: &mut TranslateMetadata,
+        subject: &Expr,
+        value: &FragmentKind,
+    ) -> FragmentKind {
+        match self {
+            MatchPattern::Type(kind, _) => Self::as_type_check(subject, kind).translate(meta),
+            MatchPattern::Value(expr) => {
+                let left = value.clone().with_quotes(false);
+                let right = expr.translate(meta).with_quotes(false);
+                match (subject.get_type(), expr.get_type()) {
+                    (Type::Num, _) | (_, Type::Num) => {
+                        translate_float_computation(meta, ArithOp::Eq, Some(left), Some(right))
+                    }
+                    (Type::Int, _) => ArithmeticFragment::new(left, ArithOp::Eq, right).to_frag(),
+                    _ => SubprocessFragment::new(fragments!(
+                        "[ \"_",
+                        left,
+                        "\" != \"_",
+                        right,
+                        "\" ]; echo $?"
+                    ))
+                    .to_frag(),
+                }
+            }
+        }
+    }
+}
+
+/// Keeps track of the values that are not matched by any of the previous arms
+#[derive(Debug, Clone)]
+pub struct MatchCoverage {
+    /// Part of the matched type that was not narrowed down by the type patterns
+    remaining: Option<Type>,
+    /// Enum variants or boolean values that were not matched yet
+    values: Option<Vec<String>>,
+    /// Literal values that were already matched
+    matched: Vec<String>,
+}
+
+impl MatchCoverage {
+    pub fn new(subject: &Expr) -> Self {
+        let kind = subject.get_type();
+        let values = match &kind {
+            Type::Enum(def) => Some(def.variants.clone()),
+            Type::Bool => Some(vec!["true".to_string(), "false".to_string()]),
+            _ => None,
+        };
+        MatchCoverage {
+            remaining: Some(kind),
+            values,
+            matched: vec![],
+        }
+    }
+
+    pub fn is_exhaustive(&self) -> bool {
+        self.remaining.is_none() || self.values.as_ref().is_some_and(|values| values.is_empty())
+    }
+
+    /// Lists the enum variants or boolean values that are not matched by any arm
+    pub fn get_missing_values(&self, subject: &Expr) -> Vec<String> {
+        let values = self.values.clone().unwrap_or_default();
+        match subject.get_type() {
+            Type::Enum(def) => values
+                .iter()
+                .map(|variant| format!("'{}.{variant}'", def.name))
+                .collect(),
+            _ => values.iter().map(|value| format!("'{value}'")).collect(),
+        }
+    }
+
+    // Returns the value of the pattern that identifies it among other patterns
+    fn get_literal(expr: &Expr) -> Option<String> {
+        match &expr.value {
+            Some(ExprType::EnumVariant(variant)) => Some(variant.variant.clone()),
+            Some(ExprType::Bool(value)) => {
+                value.analyze_control_flow().map(|value| value.to_string())
+            }
+            Some(ExprType::Integer(value)) => Some(value.value.clone()),
+            _ => expr.get_integer_value().map(|value| value.to_string()),
+        }
+    }
+
+    /// Typechecks the patterns of the arm and records the values that they match.
+    /// Unreachable patterns are removed. Returns whether the arm matches always,
+    /// never or depending on the value and the narrowed type of the matched variable.
+    pub fn typecheck_arm(
+        &mut self,
+        meta: &mut ParserMetadata,
+        subject: &Expr,
+        patterns: &mut Vec<MatchPattern>,
+    ) -> Result<(Option<bool>, HashMap<String, Type>), Failure> {
+        let subject_type = subject.get_type();
+        let mut facts = HashMap::new();
+        let mut control_flow = Some(false);
+        let mut reachable = vec![];
+        for mut pattern in std::mem::take(patterns) {
+            let pos = pattern.get_position(meta);
+            if self.is_exhaustive() {
+                warn_dead_code(
+                    meta,
+                    pos,
+                    "Pattern is unreachable, previous arms match every value",
+                );
+                continue;
+            }
+            match &mut pattern {
+                MatchPattern::Type(kind, _) => {
+                    let is = MatchPattern::as_type_check(subject, kind);
+                    match is.analyze_control_flow() {
+                        Some(true) => control_flow = Some(true),
+                        Some(false) => {
+                            let reason = format!(
+                                "Pattern never matches, value of type '{subject_type}' is not '{kind}'"
+                            );
+                            warn_dead_code(meta, pos, &reason);
+                            continue;
+                        }
+                        None => control_flow = control_flow.filter(|&always| always),
+                    }
+                    let (true_facts, _) = is.extract_facts();
+                    facts.extend(true_facts);
+                    self.remaining = self.remaining.take().and_then(|left| left.exclude(kind));
+                }
+                MatchPattern::Value(expr) => {
+                    expr.typecheck(meta)?;
+                    let kind = expr.get_type();
+                    if !kind.can_intersect(&subject_type) {
+                        return error_pos!(
+                            meta,
+                            pos,
+                            format!("Pattern of type '{kind}' cannot match a value of type '{subject_type}'")
+                        );
+                    }
+                    if let Some(literal) = Self::get_literal(expr) {
+                        if self.matched.contains(&literal) {
+                            let reason =
+                                "Pattern is unreachable, value is already matched by previous arms";
+                            warn_dead_code(meta, pos, reason);
+                            continue;
+                        }
+                        if let Some(values) = &mut self.values {
+                            values.retain(|value| *value != literal);
+                        }
+                        self.matched.push(literal);
+                    }
+                    control_flow = control_flow.filter(|&always| always);
+                }
+            }
+            reachable.push(pattern);
+        }
+        // Narrowing applies only when the arm matches a single type
+        if reachable.len() > 1 {
+            facts.clear();
+        }
+        *patterns = reachable;
+        Ok((control_flow, facts))
+    }
+
+    /// Types of the matched variable that are not matched by any previous arm
+    pub fn get_negative_facts(&self, subject: &Expr) -> HashMap<String, Type> {
+        let mut facts = HashMap::new();
+        if let (Some(ExprType::VariableGet(var)), Some(kind)) = (&subject.value, &self.remaining) {
+            facts.insert(var.name.clone(), kind.clone());
+        }
+        facts
+    }
+}
+
+pub fn warn_dead_code(meta: &mut ParserMetadata, pos: PositionInfo, reason: &str) {
+    if meta.context.cc_flags.contains(&CCFlags::AllowDeadCode) {
+        return;
+    }
+    let flag_name = get_ccflag_name(CCFlags::AllowDeadCode);
+    let message = Message::new_warn_at_position(meta, pos)
+        .message(reason)
+        .comment(format!(
+            "To suppress this warning, use '{flag_name}' compiler flag"
+        ));
+    meta.add_message(message);
+}
+
+/// Arm of the `match` statement or expression with its body
+#[derive(Debug, Clone)]
+pub struct MatchArm<T> {
+    pub patterns: Vec<MatchPattern>,
+    pub body: T,
+}
+
+/// Typechecks the arms of the match and narrows down the type of the matched variable in them.
+/// Arms that can never be reached are removed and the arm that matches every remaining value
+/// becomes the `else` arm. Returns the coverage of the matched values.
+pub fn typecheck_match_arms<T: TypeCheckModule>(
+    meta: &mut ParserMetadata,
+    subject: &Expr,
+    arms: &mut Vec<MatchArm<T>>,
+    else_arm: &mut Option<(T, Option<Token>)>,
+) -> Result<MatchCoverage, Failure> {
+    let mut coverage = MatchCoverage::new(subject);
+    let mut reachable = vec![];
+    for mut arm in std::mem::take(arms) {
+        if coverage.is_exhaustive() {
+            if let Some(pattern) = arm.patterns.first() {
+                let pos = pattern.get_position(meta);
+                warn_dead_code(
+                    meta,
+                    pos,
+                    "Arm is unreachable, previous arms match every value",
+                );
+            }
+            continue;
+        }
+        let mut facts = coverage.get_negative_facts(subject);
+        let (control_flow, true_facts) =
+            coverage.typecheck_arm(meta, subject, &mut arm.patterns)?;
+        if arm.patterns.is_empty() {
+            continue;
+        }
+        facts.extend(true_facts);
+        meta.with_narrowed_scope(facts, |meta| arm.body.typecheck(meta))?;
+        if control_flow == Some(true) || coverage.is_exhaustive() {
+            if let Some((_, tok)) = else_arm.take() {
+                let pos = PositionInfo::from_token(meta, tok);
+                warn_dead_code(
+                    meta,
+                    pos,
+                    "Arm 'else' is unreachable, previous arms match every value",
+                );
+            }
+            *else_arm = Some((arm.body, None));
+            continue;
+        }
+        reachable.push(arm);
+    }
+    *arms = reachable;
+    if let Some((body, tok)) = else_arm {
+        // The else arm was already typechecked if it comes from the exhaustive arm
+        if tok.is_some() {
+            let facts = coverage.get_negative_facts(subject);
+            meta.with_narrowed_scope(facts, |meta| body.typecheck(meta))?;
+        }
+    }
+    Ok(coverage)
+}
+
+/// Stores the matched value in a variable, so that it is evaluated only once
+pub fn translate_match_subject(meta: &mut TranslateMetadata, subject: &Expr) -> FragmentKind {
+    match subject.translate(meta) {
+        value @ FragmentKind::VarExpr(_) => value,
+        value => {
+            let id = meta.gen_value_id();
+            let var_stmt =
+                VarStmtFragment::new("match", subject.get_type(), value).with_global_id(id);
+            meta.push_ephemeral_variable(var_stmt).to_frag()
+        }
+    }
+}
+
+/// Translates the condition that checks whether the matched value fits any pattern of the arm
+pub fn translate_arm_condition(
+    meta: &mut TranslateMetadata,
+    subject: &Expr,
+    value: &FragmentKind,
+    patterns: &[MatchPattern],
+) -> FragmentKind {
+    patterns
+        .iter()
+        .map(|pattern| pattern.translate_condition(meta, subject, value))
+        .reduce(|left, right| ArithmeticFragment::new(left, ArithOp::Or, right).to_frag())
+        .unwrap_or_else(|| fragments!("0"))
+}
@@ -0,0 +1,126 @@
+use crate::modules::is_after_line_break;
+use crate::modules::prelude::*;
+use crate::modules::types::EnumType;
+use crate::modules::variable::variable_name_extensions;
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "enum"]
+#[kind = "stmt"]
+pub struct EnumDeclaration {
+    name: String,
+    tok: Option<Token>,
+}
+
+impl EnumDeclaration {
+    fn parse_variants(&self, meta: &mut ParserMetadata) -> Result<Vec<String>, Failure> {
+        let mut variants: Vec<String> = vec![];
+        token(meta, "{")?;
+        loop {
+            // Skip comments and newlines
+            if token_by(meta, |token| {
+                token.starts_with("//") || token.starts_with('\n')
+            })
+            .is_ok()
+            {
+                continue;
+            }
+            if token(meta, "}").is_ok() {
+                break;
+            }
+            let tok = meta.get_current_token();
+            let name = variable(meta, variable_name_extensions())?;
+            if variants.contains(&name) {
+                return error!(
+                    meta,
+                    tok,
+                    format!(
+                        "Variant '{name}' is already declared in enum '{}'",
+                        self.name
+                    )
+                );
+            }
+            variants.push(name);
+            if token(meta, "}").is_ok() {
+                break;
+            }
+            // Entries are separated with a comma or a line break
+            if token(meta, ",").is_err() && !is_after_line_break(meta) {
+                return error!(
+                    meta,
+                    meta.get_current_token(),
+                    "Expected ',' or '}' after enum variant"
+                );
+            }
+        }
+        Ok(variants)
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for EnumDeclaration {
+    syntax_name!("Enum Declaration");
+
+    fn new() -> Self {
+        EnumDeclaration {
+            name: String::new(),
+            tok: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        token(meta, "enum")?;
+        self.tok = meta.get_current_token();
+        self.name = variable(meta, variable_name_extensions())?;
+        if !self.name.starts_with(|chr: char| chr.is_ascii_uppercase()) {
+            return error!(meta, self.tok.clone() => {
+                message: format!("Enum name '{}' must start with an uppercase letter", self.name),
+                comment: "Enum names are written in pascal case like 'LogLevel'"
+            });
+        }
+        if meta.context.structs.contains_key(&self.name)
+            || meta.context.enums.contains_key(&self.name)
+        {
+            return error!(
+                meta,
+                self.tok.clone(),
+                format!("Type '{}' is already declared", self.name)
+            );
+        }
+        let variants = self.parse_variants(meta)?;
+        if variants.is_empty() {
+            return error!(
+                meta,
+                self.tok.clone(),
+                format!("Enum '{}' must have at least one variant", self.name)
+            );
+        }
+        // Enums are registered while parsing, so that they can be used in type annotations
+        let def = EnumType {
+            name: self.name.clone(),
+            variants,
+        };
+        meta.context.enums.insert(self.name.clone(), def);
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for EnumDeclaration {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        if !meta.is_global_scope() {
+            return error!(
+                meta,
+                self.tok.clone(),
+                "Enums can only be declared in the global scope"
+            );
+        }
+        Ok(())
+    }
+}
+
+impl TranslateModule for EnumDeclaration {
+    fn translate(&self, _meta: &mut TranslateMetadata) -> FragmentKind {
+        FragmentKind::Empty
+    }
+}
+
+crate::impl_documentation_noop!(EnumDeclaration);
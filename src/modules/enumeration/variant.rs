@@ -0,0 +1,66 @@
+use crate::modules::prelude::*;
+use crate::modules::types::{EnumType, Type, Typed};
+use crate::modules::variable::variable_name_extensions;
+use crate::translate::fragments::interpolable::InterpolablePart;
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct EnumVariant {
+    def: Option<EnumType>,
+    pub variant: String,
+}
+
+impl Typed for EnumVariant {
+    fn get_type(&self) -> Type {
+        self.def.clone().map(Type::Enum).unwrap_or_default()
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for EnumVariant {
+    syntax_name!("Enum Variant");
+
+    fn new() -> Self {
+        EnumVariant {
+            def: None,
+            variant: String::new(),
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let tok = meta.get_current_token();
+        let name = variable(meta, variable_name_extensions())?;
+        let Some(def) = meta.context.enums.get(&name).cloned() else {
+            return Err(Failure::Quiet(PositionInfo::from_token(meta, tok)));
+        };
+        token(meta, ".")?;
+        let tok = meta.get_current_token();
+        let Ok(variant) = variable(meta, variable_name_extensions()) else {
+            return error!(meta, tok, "Expected a variant name after '.'");
+        };
+        if !def.has_variant(&variant) {
+            return error!(meta, tok => {
+                message: format!("Enum '{}' has no variant '{variant}'", def.name),
+                comment: format!("Available variants are: {}", def.variants.join(", "))
+            });
+        }
+        self.variant = variant;
+        self.def = Some(def);
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for EnumVariant {
+    fn typecheck(&mut self, _meta: &mut ParserMetadata) -> SyntaxResult {
+        Ok(())
+    }
+}
+
+impl TranslateModule for EnumVariant {
+    fn translate(&self, _meta: &mut TranslateMetadata) -> FragmentKind {
+        // Variants are stored as their name, so that they can be printed as they are
+        let parts = vec![InterpolablePart::String(self.variant.clone())];
+        InterpolableFragment::new(parts, InterpolableRenderType::StringLiteral).to_frag()
+    }
+}
+
+crate::impl_documentation_noop!(EnumVariant);
@@ -2,9 +2,12 @@ use crate::modules::expression::expr::{Expr, ExprType};
 use crate::modules::prelude::*;
 use crate::modules::typecheck::TypeCheckModule;
 use crate::modules::types::{Type, Typed};
-use crate::modules::variable::{validate_index_accessor, variable_name_extensions};
+use crate::modules::variable::{
+    validate_index_accessor, validate_key_accessor, variable_name_extensions,
+};
 use crate::translate::fragments::var_stmt::VarStmtFragment;
 use crate::translate::module::TranslateModule;
+use crate::translate::nested::translate_row_access;
 use crate::utils::{ParserMetadata, TranslateMetadata};
 use heraclitus_compiler::prelude::*;
 
@@ -20,7 +23,7 @@ impl Typed for Access {
         if let Some(index) = self.index.as_ref() {
             match (&index.value, &self.kind) {
                 (Some(ExprType::Range(_)), _) => self.kind.clone(),
-                (Some(_), Type::Array(item_type)) => *item_type.clone(),
+                (Some(_), Type::Array(item_type) | Type::Map(item_type)) => *item_type.clone(),
                 _ => self.kind.clone(),
             }
         } else {
@@ -112,6 +115,10 @@ impl TypeCheckModule for Access {
 
         if let Some(ref mut index_expr) = self.index.as_mut() {
             let pos = self.left.get_position();
+            if self.kind.is_map() {
+                index_expr.typecheck(meta)?;
+                return validate_key_accessor(meta, index_expr);
+            }
             if !self.kind.is_allowed_in(&Type::array_of(Type::Generic)) {
                 return error_pos!(
                     meta,
@@ -134,22 +141,24 @@ impl TypeCheckModule for Access {
 impl TranslateModule for Access {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
         let left_frag = self.left.translate(meta);
-        match left_frag {
-            FragmentKind::VarExpr(mut var) => {
-                var.kind = self.get_type();
-                var.with_index_by_expr(meta, *self.index.clone()).to_frag()
-            }
+        let mut var = match left_frag {
+            FragmentKind::VarExpr(var) => var.with_index_by_expr(meta, *self.index.clone()),
             _ => {
                 let id = meta.gen_value_id();
                 let name = format!("access_{id}");
                 let stmt = VarStmtFragment::new(&name, self.left.get_type(), left_frag)
                     .with_ephemeral(true);
                 meta.stmt_queue.push_back(stmt.clone().to_frag());
-                let mut var = VarExprFragment::from_stmt(&stmt);
-                var.kind = self.get_type();
-                var.with_index_by_expr(meta, *self.index.clone()).to_frag()
+                VarExprFragment::from_stmt(&stmt).with_index_by_expr(meta, *self.index.clone())
             }
+        };
+        // Rows of nested arrays and structs have to be decoded before they can be used
+        if self.kind.has_encoded_rows() && !self.get_type().has_encoded_rows() {
+            var.kind = Type::Text;
+            return translate_row_access(meta, var.to_frag(), self.get_type());
         }
+        var.kind = self.get_type().into_shell_type();
+        var.to_frag()
     }
 }
 
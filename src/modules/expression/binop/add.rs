@@ -81,7 +81,12 @@ impl TranslateModule for Add {
         match self.kind {
             Type::Array(_) => {
                 let id = meta.gen_value_id();
-                let value = fragments!(left, " ", right);
+                // Arrays in POSIX sh are strings of terminated items
+                let value = if meta.target.shell.is_posix() {
+                    fragments!(left, right)
+                } else {
+                    fragments!(left, " ", right)
+                };
                 let var_stmt =
                     VarStmtFragment::new("array_add", self.kind.clone(), value).with_global_id(id);
                 meta.push_ephemeral_variable(var_stmt).to_frag()
@@ -68,9 +68,9 @@ impl TranslateModule for Eq {
         match (self.left.get_type(), self.right.get_type()) {
             (Type::Num, _) | (_, Type::Num) => translate_float_computation(meta, ArithOp::Eq, Some(left), Some(right)),
             (Type::Int, _) =>  ArithmeticFragment::new(left, ArithOp::Eq, right).to_frag(),  
-            (Type::Array(_), _) => {
+            (Type::Array(_) | Type::Struct(_), _) => {
                 if let (FragmentKind::VarExpr(left), FragmentKind::VarExpr(right)) = (left, right) {
-                    translate_array_equality(left, right, false)
+                    translate_array_equality(meta, left, right, false)
                 } else {
                     unreachable!(
                         "Arrays are always represented as variable expressions when used as values"
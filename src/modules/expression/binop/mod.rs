@@ -81,6 +81,12 @@ pub trait BinOp: SyntaxModule<ParserMetadata> + TypeCheckModule {
                     Ok(Type::Array(Box::new(Type::Generic)))
                 }
             }
+            (Type::Map(_), _) | (_, Type::Map(_)) => {
+                let pos = get_binop_position_info(meta, left, right);
+                let message =
+                    Message::new_err_at_position(meta, pos).message("Maps cannot be compared");
+                Err(Failure::Loud(message))
+            }
             (left_type, right_type) => {
                 if left_type != right_type {
                     let pos = get_binop_position_info(meta, left, right);
@@ -1,10 +1,10 @@
 use super::BinOp;
-use crate::fragments;
 use crate::modules::expression::expr::Expr;
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
 use crate::translate::compare::translate_array_equality;
 use crate::translate::compute::{translate_float_computation, ArithOp};
+use crate::{fragments, raw_fragment};
 use amber_meta::AutoKeyword;
 use heraclitus_compiler::prelude::*;
 
@@ -68,23 +68,31 @@ impl TranslateModule for Neq {
         match (self.left.get_type(), self.right.get_type()) {
             (Type::Num, _) | (_, Type::Num) => translate_float_computation(meta, ArithOp::Neq, Some(left), Some(right)),
             (Type::Int, _) =>  ArithmeticFragment::new(left, ArithOp::Neq, right).to_frag(),  
-            (Type::Array(_), _) => {
+            (Type::Array(_) | Type::Struct(_), _) => {
                 if let (FragmentKind::VarExpr(left), FragmentKind::VarExpr(right)) = (left, right) {
-                    translate_array_equality(left, right, true)
+                    translate_array_equality(meta, left, right, true)
                 } else {
                     unreachable!(
                         "Arrays are always represented as variable expressions when used as values"
                     )
                 }
             }
-            _ => SubprocessFragment::new(fragments!(
-                "[ \"_",
-                left,
-                "\" == \"_",
-                right,
-                "\" ]; echo $?"
-            ))
-            .to_frag(),
+            _ => {
+                // POSIX `test` only knows the single `=` operator
+                let operator = if meta.target.shell.is_posix() {
+                    "="
+                } else {
+                    "=="
+                };
+                SubprocessFragment::new(fragments!(
+                    "[ \"_",
+                    left,
+                    raw_fragment!("\" {operator} \"_"),
+                    right,
+                    "\" ]; echo $?"
+                ))
+                .to_frag()
+            }
         }
     }
 }
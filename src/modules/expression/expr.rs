@@ -3,8 +3,8 @@ use super::binop::{
     neq::Neq, or::Or, range::Range, sub::Sub,
 };
 use super::literal::{
-    array::Array, bool::Bool, integer::Integer, null::Null, number::Number, status::Status,
-    text::Text,
+    array::Array, bool::Bool, integer::Integer, map::Map, null::Null, number::Number,
+    status::Status, text::Text,
 };
 use super::parentheses::Parentheses;
 use super::ternop::ternary::Ternary;
@@ -17,13 +17,17 @@ use crate::modules::builtin::{
     shellversion::Shellversion,
 };
 use crate::modules::command::cmd::Command;
+use crate::modules::condition::match_expr::MatchExpression;
+use crate::modules::enumeration::variant::EnumVariant;
 use crate::modules::expression::access::Access;
 use crate::modules::expression::binop::BinOp;
 use crate::modules::expression::ternop::TernOp;
 use crate::modules::expression::typeop::TypeOp;
 use crate::modules::expression::unop::UnOp;
 use crate::modules::function::invocation::FunctionInvocation;
+use crate::modules::function::lambda::Lambda;
 use crate::modules::prelude::FragmentKind;
+use crate::modules::structure::{field::FieldAccess, literal::StructLiteral};
 use crate::modules::typecheck::TypeCheckModule;
 use crate::modules::types::parse_type;
 use crate::modules::types::{Type, Typed};
@@ -63,8 +67,10 @@ pub enum ExprType {
     Ternary(Ternary),
     LinesInvocation(LinesInvocation),
     FunctionInvocation(FunctionInvocation),
+    Lambda(Lambda),
     Command(Command),
     Array(Array),
+    Map(Map),
     Range(Range),
     Null(Null),
     Cast(Cast),
@@ -73,6 +79,10 @@ pub enum ExprType {
     Len(Len),
     Is(Is),
     Access(Access),
+    FieldAccess(FieldAccess),
+    StructLiteral(StructLiteral),
+    EnumVariant(EnumVariant),
+    MatchExpression(MatchExpression),
     Pwd(Pwd),
     Ls(Ls),
     Pid(Pid),
@@ -181,15 +191,17 @@ impl SyntaxModule<ParserMetadata> for Expr {
             multiplication @ BinOp => [ Mul, Div, Modulo ],
             types @ TypeOp => [ Is, Cast ],
             unops @ UnOp => [ Neg, Not, Len ],
-            access @ PostfixOp => [ Access ],
+            access @ PostfixOp => [ Access, FieldAccess ],
             literals @ Literal => [
                 // Literals
                 Parentheses, Bool, Number, Integer, Text,
-                Array, Null, Status, Nameof,
+                Map, Array, StructLiteral, EnumVariant, Null, Status, Nameof,
+                // Pattern matching
+                MatchExpression,
                 // Builtin invocation
                 LinesInvocation, Pwd, Ls, Pid, Shellname, Shellversion,
                 // Function invocation
-                FunctionInvocation, Command,
+                Lambda, FunctionInvocation, Command,
                 // Variable access
                 VariableGet
             ]
@@ -208,12 +220,14 @@ impl TypeCheckModule for Expr {
                 Add,
                 And,
                 Array,
+                Map,
                 Bool,
                 Cast,
                 Command,
                 Div,
                 Eq,
                 FunctionInvocation,
+                Lambda,
                 Ge,
                 Gt,
                 Integer,
@@ -239,6 +253,10 @@ impl TypeCheckModule for Expr {
                 Text,
                 VariableGet,
                 Access,
+                FieldAccess,
+                StructLiteral,
+                EnumVariant,
+                MatchExpression,
                 Pwd,
                 Ls,
                 Pid,
@@ -260,12 +278,14 @@ impl TranslateModule for Expr {
                     Add,
                     And,
                     Array,
+                    Map,
                     Bool,
                     Cast,
                     Command,
                     Div,
                     Eq,
                     FunctionInvocation,
+                    Lambda,
                     Ge,
                     Gt,
                     Integer,
@@ -291,6 +311,10 @@ impl TranslateModule for Expr {
                     Text,
                     VariableGet,
                     Access,
+                    FieldAccess,
+                    StructLiteral,
+                    EnumVariant,
+                    MatchExpression,
                     Pwd,
                     Ls,
                     Pid,
@@ -311,12 +335,14 @@ impl DocumentationModule for Expr {
                 Add,
                 And,
                 Array,
+                Map,
                 Bool,
                 Cast,
                 Command,
                 Div,
                 Eq,
                 FunctionInvocation,
+                Lambda,
                 Ge,
                 Gt,
                 Integer,
@@ -342,6 +368,10 @@ impl DocumentationModule for Expr {
                 Text,
                 VariableGet,
                 Access,
+                FieldAccess,
+                StructLiteral,
+                EnumVariant,
+                MatchExpression,
                 Pwd,
                 Ls,
                 Pid,
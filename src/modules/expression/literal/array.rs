@@ -1,6 +1,7 @@
 use crate::modules::expression::expr::Expr;
 use crate::modules::prelude::*;
 use crate::modules::types::{try_parse_type, Type, Typed};
+use crate::translate::nested::translate_row_encoding;
 use heraclitus_compiler::prelude::*;
 
 #[derive(Debug, Clone)]
@@ -15,6 +16,13 @@ impl Typed for Array {
     }
 }
 
+impl Array {
+    // Empty arrays can be placed as rows of any nested array
+    fn is_item_compatible(item: &Type, expected: &Type) -> bool {
+        item == expected || (expected.is_array() && *item == Type::array_of(Type::Generic))
+    }
+}
+
 impl SyntaxModule<ParserMetadata> for Array {
     syntax_name!("Array");
 
@@ -32,52 +40,63 @@ impl SyntaxModule<ParserMetadata> for Array {
             self.kind = Type::Array(Box::new(Type::Generic));
             return Ok(());
         }
-        // Try to parse array type
+        // Try to parse array type like `[Int]`, but leave `[[]]` to be parsed
+        // as an array holding a single empty array
+        let index = meta.get_index();
         match try_parse_type(meta) {
-            Ok(kind) => {
-                if matches!(kind, Type::Array(_)) {
-                    return error!(
-                        meta,
-                        tok, "Arrays cannot be nested due to the Bash limitations"
-                    );
-                }
-                if matches!(kind, Type::Union(_)) {
-                    return error!(meta, tok, "Arrays don't support mixed type values");
+            Ok(kind) if kind != Type::array_of(Type::Generic) && token(meta, "]").is_ok() => {
+                match kind {
+                    Type::Array(ref inner) if !inner.is_scalar() => {
+                        return error!(
+                            meta,
+                            tok,
+                            "Arrays can only be nested one level deep due to the Bash limitations"
+                        );
+                    }
+                    Type::Map(_) => {
+                        return error!(
+                            meta,
+                            tok, "Arrays cannot contain maps due to the Bash limitations"
+                        );
+                    }
+                    Type::Union(_) => {
+                        return error!(meta, tok, "Arrays don't support mixed type values");
+                    }
+                    _ => {}
                 }
                 self.kind = Type::Array(Box::new(kind));
-                token(meta, "]")?;
+                return Ok(());
             }
             Err(Failure::Loud(err)) => return Err(Failure::Loud(err)),
             // Parse the array values
-            Err(Failure::Quiet(_)) => {
-                loop {
-                    // Skip comments and newlines
-                    if token_by(meta, |token| {
-                        token.starts_with("//") || token.starts_with('\n')
-                    })
-                    .is_ok()
-                    {
-                        continue;
-                    }
-                    if token(meta, "]").is_ok() {
-                        break;
-                    }
-                    // Parse array value
-                    let mut value = Expr::new();
-                    syntax(meta, &mut value)?;
-                    let tok = meta.get_current_token();
-                    if token(meta, "]").is_ok() {
-                        self.exprs.push(value);
-                        break;
-                    }
-                    if token(meta, ",").is_ok() {
-                        self.exprs.push(value);
-                        continue;
-                    }
-                    return error!(meta, tok, "Expected ',' or ']' after array value");
-                }
+            _ => meta.set_index(index),
+        }
+        loop {
+            // Skip comments and newlines
+            if token_by(meta, |token| {
+                token.starts_with("//") || token.starts_with('\n')
+            })
+            .is_ok()
+            {
+                continue;
             }
-        };
+            if token(meta, "]").is_ok() {
+                break;
+            }
+            // Parse array value
+            let mut value = Expr::new();
+            syntax(meta, &mut value)?;
+            let tok = meta.get_current_token();
+            if token(meta, "]").is_ok() {
+                self.exprs.push(value);
+                break;
+            }
+            if token(meta, ",").is_ok() {
+                self.exprs.push(value);
+                continue;
+            }
+            return error!(meta, tok, "Expected ',' or ']' after array value");
+        }
         Ok(())
     }
 }
@@ -88,12 +107,22 @@ impl TypeCheckModule for Array {
         for expr in &mut self.exprs {
             expr.typecheck(meta)?;
             // Handle nested arrays
-            if expr.get_type().is_array() {
+            if let Type::Array(inner) = expr.get_type() {
+                if !inner.is_scalar() && *inner != Type::Generic {
+                    let pos = expr.get_position();
+                    return error_pos!(
+                        meta,
+                        pos,
+                        "Arrays can only be nested one level deep due to the Bash limitations"
+                    );
+                }
+            }
+            if expr.get_type().is_map() {
                 let pos = expr.get_position();
                 return error_pos!(
                     meta,
                     pos,
-                    "Arrays cannot be nested due to the Bash limitations"
+                    "Arrays cannot contain maps due to the Bash limitations"
                 );
             }
         }
@@ -106,14 +135,20 @@ impl TypeCheckModule for Array {
 
         match self.kind {
             Type::Generic => {
-                // Infer type from first element
-                self.kind = Type::Array(Box::new(self.exprs[0].get_type()));
+                // Infer type from the first element that is not an empty array
+                let kind = self
+                    .exprs
+                    .iter()
+                    .map(|expr| expr.get_type())
+                    .find(|kind| *kind != Type::array_of(Type::Generic))
+                    .unwrap_or_else(|| self.exprs[0].get_type());
+                self.kind = Type::Array(Box::new(kind));
             }
             Type::Array(ref expected_type) => {
                 // Type already specified, validate all elements match
                 for expr in &self.exprs {
                     let expr_type = expr.get_type();
-                    if expr_type != **expected_type {
+                    if !Self::is_item_compatible(&expr_type, expected_type) {
                         let pos = expr.get_position();
                         return error_pos!(
                             meta,
@@ -128,9 +163,9 @@ impl TypeCheckModule for Array {
 
         // Validate all elements have the same type
         if let Type::Array(ref element_type) = self.kind {
-            for expr in &self.exprs[1..] {
+            for expr in &self.exprs {
                 let expr_type = expr.get_type();
-                if expr_type != **element_type {
+                if !Self::is_item_compatible(&expr_type, element_type) {
                     let pos = expr.get_position();
                     return error_pos!(
                         meta,
@@ -144,19 +179,48 @@ impl TypeCheckModule for Array {
             }
         }
 
+        if self.kind.is_nested_array() && meta.nested_array_usage.is_none() {
+            meta.nested_array_usage = Some(self.exprs[0].get_position());
+        }
+        let is_struct_array = matches!(self.kind, Type::Array(ref inner) if inner.is_struct());
+        if is_struct_array && meta.struct_array_usage.is_none() {
+            meta.struct_array_usage = Some(self.exprs[0].get_position());
+        }
         Ok(())
     }
 }
 
+/// Joins the items into the value of an array variable
+pub fn translate_array_items(meta: &TranslateMetadata, items: Vec<FragmentKind>) -> FragmentKind {
+    if meta.target.shell.is_posix() {
+        // Every item is terminated with the unit separator
+        let separator = VarExprFragment::new("__US", Type::Text).to_frag();
+        let items = items
+            .into_iter()
+            .flat_map(|item| [item, separator.clone()])
+            .collect();
+        ListFragment::new(items).to_frag()
+    } else {
+        ListFragment::new(items).with_spaces().to_frag()
+    }
+}
+
 impl TranslateModule for Array {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
         let id = meta.gen_value_id();
         let args = self
             .exprs
             .iter()
-            .map(|expr| expr.translate_eval(meta, false))
+            .map(|expr| {
+                if self.kind.has_encoded_rows() {
+                    let row = expr.translate(meta);
+                    translate_row_encoding(meta, row, expr.get_type())
+                } else {
+                    expr.translate_eval(meta, false)
+                }
+            })
             .collect::<Vec<FragmentKind>>();
-        let args = ListFragment::new(args).with_spaces().to_frag();
+        let args = translate_array_items(meta, args);
         let var_stmt = VarStmtFragment::new("array", self.kind.clone(), args).with_global_id(id);
         meta.push_ephemeral_variable(var_stmt).to_frag()
     }
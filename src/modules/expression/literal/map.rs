@@ -0,0 +1,165 @@
+use crate::fragments;
+use crate::modules::expression::expr::Expr;
+use crate::modules::prelude::*;
+use crate::modules::types::{try_parse_type, Type, Typed};
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct Map {
+    entries: Vec<(Expr, Expr)>,
+    kind: Type,
+}
+
+impl Typed for Map {
+    fn get_type(&self) -> Type {
+        self.kind.clone()
+    }
+}
+
+impl Map {
+    // Looks ahead for a colon after the first key to tell maps apart from arrays
+    fn is_map_literal(meta: &ParserMetadata) -> bool {
+        let mut depth = 0;
+        for tok in meta.context.expr.iter().skip(meta.get_index() + 1) {
+            match tok.word.as_str() {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" if depth == 0 => return false,
+                ")" | "]" | "}" => depth -= 1,
+                "," if depth == 0 => return false,
+                ":" if depth == 0 => return true,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// Translates the entries to the `[key]=value` pairs of an associative array
+    pub fn translate_entries(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let entries = self
+            .entries
+            .iter()
+            .map(|(key, value)| {
+                let key = key.translate(meta);
+                let value = value.translate_eval(meta, false);
+                fragments!("[", key, "]=", value)
+            })
+            .collect::<Vec<FragmentKind>>();
+        ListFragment::new(entries).with_spaces().to_frag()
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for Map {
+    syntax_name!("Map");
+
+    fn new() -> Self {
+        Map {
+            entries: vec![],
+            kind: Type::Generic,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let tok = meta.get_current_token();
+        if tok.as_ref().map(|tok| tok.word.as_str()) != Some("[") || !Self::is_map_literal(meta) {
+            return Err(Failure::Quiet(PositionInfo::from_token(meta, tok)));
+        }
+        // Empty map with explicit type like `[Text: Int]`
+        if let Ok(kind) = try_parse_type(meta) {
+            self.kind = kind;
+            return Ok(());
+        }
+        token(meta, "[")?;
+        if meta.map_usage.is_none() {
+            meta.map_usage = Some(PositionInfo::from_token(meta, tok));
+        }
+        loop {
+            // Skip comments and newlines
+            if token_by(meta, |token| {
+                token.starts_with("//") || token.starts_with('\n')
+            })
+            .is_ok()
+            {
+                continue;
+            }
+            if token(meta, "]").is_ok() {
+                break;
+            }
+            // Parse map entry
+            let mut key = Expr::new();
+            syntax(meta, &mut key)?;
+            let tok = meta.get_current_token();
+            if token(meta, ":").is_err() {
+                return error!(meta, tok, "Expected ':' after map key");
+            }
+            let mut value = Expr::new();
+            syntax(meta, &mut value)?;
+            self.entries.push((key, value));
+            let tok = meta.get_current_token();
+            if token(meta, "]").is_ok() {
+                break;
+            }
+            if token(meta, ",").is_ok() {
+                continue;
+            }
+            return error!(meta, tok, "Expected ',' or ']' after map value");
+        }
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for Map {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        for (key, value) in &mut self.entries {
+            key.typecheck(meta)?;
+            if key.get_type() != Type::Text {
+                let pos = key.get_position();
+                return error_pos!(
+                    meta,
+                    pos,
+                    format!("Map keys must be of type 'Text', not '{}'", key.get_type())
+                );
+            }
+            value.typecheck(meta)?;
+            let value_type = value.get_type();
+            if value_type.is_array() || value_type.is_map() || value_type.is_struct() {
+                let pos = value.get_position();
+                return error_pos!(
+                    meta,
+                    pos,
+                    "Map values cannot be arrays, maps or structs due to the Bash limitations"
+                );
+            }
+        }
+
+        if let Some((_, first)) = self.entries.first() {
+            let value_type = first.get_type();
+            for (_, value) in &self.entries[1..] {
+                if value.get_type() != value_type {
+                    let pos = value.get_position();
+                    return error_pos!(
+                        meta,
+                        pos,
+                        format!(
+                            "Map values must have the same type. Expected '{}', found '{}'",
+                            value_type,
+                            value.get_type()
+                        )
+                    );
+                }
+            }
+            self.kind = Type::map_of(value_type);
+        }
+        Ok(())
+    }
+}
+
+impl TranslateModule for Map {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let id = meta.gen_value_id();
+        let entries = self.translate_entries(meta);
+        let var_stmt = VarStmtFragment::new("map", self.kind.clone(), entries).with_global_id(id);
+        meta.push_ephemeral_variable(var_stmt).to_frag()
+    }
+}
+
+crate::impl_documentation_noop!(Map);
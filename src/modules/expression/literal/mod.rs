@@ -1,6 +1,7 @@
 pub mod array;
 pub mod bool;
 pub mod integer;
+pub mod map;
 pub mod null;
 pub mod number;
 pub mod status;
@@ -34,6 +34,7 @@ impl SyntaxModule<ParserMetadata> for Status {
             let warning = Message::new_warn_at_token(meta, tok)
                 .message("Calling status without parentheses is deprecated");
             meta.add_message(warning);
+            meta.add_parenless_call(position, &[]);
         }
         Ok(())
     }
@@ -34,6 +34,10 @@ impl TextPart {
                 TextPart::Expr(expr) => {
                     let frag = expr.translate(meta).with_quotes(false);
                     if let FragmentKind::VarExpr(var) = frag {
+                        // Arrays in POSIX sh are not joined by the shell itself
+                        let is_joined = meta.target.shell.is_posix() && var.kind.is_array();
+                        let is_joined = var.is_array_to_string || is_joined;
+                        let var = var.with_array_to_string(is_joined);
                         InterpolablePart::Interp(var.to_frag())
                     } else {
                         InterpolablePart::Interp(frag)
@@ -103,6 +103,14 @@ impl TypeCheckModule for Ternary {
         let true_type = true_expr.get_type();
         let false_type = false_expr.get_type();
 
+        if true_type.is_map() || false_type.is_map() {
+            let pos = get_binop_position_info(meta, true_expr, false_expr);
+            let msg = Message::new_err_at_position(meta, pos)
+                .message("Ternary operation cannot evaluate to a map")
+                .comment("Maps cannot be copied. Use an 'if' statement instead.");
+            return Err(Failure::Loud(msg));
+        }
+
         if true_type == false_type {
             self.kind = true_type;
         } else {
@@ -155,7 +163,7 @@ impl TranslateModule for Ternary {
                     .as_ref()
                     .map(|e| e.get_type())
                     .unwrap_or(Type::Null);
-                let is_array = true_type.is_array();
+                let is_array = true_type.is_array() || true_type.is_struct();
                 let cond = self.cond.translate(meta);
                 let true_expr = self
                     .true_expr
@@ -53,6 +53,29 @@ impl TypeCheckModule for Cast {
         self.expr.typecheck(meta)?;
 
         let pos = self.expr.get_position();
+        let expr_type = self.expr.get_type();
+        if (expr_type.is_map() || self.kind.is_map()) && expr_type != self.kind {
+            return error_pos!(meta, pos => {
+                message: format!("Cannot cast a value of type '{expr_type}' to '{}'", self.kind),
+                comment: "Maps cannot be cast to other types"
+            });
+        }
+        if (expr_type.is_struct() || self.kind.is_struct()) && expr_type != self.kind {
+            return error_pos!(meta, pos => {
+                message: format!("Cannot cast a value of type '{expr_type}' to '{}'", self.kind),
+                comment: "Structs cannot be cast to other types"
+            });
+        }
+        let is_enum_to_text = expr_type.is_enum() && self.kind == Type::Text;
+        if (expr_type.is_enum() || self.kind.is_enum())
+            && expr_type != self.kind
+            && !is_enum_to_text
+        {
+            return error_pos!(meta, pos => {
+                message: format!("Cannot cast a value of type '{expr_type}' to '{}'", self.kind),
+                comment: "Enums can only be cast to 'Text'. Use a 'match' to turn text into an enum variant"
+            });
+        }
         if !meta.context.cc_flags.contains(&CCFlags::AllowAbsurdCast) {
             let flag_name = get_ccflag_name(CCFlags::AllowAbsurdCast);
             let l_type = self.expr.get_type();
@@ -1,19 +1,20 @@
 use super::declaration_utils::*;
-use crate::fragments;
 use crate::modules::expression::expr::Expr;
 use crate::modules::prelude::*;
-use crate::modules::statement::comment_doc::CommentDoc;
+use crate::modules::statement::comment_doc::{warn_missing_docs, CommentDoc};
 use crate::modules::typecheck::TypeCheckModule;
 use crate::modules::types::parse_type;
 use crate::modules::types::{Type, Typed};
 use crate::modules::variable::variable_name_extensions;
 use crate::raw_fragment;
+use crate::translate::fragments::function::InlineSignature;
 use crate::translate::fragments::get_variable_name;
 use crate::utils::cc_flags::{get_ccflag_by_name, CCFlags};
 use crate::utils::context::Context;
 use crate::utils::function_cache::FunctionInstance;
 use crate::utils::function_interface::FunctionInterface;
 use crate::utils::function_metadata::FunctionMetadata;
+use crate::utils::symbol_table::{SymbolDefinition, SymbolKind};
 use crate::utils::ShellType;
 use heraclitus_compiler::prelude::*;
 use itertools::{izip, Itertools};
@@ -21,6 +22,7 @@ use std::collections::HashSet;
 use std::ffi::OsStr;
 use std::path::Path;
 
+use crate::docs::item::{DocsArgument, DocsFunction};
 use crate::modules::block::Block;
 
 use amber_meta::AutoKeyword;
@@ -30,6 +32,8 @@ pub struct FunctionDeclarationArgument {
     pub name: String,
     pub kind: Type,
     pub optional: Option<Expr>,
+    /// Source code of the default value as written in the signature
+    pub optional_source: Option<String>,
     pub is_ref: bool,
     pub tok: Option<Token>,
 }
@@ -59,6 +63,8 @@ pub struct FunctionDeclaration {
     pub is_failable: bool,
     /// Whether function was declared as failable
     pub declared_failable: bool,
+    /// Whether function contains defer statements
+    pub uses_defer: bool,
     /// Token for function name (for error positioning)
     pub name_token: Option<Token>,
 }
@@ -76,8 +82,8 @@ impl FunctionDeclaration {
             {
                 let name = get_variable_name(&arg.name, *global_id);
                 match (arg.is_ref, kind) {
-                    // Copy array arguments into a function-local array variable.
-                    (false, Type::Array(_)) => {
+                    // Copy array and struct arguments into a function-local array variable.
+                    (false, Type::Array(_) | Type::Struct(_)) if !meta.target.shell.is_posix() => {
                         // ksh cannot copy a caller-local array through indirect expansion, so bind
                         // the argument name as a nameref first and then copy from that local alias.
                         if matches!(meta.target.shell, ShellType::Ksh) {
@@ -115,8 +121,8 @@ impl FunctionDeclaration {
                             result.push(var.to_frag())
                         }
                     }
-                    // Bind array `ref` arguments so the function can mutate the caller-owned array.
-                    (true, Type::Array(_)) => {
+                    // Bind array, map and struct `ref` arguments so the function can mutate the caller-owned value.
+                    (true, Type::Array(_) | Type::Map(_) | Type::Struct(_)) => {
                         let val = VarExprFragment::new(&format!("{}", index + 1), Type::Generic)
                             .with_ref(false);
 
@@ -127,7 +133,8 @@ impl FunctionDeclaration {
                                 .with_ref(false)
                                 .with_array_ref(true)
                                 .with_declared(false)
-                        } else if meta.target.shell.is_bash_legacy() {
+                        } else if meta.target.shell.is_bash_legacy() || meta.target.shell.is_posix()
+                        {
                             VarStmtFragment::new(&name, kind.clone(), val.to_frag())
                                 .with_local(true)
                                 .with_optimization_when_unused(false)
@@ -145,6 +152,7 @@ impl FunctionDeclaration {
                         result.push(var.to_frag())
                     }
                     // Bind scalar arguments as local variables, preserving `ref` semantics when requested.
+                    // Arrays and structs in POSIX sh are strings, so they are bound the same way.
                     _ => {
                         let val = VarExprFragment::new(&format!("{}", index + 1), Type::Generic)
                             .with_ref(false);
@@ -166,6 +174,191 @@ impl FunctionDeclaration {
         }
     }
 
+    /// Describes the variant for the optimizer if its body can replace its calls
+    fn get_inline_signature(
+        &self,
+        meta: &TranslateMetadata,
+        function: &FunctionInstance,
+    ) -> Option<InlineSignature> {
+        // Failures, deferred blocks and values passed by name need the function scope
+        let is_inlinable = !self.is_failable
+            && !self.uses_defer
+            && (function.returns.is_scalar() || function.returns == Type::Null)
+            && izip!(self.args.iter(), &function.args)
+                .all(|(arg, kind)| !arg.is_ref && kind.is_scalar());
+        if !is_inlinable {
+            return None;
+        }
+        let params = izip!(self.args.iter(), &function.args, &function.args_global_ids)
+            .map(|(arg, kind, global_id)| {
+                let name = get_variable_name(&arg.name, *global_id);
+                VarStmtFragment::new(&name, kind.clone(), FragmentKind::Empty)
+                    .with_optimization_when_unused(false)
+            })
+            .collect();
+        let return_name = meta.fun_meta.as_ref()?.mangled_name();
+        Some(InlineSignature {
+            params,
+            return_name,
+        })
+    }
+
+    /// Parses the arguments, the return type and the body of the function
+    pub fn parse_signature_and_body(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        // Get the arguments
+        token(meta, "(")?;
+        loop {
+            // Skip comments and newlines
+            if token_by(meta, |token| {
+                token.starts_with("//") || token.starts_with('\n')
+            })
+            .is_ok()
+            {
+                continue;
+            }
+            if token(meta, ")").is_ok() {
+                break;
+            }
+            let is_ref = token(meta, "ref").is_ok();
+            let name_token = meta.get_current_token();
+            let name = variable(meta, variable_name_extensions())?;
+
+            // Optionally parse the argument type
+            let arg_type = match token(meta, ":") {
+                Ok(_) => parse_type(meta)?,
+                Err(_) => Type::Generic,
+            };
+
+            // Optionally parse default value
+            let (optional_expr, optional_source) = match token(meta, "=") {
+                Ok(_) => {
+                    let start = meta.get_index();
+                    let mut expr = Expr::new();
+                    syntax(meta, &mut expr)?;
+                    (Some(expr), Some(self.render_default_value(meta, start)))
+                }
+                Err(_) => (None, None),
+            };
+
+            self.args.push(FunctionDeclarationArgument {
+                name,
+                kind: arg_type,
+                optional: optional_expr,
+                optional_source,
+                is_ref,
+                tok: name_token,
+            });
+            match token(meta, ")") {
+                Ok(_) => break,
+                Err(_) => token(meta, ",")?,
+            };
+        }
+        let mut returns_tok = None;
+        let mut question_tok = None;
+        // Optionally parse the return type
+        match token(meta, ":") {
+            Ok(_) => {
+                returns_tok = meta.get_current_token();
+                self.returns = parse_type(meta)?;
+                question_tok = meta.get_current_token();
+                if token(meta, "?").is_ok() {
+                    self.declared_failable = true;
+                }
+            }
+            Err(_) => self.returns = Type::Generic,
+        }
+        // Parse the body
+        let start_pos = meta.get_index();
+        token(meta, "{")?;
+        let (_, _, is_failable) = skip_function_body(meta);
+        meta.set_index(start_pos);
+
+        self.is_failable = is_failable;
+        if self.returns == Type::Generic {
+            self.declared_failable = is_failable;
+        }
+
+        // Validate failable function declarations
+        if is_failable && !self.declared_failable {
+            return error!(
+                meta,
+                returns_tok, "Failable functions must have a '?' after the type name"
+            );
+        }
+        if !is_failable && self.declared_failable {
+            return error!(
+                meta,
+                question_tok.or(returns_tok),
+                "Infallible functions must not have a '?' after the type name"
+            );
+        }
+
+        // Store function body for typecheck phase
+        let mut block = Block::new().with_condition();
+        let was_fun_ctx = meta.context.is_fun_ctx;
+        let was_defer_used = std::mem::take(&mut meta.defer_used);
+        meta.context.is_fun_ctx = true;
+        let result = meta.with_context_fn(Context::set_cc_flags, self.flags.clone(), |meta| {
+            meta.with_context_fn(Context::set_is_defer_ctx, false, |meta| {
+                syntax(meta, &mut block)
+            })
+        });
+        meta.context.is_fun_ctx = was_fun_ctx;
+        self.uses_defer = meta.defer_used;
+        meta.defer_used = was_defer_used;
+        result?;
+        self.function_body = Some(block);
+        Ok(())
+    }
+
+    /// Checks the argument names and typechecks the default values
+    pub fn validate_arguments(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        // Check for duplicate argument names
+        let mut seen_argument_names = HashSet::new();
+        for arg in &self.args {
+            if !seen_argument_names.insert(arg.name.clone()) {
+                return error!(
+                    meta,
+                    arg.tok.clone(),
+                    format!("Argument '{}' is already defined", arg.name)
+                );
+            }
+        }
+
+        // Validate optional arguments
+        // Typecheck and validate optional arguments
+        let mut optional_started = false;
+        for arg in &mut self.args {
+            if let Some(ref mut expr) = arg.optional {
+                // Check if ref arguments are optional
+                if arg.is_ref {
+                    return error!(meta, arg.tok.clone(), "A ref cannot be optional");
+                }
+
+                // Typecheck the optional argument expression first
+                expr.typecheck(meta)?;
+
+                // Validate optional argument type
+                if !expr.get_type().is_allowed_in(&arg.kind) {
+                    return error!(
+                        meta,
+                        arg.tok.clone(),
+                        "Optional argument does not match annotated type"
+                    );
+                }
+
+                optional_started = true;
+            } else if optional_started {
+                return error!(
+                    meta,
+                    arg.tok.clone(),
+                    "All arguments following an optional argument must also be optional"
+                );
+            }
+        }
+        Ok(())
+    }
+
     fn get_space(&self, parentheses: usize, before: &str, word: &str) -> String {
         if parentheses == 0 && word == "("
             || word == ":"
@@ -181,6 +374,20 @@ impl FunctionDeclaration {
         " ".to_string()
     }
 
+    /// Renders the default value of an argument the same way as the rest of the signature
+    fn render_default_value(&self, meta: &ParserMetadata, start: usize) -> String {
+        let mut result = String::new();
+        let mut before = String::new();
+        for token in &meta.context.expr[start..meta.get_index()] {
+            if !result.is_empty() {
+                result.push_str(&self.get_space(1, &before, &token.word));
+            }
+            before.clone_from(&token.word);
+            result.push_str(&token.word);
+        }
+        result
+    }
+
     fn render_function_signature(
         &self,
         meta: &ParserMetadata,
@@ -233,6 +440,7 @@ impl SyntaxModule<ParserMetadata> for FunctionDeclaration {
             function_body: None,
             is_failable: false,
             declared_failable: false,
+            uses_defer: false,
             name_token: None,
         }
     }
@@ -259,108 +467,13 @@ impl SyntaxModule<ParserMetadata> for FunctionDeclaration {
         // Get the function name
         self.name_token = meta.get_current_token();
         self.name = variable(meta, variable_name_extensions())?;
-        let mut optional = false;
+        if self.is_public && self.comment.is_none() {
+            let item = format!("public function '{}'", self.name);
+            warn_missing_docs(meta, self.name_token.clone(), item, &self.flags);
+        }
         context!(
             {
-                // Get the arguments
-                token(meta, "(")?;
-                loop {
-                    // Skip comments and newlines
-                    if token_by(meta, |token| {
-                        token.starts_with("//") || token.starts_with('\n')
-                    })
-                    .is_ok()
-                    {
-                        continue;
-                    }
-                    if token(meta, ")").is_ok() {
-                        break;
-                    }
-                    let is_ref = token(meta, "ref").is_ok();
-                    let name_token = meta.get_current_token();
-                    let name = variable(meta, variable_name_extensions())?;
-
-                    // Optionally parse the argument type
-                    let arg_type = match token(meta, ":") {
-                        Ok(_) => parse_type(meta)?,
-                        Err(_) => Type::Generic,
-                    };
-
-                    // Optionally parse default value
-                    let optional_expr = match token(meta, "=") {
-                        Ok(_) => {
-                            optional = true;
-                            let mut expr = Expr::new();
-                            syntax(meta, &mut expr)?;
-                            Some(expr)
-                        }
-                        Err(_) => None,
-                    };
-
-                    self.args.push(FunctionDeclarationArgument {
-                        name,
-                        kind: arg_type,
-                        optional: optional_expr,
-                        is_ref,
-                        tok: name_token,
-                    });
-                    match token(meta, ")") {
-                        Ok(_) => break,
-                        Err(_) => token(meta, ",")?,
-                    };
-                }
-                let mut returns_tok = None;
-                let mut question_tok = None;
-                // Optionally parse the return type
-                match token(meta, ":") {
-                    Ok(_) => {
-                        returns_tok = meta.get_current_token();
-                        self.returns = parse_type(meta)?;
-                        question_tok = meta.get_current_token();
-                        if token(meta, "?").is_ok() {
-                            self.declared_failable = true;
-                        }
-                    }
-                    Err(_) => self.returns = Type::Generic,
-                }
-                // Parse the body
-                let start_pos = meta.get_index();
-                token(meta, "{")?;
-                let (_, _, is_failable) = skip_function_body(meta);
-                meta.set_index(start_pos);
-
-                self.is_failable = is_failable;
-                if self.returns == Type::Generic {
-                    self.declared_failable = is_failable;
-                }
-
-                // Validate failable function declarations
-                if is_failable && !self.declared_failable {
-                    return error!(
-                        meta,
-                        returns_tok, "Failable functions must have a '?' after the type name"
-                    );
-                }
-                if !is_failable && self.declared_failable {
-                    return error!(
-                        meta,
-                        question_tok.or(returns_tok),
-                        "Infallible functions must not have a '?' after the type name"
-                    );
-                }
-
-                // Store function body for typecheck phase
-                let mut block = Block::new().with_condition();
-                let was_fun_ctx = meta.context.is_fun_ctx;
-                meta.context.is_fun_ctx = true;
-                let result =
-                    meta.with_context_fn(Context::set_cc_flags, self.flags.clone(), |meta| {
-                        syntax(meta, &mut block)
-                    });
-                meta.context.is_fun_ctx = was_fun_ctx;
-                result?;
-                self.function_body = Some(block);
-
+                self.parse_signature_and_body(meta)?;
                 self.doc_signature = Some(self.render_function_signature(meta, doc_index)?);
                 Ok(())
             },
@@ -390,49 +503,7 @@ impl TypeCheckModule for FunctionDeclaration {
         handle_existing_function(meta, self.name_token.clone())?;
 
         meta.with_context_fn(Context::set_cc_flags, self.flags.clone(), |meta| {
-            // Check for duplicate argument names
-            let mut seen_argument_names = HashSet::new();
-            for arg in &self.args {
-                if !seen_argument_names.insert(arg.name.clone()) {
-                    return error!(
-                        meta,
-                        arg.tok.clone(),
-                        format!("Argument '{}' is already defined", arg.name)
-                    );
-                }
-            }
-
-            // Validate optional arguments
-            // Typecheck and validate optional arguments
-            let mut optional_started = false;
-            for arg in &mut self.args {
-                if let Some(ref mut expr) = arg.optional {
-                    // Check if ref arguments are optional
-                    if arg.is_ref {
-                        return error!(meta, arg.tok.clone(), "A ref cannot be optional");
-                    }
-
-                    // Typecheck the optional argument expression first
-                    expr.typecheck(meta)?;
-
-                    // Validate optional argument type
-                    if !expr.get_type().is_allowed_in(&arg.kind) {
-                        return error!(
-                            meta,
-                            arg.tok.clone(),
-                            "Optional argument does not match annotated type"
-                        );
-                    }
-
-                    optional_started = true;
-                } else if optional_started {
-                    return error!(
-                        meta,
-                        arg.tok.clone(),
-                        "All arguments following an optional argument must also be optional"
-                    );
-                }
-            }
+            self.validate_arguments(meta)?;
 
             // Create function context and add to memory
             let block = self.function_body.clone().unwrap_or_else(Block::new);
@@ -455,6 +526,23 @@ impl TypeCheckModule for FunctionDeclaration {
                 block,
             )?;
 
+            let definition = SymbolDefinition {
+                name: self.name.clone(),
+                kind: SymbolKind::Function,
+                detail: self
+                    .doc_signature
+                    .as_deref()
+                    .unwrap_or_default()
+                    .trim_end()
+                    .to_string(),
+                doc: self
+                    .comment
+                    .as_ref()
+                    .map(|comment| comment.value.trim().to_string()),
+                position: Some(PositionInfo::from_token(meta, self.name_token.clone())),
+            };
+            meta.symbol_table.add_function(self.id, definition);
+
             Ok(())
         })
     }
@@ -465,10 +553,15 @@ impl TranslateModule for FunctionDeclaration {
         let mut result = vec![];
         let blocks = meta.fun_cache.get_instances_cloned(self.id).unwrap();
         let prev_fun_meta = meta.fun_meta.clone();
+        let prev_defer_mark = meta.defer_mark.take();
         // Get the variable prefix based on function name casing
         let prefix = meta.gen_variable_prefix(&self.name);
         // Translate each one of them
         for (index, function) in blocks.iter().enumerate() {
+            // Skip the variants that are never invoked
+            if !meta.is_reachable(self.id, index) {
+                continue;
+            }
             meta.fun_meta = Some(FunctionMetadata::new(
                 &self.name,
                 self.id,
@@ -481,24 +574,40 @@ impl TranslateModule for FunctionDeclaration {
                 .map(|(arg, ty)| format!("{}: {}", arg.name, ty))
                 .join(", ");
             let function_name = &self.name;
-            result.push(raw_fragment!("# {function_name}({argument_types})"));
+            let header = vec![raw_fragment!("# {function_name}({argument_types})")];
 
             // Parse the function body
-            let name = raw_fragment!("{}{}__{}_v{}", prefix, self.name, self.id, index);
-            // required for the local scope in ksh
-            if matches!(meta.target.shell, ShellType::Ksh) {
-                result.push(fragments!("function ", name, " {"));
-            } else {
-                result.push(fragments!(name, "() {"));
+            let mangled_name = format!("{}{}__{}_v{}", prefix, self.name, self.id, index);
+            let args = self.set_args_as_variables(meta, function);
+            let mut body = vec![];
+            // Remember which deferred blocks were pushed before the function was called
+            meta.defer_mark = self
+                .uses_defer
+                .then(|| format!("__defers_{}", meta.gen_value_id()));
+            if let Some(mark) = &meta.defer_mark {
+                let stmt = VarStmtFragment::new(
+                    mark,
+                    Type::Text,
+                    raw_fragment!("\"${{__amber_defers}}\""),
+                )
+                .with_local(true)
+                .with_optimization_when_unused(false);
+                body.push(BlockFragment::new(vec![stmt.to_frag()], true).to_frag());
             }
-            if let Some(args) = self.set_args_as_variables(meta, function) {
-                result.push(args);
+            body.push(function.block.translate(meta));
+            if let Some(mark) = &meta.defer_mark {
+                let run = raw_fragment!("__amber_defers_run \"${{{mark}}}\" \"$?\"");
+                body.push(BlockFragment::new(vec![run], true).to_frag());
             }
-            result.push(function.block.translate(meta));
-            result.push(fragments!("}\n"));
+            let inline = self.get_inline_signature(meta, function);
+            let fragment = FunctionFragment::new(&mangled_name, (self.id, index), header, body)
+                .with_args(args)
+                .with_inline(inline);
+            result.push(fragment.to_frag());
         }
         // Restore the function name
         meta.fun_meta = prev_fun_meta;
+        meta.defer_mark = prev_defer_mark;
         // Return the translation
         BlockFragment::new(result, false).to_frag()
     }
@@ -522,6 +631,44 @@ impl DocumentationModule for FunctionDeclaration {
 }
 
 impl FunctionDeclaration {
+    /// Name of the public function and whether it has a doc comment.
+    /// Functions that allow missing docs are left out.
+    pub fn get_public_item(&self) -> Option<(String, bool)> {
+        if !self.is_public || self.flags.contains(&CCFlags::AllowMissingDocs) {
+            return None;
+        }
+        Some((self.name.clone(), self.comment.is_some()))
+    }
+
+    /// Collects the documentation of the function for the generated docs site
+    pub fn get_docs_item(&self, meta: &ParserMetadata) -> DocsFunction {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| DocsArgument {
+                name: arg.name.clone(),
+                kind: (arg.kind != Type::Generic).then(|| arg.kind.to_string()),
+                is_ref: arg.is_ref,
+                default: arg.optional_source.clone(),
+            })
+            .collect();
+        let comment = self.comment.as_ref().map(|comment| {
+            let comment_text = comment.document(meta);
+            self.insert_usage_import_statement(meta, comment_text)
+                .trim_end()
+                .to_string()
+        });
+        DocsFunction {
+            name: self.name.clone(),
+            args,
+            returns: (self.returns != Type::Generic).then(|| self.returns.to_string()),
+            is_public: self.is_public,
+            is_failable: self.declared_failable,
+            comment,
+            position: self.name_token.as_ref().map(|token| token.pos),
+        }
+    }
+
     fn insert_usage_import_statement(
         &self,
         meta: &ParserMetadata,
@@ -11,12 +11,27 @@ pub fn skip_function_body(meta: &mut ParserMetadata) -> (usize, usize, bool) {
     let index_begin = meta.get_index();
     let mut is_failable = false;
     let mut scope = 1;
+    // Failures inside of nested lambdas do not make this function failable
+    let mut is_lambda_pending = false;
+    let mut lambda_scopes = vec![];
     while let Some(tok) = meta.get_current_token() {
+        let is_in_lambda = is_lambda_pending || !lambda_scopes.is_empty();
         match tok.word.as_str() {
-            "{" => scope += 1,
-            "}" => scope -= 1,
-            "fail" => is_failable = true,
-            "?" => is_failable = true,
+            "{" => {
+                scope += 1;
+                if is_lambda_pending {
+                    is_lambda_pending = false;
+                    lambda_scopes.push(scope);
+                }
+            }
+            "}" => {
+                if lambda_scopes.last() == Some(&scope) {
+                    lambda_scopes.pop();
+                }
+                scope -= 1;
+            }
+            "fun" => is_lambda_pending = true,
+            "fail" | "?" if !is_in_lambda => is_failable = true,
             _ => {}
         }
         if scope == 0 {
@@ -76,24 +91,33 @@ pub fn handle_existing_function(
     handle_symbol_scope_declaration(meta, &name, tok)
 }
 
-pub fn handle_add_function(
+pub fn handle_argument_types(
     meta: &mut ParserMetadata,
     tok: Option<Token>,
-    fun: FunctionInterface,
-    ctx: Context,
-    block: Block,
-) -> Result<usize, Failure> {
-    let name = fun.name.clone();
-    handle_identifier_name(meta, &name, tok.clone())?;
+    fun: &FunctionInterface,
+) -> Result<(), Failure> {
     let any_generic = fun.args.iter().any(|arg| arg.kind == Type::Generic);
     let any_typed = fun.args.iter().any(|arg| arg.kind != Type::Generic);
     // Either all arguments are generic or typed
     if any_typed && any_generic {
         return error!(meta, tok => {
-            message: format!("Function '{}' has a mix of generic and typed arguments", name),
+            message: format!("Function '{}' has a mix of generic and typed arguments", fun.name),
             comment: "Please decide whether to use generics or types for all arguments"
         });
     }
+    Ok(())
+}
+
+pub fn handle_add_function(
+    meta: &mut ParserMetadata,
+    tok: Option<Token>,
+    fun: FunctionInterface,
+    ctx: Context,
+    block: Block,
+) -> Result<usize, Failure> {
+    let name = fun.name.clone();
+    handle_identifier_name(meta, &name, tok.clone())?;
+    handle_argument_types(meta, tok.clone(), &fun)?;
     // Try to add the function to the memory
     match meta.add_fun_declaration(fun, ctx, block) {
         // Return the id of the function
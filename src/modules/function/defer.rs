@@ -0,0 +1,117 @@
+use crate::modules::block::Block;
+use crate::modules::prelude::*;
+use crate::translate::module::TranslateModule;
+use crate::utils::context::Context;
+use crate::utils::metadata::{ParserMetadata, TranslateMetadata};
+use crate::{fragments, raw_fragment};
+use amber_meta::AutoKeyword;
+use heraclitus_compiler::prelude::*;
+use itertools::Itertools;
+
+/// Block that runs when the enclosing function or main block exits.
+/// The block reads the variables when it runs, so the blocks deferred
+/// in a loop see the values of the last iteration.
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "defer"]
+#[kind = "stmt"]
+pub struct Defer {
+    block: Box<Block>,
+    /// Identifiers used in the block that may refer to the variables outside of it
+    identifiers: Vec<String>,
+    tok: Option<Token>,
+}
+
+impl SyntaxModule<ParserMetadata> for Defer {
+    syntax_name!("Defer");
+
+    fn new() -> Self {
+        Defer {
+            block: Box::new(Block::new().with_needs_noop()),
+            identifiers: vec![],
+            tok: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let tok = meta.get_current_token();
+        token(meta, "defer")?;
+        self.tok = tok.clone();
+        if !meta.context.is_fun_ctx && !meta.context.is_main_ctx {
+            return error!(meta, tok => {
+                message: "Defer statement outside of function or main",
+                comment: "Deferred blocks run when the enclosing function or the main block exits"
+            });
+        }
+        if meta.context.is_defer_ctx {
+            return error!(meta, tok, "Defer statements cannot be nested");
+        }
+        meta.defer_used = true;
+        let start = meta.get_index();
+        meta.with_context_fn(Context::set_is_defer_ctx, true, |meta| {
+            syntax(meta, &mut *self.block)
+        })?;
+        let tokens = &meta.context.expr[start..meta.get_index()];
+        self.identifiers = tokens.iter().map(|tok| tok.word.clone()).unique().collect();
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for Defer {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        if meta.context.is_loop_ctx {
+            let variable = self
+                .identifiers
+                .iter()
+                .find(|name| meta.get_var(name).is_some());
+            if let Some(name) = variable {
+                let warning = Message::new_warn_at_token(meta, self.tok.clone())
+                    .message(format!(
+                        "Deferred block reads variable '{name}' when the function exits"
+                    ))
+                    .comment("Blocks deferred in a loop see the values of the last iteration");
+                meta.add_message(warning);
+            }
+        }
+        // Loops surrounding the defer statement have finished by the time the block runs
+        meta.with_context_fn(Context::set_is_loop_ctx, false, |meta| {
+            self.block.typecheck(meta)
+        })
+    }
+}
+
+impl TranslateModule for Defer {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        // The block becomes a function that is pushed on top of the stack of deferred blocks
+        meta.defer_used = true;
+        let name = format!("__defer_{}", meta.gen_value_id());
+        let block = meta.with_expr_ctx(false, |meta| self.block.translate(meta));
+        BlockFragment::new(
+            vec![
+                raw_fragment!("{name}() {{"),
+                block,
+                raw_fragment!("}}"),
+                raw_fragment!("__amber_defers=\"{name} ${{__amber_defers}}\""),
+            ],
+            false,
+        )
+        .to_frag()
+    }
+}
+
+/// Returns from the current function with the given status. Functions that
+/// defer blocks run the ones they pushed to the stack before returning.
+pub fn translate_defer_return(meta: &TranslateMetadata, status: FragmentKind) -> FragmentKind {
+    match &meta.defer_mark {
+        Some(mark) => BlockFragment::new(
+            vec![
+                fragments!(raw_fragment!("__amber_defers_run \"${{{mark}}}\" "), status),
+                raw_fragment!("return $?"),
+            ],
+            false,
+        )
+        .to_frag(),
+        None => fragments!("return ", status),
+    }
+}
+
+crate::impl_documentation_noop!(Defer);
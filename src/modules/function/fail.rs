@@ -1,10 +1,12 @@
 use crate::modules::expression::expr::Expr;
+use crate::modules::function::defer::translate_defer_return;
 use crate::modules::prelude::FragmentKind;
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
+use crate::raw_fragment;
 use crate::translate::module::TranslateModule;
+use crate::translate::stack_trace::{translate_trace_exit, translate_trace_step, TraceStep};
 use crate::utils::metadata::{ParserMetadata, TranslateMetadata};
-use crate::{fragments, raw_fragment};
 use amber_meta::AutoKeyword;
 use heraclitus_compiler::prelude::*;
 
@@ -14,6 +16,7 @@ pub struct Fail {
     pub expr: Expr,
     pub code: String,
     pub is_main: bool,
+    position: Option<PositionInfo>,
 }
 
 impl Typed for Fail {
@@ -30,12 +33,20 @@ impl SyntaxModule<ParserMetadata> for Fail {
             expr: Expr::new(),
             code: String::new(),
             is_main: false,
+            position: None,
         }
     }
 
     fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.position = Some(PositionInfo::from_metadata(meta));
         token(meta, "fail")?;
         let tok = meta.get_current_token();
+        if meta.context.is_defer_ctx {
+            return error!(meta, tok => {
+                message: "Fail statement inside of a defer block",
+                comment: "Deferred blocks run while the function is already returning"
+            });
+        }
         if !meta.context.is_fun_ctx && !meta.context.is_main_ctx {
             return error!(meta, tok => {
                 message: "Fail statement outside of function or main",
@@ -98,8 +109,12 @@ impl TranslateModule for Fail {
         } else {
             raw_fragment!("{}", &self.code)
         };
+        let trace = translate_trace_step(meta, TraceStep::Start, self.position.as_ref());
+        if !matches!(trace, FragmentKind::Empty) {
+            meta.stmt_queue.push_back(trace);
+        }
         if self.is_main {
-            fragments!("exit ", translate)
+            translate_trace_exit(meta, translate)
         } else {
             // Clean the return value if the function fails
             let fun_meta = meta
@@ -113,7 +128,7 @@ impl TranslateModule for Fail {
             )
             .with_optimization_when_unused(false);
             meta.stmt_queue.push_back(stmt.to_frag());
-            fragments!("return ", translate)
+            translate_defer_return(meta, translate)
         }
     }
 }
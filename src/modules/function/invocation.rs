@@ -1,4 +1,5 @@
 use super::invocation_utils::*;
+use crate::fragments;
 use crate::modules::command::modifier::CommandModifier;
 use crate::modules::condition::failure_handler::FailureHandler;
 use crate::modules::expression::expr::{Expr, ExprType};
@@ -6,7 +7,6 @@ use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
 use crate::modules::variable::variable_name_extensions;
 use crate::utils::ShellType;
-use crate::{fragments, raw_fragment};
 use heraclitus_compiler::prelude::*;
 use itertools::izip;
 
@@ -121,9 +121,26 @@ impl TypeCheckModule for FunctionInvocation {
             }
 
             // Look up the function declaration (this requires typecheck phase context)
-            self.id = handle_function_reference(meta, self.name_tok.clone(), &self.name)?;
-
-            let function_unit = meta.get_fun_declaration(&self.name).unwrap().clone();
+            let function_unit = match handle_function_value_reference(meta, self.name_tok.clone(), &self.name)? {
+                FunctionValue::Known(function_unit) => function_unit,
+                // The function value is not known until the enclosing function gets its arguments
+                FunctionValue::Unknown(returns) => {
+                    self.kind = returns;
+                    if self.failure_handler.is_parsed {
+                        self.failure_handler.typecheck(meta)?;
+                    }
+                    return Ok(());
+                }
+                FunctionValue::None => {
+                    handle_function_reference(meta, self.name_tok.clone(), &self.name)?;
+                    let function_unit = meta.get_fun_declaration(&self.name).unwrap().clone();
+                    let definition = meta.symbol_table.resolve_function(&function_unit);
+                    let position = PositionInfo::from_token(meta, self.name_tok.clone());
+                    meta.symbol_table.add_reference(position, definition);
+                    function_unit
+                }
+            };
+            self.id = function_unit.id;
             let expected_arg_count = function_unit.args.len();
             let actual_arg_count = self.args.len();
             let optional_count = function_unit.args.iter().filter(|arg| arg.optional.is_some()).count();
@@ -189,7 +206,7 @@ impl TypeCheckModule for FunctionInvocation {
                 self.failure_handler.typecheck(meta)?;
             } else if self.failure_handler.is_parsed && !meta.context.is_trust_ctx {
                 let message = Message::new_warn_at_token(meta, self.name_tok.clone())
-                    .message(format!("Function '{}' cannot fail", &self.name))
+                    .message(format!("Function '{}' cannot fail", self.name))
                     .comment("You can remove the failure handler block or '?' at the end");
                 meta.add_message(message);
             }
@@ -201,12 +218,24 @@ impl TypeCheckModule for FunctionInvocation {
 
 impl TranslateModule for FunctionInvocation {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        // Functions called through a variable have a different name than the declaration
+        let fun_name = match meta.fun_cache.get_declaration(self.id) {
+            Some(decl) => decl.name.clone(),
+            None => self.name.clone(),
+        };
         // Get the variable prefix based on function name casing
-        let prefix = meta.gen_variable_prefix(&self.name);
-        let name = raw_fragment!("{}{}__{}_v{}", prefix, self.name, self.id, self.variant_id);
+        let prefix = meta.gen_variable_prefix(&fun_name);
+        let name = RawFragment::from(format!(
+            "{}{}__{}_v{}",
+            prefix, fun_name, self.id, self.variant_id
+        ))
+        .with_function((self.id, self.variant_id))
+        .to_frag();
         meta.with_silenced(self.modifier.is_silent || meta.silenced, |meta| {
             let silent = meta.gen_silent().to_frag();
             let suppress = meta.gen_suppress().to_frag();
+            // Arrays in POSIX sh are plain strings, so they are passed by value
+            let is_posix = meta.target.shell.is_posix();
             let args = izip!(self.args.iter(), self.refs.iter())
                 .map(|(arg, is_ref)| match arg.translate(meta) {
                     // Pass explicit `ref` arguments as shell references so the callee can alias them directly.
@@ -215,7 +244,9 @@ impl TranslateModule for FunctionInvocation {
                         .with_array_ref(matches!(meta.target.shell, ShellType::Zsh))
                         .to_frag(),
                     // Materialize indexed array expressions first so the callee receives a stable temporary array name.
-                    FragmentKind::VarExpr(var) if var.kind.is_array() && var.index.is_some() => {
+                    FragmentKind::VarExpr(var)
+                        if var.kind.is_array() && var.index.is_some() && !is_posix =>
+                    {
                         let id = meta.gen_value_id();
                         let temp_name = format!("{}_{id}", var.get_index_typename());
                         let stmt = VarStmtFragment::new(
@@ -241,7 +272,7 @@ impl TranslateModule for FunctionInvocation {
                         }
                     }
                     // Pass whole arrays by name so each shell can lower array copy semantics in its own way.
-                    FragmentKind::VarExpr(var) if var.kind.is_array() => {
+                    FragmentKind::VarExpr(var) if var.kind.is_array() && !is_posix => {
                         if matches!(meta.target.shell, ShellType::Ksh) {
                             if var.is_ref {
                                 // In ksh93, forwarding a ref-bound local array into a by-copy array
@@ -291,14 +322,12 @@ impl TranslateModule for FunctionInvocation {
         }
         if self.kind != Type::Null {
             // Get the variable prefix for return values
-            let prefix = meta.gen_variable_prefix(&self.name);
-            let invocation_return = format!(
-                "{}ret_{}{}_v{}",
-                prefix, self.name, self.id, self.variant_id
-            );
+            let prefix = meta.gen_variable_prefix(&fun_name);
+            let invocation_return =
+                format!("{}ret_{}{}_v{}", prefix, fun_name, self.id, self.variant_id);
             let invocation_instance = format!(
                 "{}ret_{}{}_v{}__{}_{}",
-                prefix, self.name, self.id, self.variant_id, self.line, self.col
+                prefix, fun_name, self.id, self.variant_id, self.line, self.col
             );
             let parsed_invocation_return =
                 VarExprFragment::new(&invocation_return, self.kind.clone()).to_frag();
@@ -1,5 +1,6 @@
 use crate::modules::typecheck::TypeCheckModule;
-use crate::modules::types::Type;
+use crate::modules::types::{FunctionType, Type};
+use crate::modules::variable::handle_variable_reference;
 use crate::utils::context::{FunctionDecl, VariableDecl, VariableDeclWarn};
 use crate::utils::{pluralize, ParserMetadata};
 use heraclitus_compiler::prelude::*;
@@ -108,6 +109,9 @@ pub fn run_function_with_args(
 
     // Suppress warnings only after first-pass typechecking with declared types has emitted them
     let suppress = meta.fun_cache.is_first_pass_done(fun.id);
+    // Collect the function instances that the body of this instance invokes
+    let caller_invoked = std::mem::take(&mut meta.invoked_functions);
+    let caller_bash_only = std::mem::take(&mut meta.bash_only_command);
     let res = meta.with_suppress_warnings(suppress, |meta| {
         // Swap the contexts to use the function context
         meta.with_context_ref(&mut context, |meta| {
@@ -120,7 +124,13 @@ pub fn run_function_with_args(
                     let var = VariableDecl::new(arg.name.clone(), kind.clone())
                         .with_warn(VariableDeclWarn::from_token(meta, tok.clone()))
                         .with_ref(arg.is_ref);
-                    args_global_ids.push(meta.add_var(var));
+                    let global_id = meta.add_var(var);
+                    // Point editor tooling to the argument itself rather than the call site
+                    if let (Some(id), Some(arg_tok)) = (global_id, arg.tok.clone()) {
+                        let position = PositionInfo::from_token(meta, Some(arg_tok));
+                        meta.symbol_table.set_variable_position(id, position);
+                    }
+                    args_global_ids.push(global_id);
                 }
                 // Set the expected return type if specified
                 if fun.returns != Type::Generic {
@@ -150,6 +160,19 @@ pub fn run_function_with_args(
         })
     });
 
+    let invokes = std::mem::replace(&mut meta.invoked_functions, caller_invoked);
+    let is_bash_only = std::mem::replace(&mut meta.bash_only_command, caller_bash_only);
+    if is_bash_only {
+        // Report the call in the user code rather than the calls within the standard library
+        let is_std_caller = caller_path
+            .as_ref()
+            .is_some_and(|path| path.starts_with("std/"));
+        if is_std_caller {
+            meta.bash_only_command = true;
+        } else if meta.bash_only_usage.is_none() {
+            meta.bash_only_usage = call_site_pos.map(|pos| (pos, fun.name.clone()));
+        }
+    }
     meta.parsing_functions.remove(&(fun.id, args.to_vec()));
     res?;
 
@@ -165,7 +188,7 @@ pub fn run_function_with_args(
     if persist {
         Ok((
             fun.returns.clone(),
-            meta.add_fun_instance(fun.into_interface(), args_global_ids, block),
+            meta.add_fun_instance(fun.into_interface(), args_global_ids, block, invokes),
         ))
     } else {
         Ok((fun.returns.clone(), 0))
@@ -191,6 +214,48 @@ pub fn handle_function_reference(
     }
 }
 
+/// Function that is invoked through a variable holding a function value
+pub enum FunctionValue {
+    /// Declaration of the function that the value refers to
+    Known(FunctionDecl),
+    /// Value that will be known once the enclosing function is called with
+    /// concrete arguments. It holds the return type of the signature.
+    Unknown(Type),
+    /// There is no variable to call the function through
+    None,
+}
+
+pub fn handle_function_value_reference(
+    meta: &mut ParserMetadata,
+    tok: Option<Token>,
+    name: &str,
+) -> Result<FunctionValue, Failure> {
+    // Declared functions take precedence over variables
+    if meta.get_fun_declaration(name).is_some() || meta.get_var(name).is_none() {
+        return Ok(FunctionValue::None);
+    }
+    let var = handle_variable_reference(meta, &tok, name)?;
+    match var.kind {
+        Type::Function(FunctionType { id: Some(id), .. }) => {
+            let fun = meta.fun_cache.get_declaration(id).cloned();
+            Ok(fun.map_or(FunctionValue::None, FunctionValue::Known))
+        }
+        Type::Function(FunctionType { returns, .. }) if meta.first_pass_ctx => {
+            Ok(FunctionValue::Unknown(*returns))
+        }
+        Type::Generic if meta.first_pass_ctx => Ok(FunctionValue::Unknown(Type::Generic)),
+        Type::Function(_) => error!(
+            meta,
+            tok,
+            format!("Function value '{name}' is not known at compile time")
+        ),
+        kind => error!(meta, tok => {
+            message: format!("Variable '{name}' of type '{kind}' cannot be called"),
+            comment: "Only variables holding functions can be called"
+        }),
+    }
+}
+
 pub fn handle_function_parameters(
     meta: &mut ParserMetadata,
     id: usize,
@@ -208,37 +273,49 @@ pub fn handle_function_parameters(
             let ordinal = ordinal_number(index);
             return error!(meta, tok, format!("Cannot pass {ordinal} argument '{arg_name}' as a reference to the function '{fun_name}' because it is not a variable"));
         }
+        if !is_ref && args.get(index).is_some_and(Type::is_map) {
+            let fun_name = &fun.name;
+            let ordinal = ordinal_number(index);
+            return error!(meta, tok => {
+                message: format!("Cannot pass {ordinal} argument '{arg_name}' to the function '{fun_name}' by value because it is a map"),
+                comment: format!("Maps cannot be copied. Declare the parameter as 'ref {arg_name}' to pass the map by reference")
+            });
+        }
     }
 
     // On first invocation, run first-pass with declared types (or Generic) to emit correct warnings.
-    if !meta.fun_cache.is_first_pass_done(id) {
-        let declared_types: Vec<Type> = fun.args.iter().map(|arg| arg.kind.clone()).collect();
-        // We set persist to false, because we don't want to cache the function instance
-        let _ = meta.with_first_pass_ctx(true, |meta| {
-            run_function_with_args(meta, fun.clone(), &declared_types, tok.clone(), false)
-        });
-        meta.fun_cache.set_first_pass_done(id);
-    }
+    run_first_pass(meta, id, &fun, tok.clone());
 
     // If the function was previously called with the same arguments, return the cached variant
-    let result = match meta
+    let (kind, variant_id) = match meta
         .fun_cache
         .get_instances(id)
         .unwrap()
         .iter()
         .find(|fun| fun.args == args)
     {
-        Some(fun) => Ok((fun.returns.clone(), fun.variant_id)),
-        None => Ok(run_function_with_args(
-            meta,
-            fun,
-            args,
-            tok,
-            !meta.first_pass_ctx,
-        )?),
+        Some(fun) => (fun.returns.clone(), fun.variant_id),
+        None => run_function_with_args(meta, fun, args, tok, !meta.first_pass_ctx)?,
     };
+    meta.add_invoked_function(id, variant_id);
+    Ok((kind, variant_id))
+}
 
-    result
+/// Typechecks the function once with the declared types (or Generic) to emit correct warnings
+pub fn run_first_pass(
+    meta: &mut ParserMetadata,
+    id: usize,
+    fun: &FunctionDecl,
+    tok: Option<Token>,
+) {
+    if !meta.fun_cache.is_first_pass_done(id) {
+        let declared_types: Vec<Type> = fun.args.iter().map(|arg| arg.kind.clone()).collect();
+        // We set persist to false, because we don't want to cache the function instance
+        let _ = meta.with_first_pass_ctx(true, |meta| {
+            run_function_with_args(meta, fun.clone(), &declared_types, tok.clone(), false)
+        });
+        meta.fun_cache.set_first_pass_done(id);
+    }
 }
 
 fn handle_similar_function(meta: &ParserMetadata, name: &str) -> Option<String> {
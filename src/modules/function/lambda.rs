@@ -0,0 +1,104 @@
+use super::declaration::FunctionDeclaration;
+use super::declaration_utils::handle_argument_types;
+use super::invocation_utils::run_first_pass;
+use crate::modules::block::Block;
+use crate::modules::prelude::*;
+use crate::modules::types::{FunctionType, Type, Typed};
+use crate::raw_fragment;
+use crate::utils::function_interface::FunctionInterface;
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct Lambda {
+    function: Box<FunctionDeclaration>,
+    kind: Type,
+}
+
+impl Typed for Lambda {
+    fn get_type(&self) -> Type {
+        self.kind.clone()
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for Lambda {
+    syntax_name!("Lambda");
+
+    fn new() -> Self {
+        Lambda {
+            function: Box::new(FunctionDeclaration::new()),
+            kind: Type::Null,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let tok = meta.get_current_token();
+        token(meta, "fun")?;
+        // Named functions are parsed as declarations
+        if meta.get_current_token().map_or(true, |tok| tok.word != "(") {
+            return Err(Failure::Quiet(PositionInfo::from_token(meta, tok)));
+        }
+        self.function.name = "lambda".to_string();
+        self.function.name_token = tok;
+        context!({ self.function.parse_signature_and_body(meta) }, |pos| {
+            error_pos!(meta, pos, "Failed to parse lambda")
+        })?;
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for Lambda {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let function = &mut self.function;
+        function.validate_arguments(meta)?;
+        let interface = FunctionInterface {
+            id: None,
+            name: function.name.clone(),
+            args: function.args.clone(),
+            returns: function.returns.clone(),
+            is_public: false,
+            is_failable: function.is_failable,
+        };
+        handle_argument_types(meta, function.name_token.clone(), &interface)?;
+
+        // Lambdas see the variables of the scope they are created in
+        let block = function.function_body.clone().unwrap_or_else(Block::new);
+        let mut ctx = meta.context.clone();
+        ctx.captured_scopes = ctx.is_fun_ctx.then_some(ctx.scopes.len());
+        ctx.lambda_scopes = Some(ctx.scopes.len());
+        ctx.is_fun_ctx = true;
+        ctx.is_loop_ctx = false;
+        ctx.is_main_ctx = false;
+        ctx.is_trust_ctx = false;
+        ctx.fun_ret_type = None;
+        ctx.expr.clear();
+        function.id = meta.add_lambda_declaration(interface, ctx, block);
+
+        // The body is typechecked on a copy of the context, so the variables it references
+        // are marked in the scopes of the lambda once the first pass is done
+        let outer_captured = std::mem::take(&mut meta.captured_vars);
+        if let Some(decl) = meta.fun_cache.get_declaration(function.id).cloned() {
+            run_first_pass(meta, function.id, &decl, function.name_token.clone());
+        }
+        let captured = std::mem::replace(&mut meta.captured_vars, outer_captured);
+        meta.mark_captured_vars(captured);
+
+        self.kind = Type::Function(FunctionType {
+            id: Some(function.id),
+            args: function.args.iter().map(|arg| arg.kind.clone()).collect(),
+            returns: Box::new(function.returns.clone()),
+        });
+        Ok(())
+    }
+}
+
+impl TranslateModule for Lambda {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        // Define the function right before the expression that uses it
+        let definition = meta.with_expr_ctx(false, |meta| self.function.translate(meta));
+        meta.stmt_queue.push_back(definition);
+        let prefix = meta.gen_variable_prefix(&self.function.name);
+        raw_fragment!("{}{}__{}", prefix, self.function.name, self.function.id)
+    }
+}
+
+crate::impl_documentation_noop!(Lambda);
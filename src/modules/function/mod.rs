@@ -1,6 +1,9 @@
 pub mod declaration;
 pub mod declaration_utils;
+pub mod defer;
 pub mod fail;
 pub mod invocation;
 pub mod invocation_utils;
+pub mod lambda;
 pub mod ret;
+pub mod signal;
@@ -1,7 +1,8 @@
-use crate::fragments;
 use crate::modules::expression::expr::Expr;
+use crate::modules::function::defer::translate_defer_return;
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
+use crate::raw_fragment;
 use crate::utils::function_metadata::FunctionMetadata;
 use amber_meta::AutoKeyword;
 use heraclitus_compiler::prelude::*;
@@ -26,7 +27,14 @@ impl SyntaxModule<ParserMetadata> for Return {
     }
 
     fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let tok = meta.get_current_token();
         token(meta, "return")?;
+        if meta.context.is_defer_ctx {
+            return error!(meta, tok => {
+                message: "Return statement inside of a defer block",
+                comment: "Deferred blocks run while the function is already returning"
+            });
+        }
         if !meta.context.is_fun_ctx {
             let tok = meta.get_current_token();
             return error!(meta, tok => {
@@ -42,6 +50,20 @@ impl SyntaxModule<ParserMetadata> for Return {
 impl TypeCheckModule for Return {
     fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
         self.expr.typecheck(meta)?;
+        if self.expr.get_type().is_map() {
+            let pos = self.expr.get_position();
+            return error_pos!(meta, pos => {
+                message: "Functions cannot return maps",
+                comment: "Pass the map to the function by reference and modify it instead"
+            });
+        }
+        if self.expr.get_type().is_function() {
+            let pos = self.expr.get_position();
+            return error_pos!(meta, pos => {
+                message: "Functions cannot return other functions",
+                comment: "Lambdas would lose access to the captured variables. Call the function where it is needed instead"
+            });
+        }
 
         let ret_type = meta.context.fun_ret_type.as_ref();
         let expr_type = &self.expr.get_type();
@@ -74,7 +96,7 @@ impl TranslateModule for Return {
         let var_stmt = VarStmtFragment::new(&fun_name, self.expr.get_type(), result)
             .with_optimization_when_unused(false);
         meta.stmt_queue.push_back(var_stmt.to_frag());
-        fragments!("return 0")
+        translate_defer_return(meta, raw_fragment!("0"))
     }
 }
 
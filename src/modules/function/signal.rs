@@ -0,0 +1,155 @@
+use crate::modules::block::Block;
+use crate::modules::prelude::*;
+use crate::raw_fragment;
+use crate::translate::module::TranslateModule;
+use crate::utils::context::Context;
+use crate::utils::metadata::{ParserMetadata, TranslateMetadata};
+use amber_meta::AutoKeyword;
+use heraclitus_compiler::prelude::*;
+use itertools::Itertools;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Signal {
+    Interrupt,
+    Terminate,
+    Hangup,
+    Exit,
+}
+
+impl Signal {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "SIGINT" => Some(Signal::Interrupt),
+            "SIGTERM" => Some(Signal::Terminate),
+            "SIGHUP" => Some(Signal::Hangup),
+            "EXIT" => Some(Signal::Exit),
+            _ => None,
+        }
+    }
+
+    /// Condition name that `trap` accepts in every shell (POSIX sh rejects the `SIG` prefix)
+    fn trap_condition(&self) -> &'static str {
+        match self {
+            Signal::Interrupt => "INT",
+            Signal::Terminate => "TERM",
+            Signal::Hangup => "HUP",
+            Signal::Exit => "EXIT",
+        }
+    }
+
+    /// Exit code of a shell killed by the signal, which is 128 plus the signal number
+    fn exit_code(&self) -> Option<u8> {
+        match self {
+            Signal::Hangup => Some(129),
+            Signal::Interrupt => Some(130),
+            Signal::Terminate => Some(143),
+            Signal::Exit => None,
+        }
+    }
+}
+
+/// Runs the block when the script receives one of the signals. Once the handler of
+/// SIGINT, SIGTERM or SIGHUP returns, the script exits with 128 plus the signal number
+/// as if it was killed by the signal, unless the signals are followed by `resume`.
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "on"]
+#[kind = "stmt"]
+pub struct OnSignal {
+    signals: Vec<Signal>,
+    /// Whether the script continues after the handler of a signal returns
+    /// instead of exiting with the code of the signal
+    is_resumed: bool,
+    block: Box<Block>,
+}
+
+impl SyntaxModule<ParserMetadata> for OnSignal {
+    syntax_name!("Signal Handler");
+
+    fn new() -> Self {
+        OnSignal {
+            signals: vec![],
+            is_resumed: false,
+            block: Box::new(Block::new().with_needs_noop()),
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let tok = meta.get_current_token();
+        token(meta, "on")?;
+        token(meta, "signal")?;
+        if meta.context.is_fun_ctx {
+            return error!(meta, tok => {
+                message: "Signal handlers cannot be registered inside of functions",
+                comment: "Handlers can run after the function has returned. Register the handler in the main block instead"
+            });
+        }
+        loop {
+            let signal_tok = meta.get_current_token();
+            let name = token_by(meta, |word| word.chars().all(|c| c.is_ascii_alphanumeric()))?;
+            match Signal::from_name(&name) {
+                Some(signal) if !self.signals.contains(&signal) => self.signals.push(signal),
+                Some(_) => {
+                    return error!(
+                        meta,
+                        signal_tok,
+                        format!("Signal '{name}' is handled twice")
+                    );
+                }
+                None => {
+                    return error!(meta, signal_tok => {
+                        message: format!("Unknown signal '{name}'"),
+                        comment: "Supported signals are SIGINT, SIGTERM, SIGHUP and EXIT"
+                    });
+                }
+            }
+            if token(meta, ",").is_err() {
+                break;
+            }
+        }
+        self.is_resumed = token(meta, "resume").is_ok();
+        syntax(meta, &mut *self.block)
+    }
+}
+
+impl TypeCheckModule for OnSignal {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        meta.with_context_fn(Context::set_is_loop_ctx, false, |meta| {
+            self.block.typecheck(meta)
+        })
+    }
+}
+
+impl TranslateModule for OnSignal {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        // The handler is a function so that the trap command needs no quoting of the block
+        let name = format!("__signal_{}", meta.gen_value_id());
+        let block = meta.with_expr_ctx(false, |meta| self.block.translate(meta));
+        let mut result = vec![raw_fragment!("{name}() {{"), block, raw_fragment!("}}")];
+        // Exit handlers have no exit code and are not trapped as signals
+        let signals = self
+            .signals
+            .iter()
+            .filter_map(|signal| Some((signal.trap_condition(), signal.exit_code()?)))
+            .collect::<Vec<_>>();
+        if self.is_resumed && !signals.is_empty() {
+            let conditions = signals.iter().map(|(condition, _)| condition).join(" ");
+            result.push(raw_fragment!("trap '{name}' {conditions}"));
+        }
+        if !self.is_resumed {
+            // Like a shell without the trap, the script stops once the handler returns
+            for (condition, code) in signals {
+                result.push(raw_fragment!("trap '{name}; exit {code}' {condition}"));
+            }
+        }
+        if self.signals.contains(&Signal::Exit) {
+            // Exit handlers are run by the exit trap along with the deferred blocks
+            meta.defer_used = true;
+            result.push(raw_fragment!(
+                "__amber_defers=\"{name} ${{__amber_defers}}\""
+            ));
+        }
+        BlockFragment::new(result, false).to_frag()
+    }
+}
+
+crate::impl_documentation_noop!(OnSignal);
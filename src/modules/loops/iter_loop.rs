@@ -9,7 +9,9 @@ use crate::modules::prelude::{FragmentKind, RawFragment};
 use crate::modules::types::{Type, Typed};
 use crate::modules::variable::variable_name_extensions;
 use crate::translate::fragments::get_variable_name;
+use crate::translate::fragments::var_expr::VarIndexValue;
 use crate::translate::module::TranslateModule;
+use crate::translate::nested::translate_row_decoding;
 use crate::utils::context::{Context, VariableDecl, VariableDeclWarn};
 use crate::utils::metadata::{ParserMetadata, TranslateMetadata};
 use crate::{fragments, raw_fragment};
@@ -76,7 +78,23 @@ impl TranslateModule for IterLoop {
             }
         }
 
-        let iter_name_str = get_variable_name(&self.iter_name, self.iter_global_id);
+        let map_var = match self.iter_expr.get_type() {
+            Type::Map(_) => match self.iter_expr.translate(meta) {
+                FragmentKind::VarExpr(var) => Some(var),
+                _ => unreachable!("Maps are always represented as variable expressions"),
+            },
+            _ => None,
+        };
+
+        let iter_name_str = match (&map_var, &self.iter_index) {
+            // Keys of the map are assigned to the first of the two variables
+            (Some(_), Some(key)) => get_variable_name(key, self.iter_index_global_id),
+            // Rows of nested arrays and structs are iterated in their encoded form
+            _ if self.iter_expr.get_type().has_encoded_rows() => {
+                format!("__row_encoded_{}", meta.gen_value_id())
+            }
+            _ => get_variable_name(&self.iter_name, self.iter_global_id),
+        };
         let iter_name = raw_fragment!("{}", iter_name_str);
 
         let fifo_var = format!("__AMBER_FIFO_{}", meta.gen_value_id());
@@ -133,13 +151,35 @@ impl TranslateModule for IterLoop {
                 )
                 .to_frag()
             }
-            None => fragments!(
-                "for ",
-                iter_name,
-                " in ",
-                self.iter_expr.translate(meta),
-                "; do"
-            ),
+            // Items of arrays in POSIX sh are cut one by one from the front of the string
+            None if meta.target.shell.is_posix() => {
+                let iter_expr = self.iter_expr.translate(meta);
+                let rest = format!("__rest_{}", meta.gen_value_id());
+                BlockFragment::new(
+                    vec![
+                        fragments!(raw_fragment!("{rest}="), iter_expr),
+                        raw_fragment!("while [ -n \"${{{rest}}}\" ]; do"),
+                        BlockFragment::new(
+                            vec![
+                                raw_fragment!("{iter_name_str}=\"${{{rest}%%\"$__US\"*}}\""),
+                                raw_fragment!("{rest}=\"${{{rest}#*\"$__US\"}}\""),
+                            ],
+                            true,
+                        )
+                        .to_frag(),
+                    ],
+                    false,
+                )
+                .to_frag()
+            }
+            None => {
+                let iter_expr = match &map_var {
+                    // Maps are iterated over their keys
+                    Some(var) => var.clone().with_keys_getter(true).to_frag(),
+                    None => self.iter_expr.translate(meta),
+                };
+                fragments!("for ", iter_name, " in ", iter_expr, "; do")
+            }
         };
 
         let for_loop_suffix = match iter_lines {
@@ -156,27 +196,63 @@ impl TranslateModule for IterLoop {
             None => fragments!("done"),
         };
 
+        let block = self.block.translate(meta);
+        // Rows of nested arrays and structs are decoded at the beginning of each iteration
+        let block = if self.iter_expr.get_type().has_encoded_rows() {
+            let row =
+                VarStmtFragment::new(&self.iter_name, self.iter_type.clone(), FragmentKind::Empty)
+                    .with_global_id(self.iter_global_id)
+                    .with_optimization_when_unused(false);
+            let decoding = translate_row_decoding(&row.get_name(), &iter_name_str);
+            let decoding = BlockFragment::new(vec![row.to_frag(), decoding], true).to_frag();
+            BlockFragment::new(vec![decoding, block], false).to_frag()
+        } else {
+            block
+        };
+
         match (self.iter_index.as_ref(), self.iter_index_global_id) {
+            // The first variable holds the key and the second one the value of the map entry
+            (Some(key), global_id) if map_var.is_some() => {
+                let key = get_variable_name(key, global_id);
+                let value = map_var
+                    .unwrap()
+                    .with_index_by_value(VarIndexValue::Key(raw_fragment!("\"${{{key}}}\"")))
+                    .to_frag();
+                let value = VarStmtFragment::new(&self.iter_name, self.iter_type.clone(), value)
+                    .with_global_id(self.iter_global_id)
+                    .with_optimization_when_unused(false);
+                BlockFragment::new(
+                    vec![
+                        for_loop_prefix,
+                        BlockFragment::new(vec![value.to_frag()], true).to_frag(),
+                        block,
+                        for_loop_suffix,
+                    ],
+                    false,
+                )
+                .to_frag()
+            }
             (Some(index), global_id) => {
                 let indent = TranslateMetadata::single_indent();
                 let index = get_variable_name(index, global_id);
+                let increment = if meta.target.shell.is_posix() {
+                    format!("{indent}{index}=$(( {index} + 1 ))")
+                } else {
+                    format!("{indent}(( {index}++ )) || true")
+                };
                 BlockFragment::new(
                     vec![
                         RawFragment::from(format!("{index}=0;")).to_frag(),
                         for_loop_prefix,
-                        self.block.translate(meta),
-                        RawFragment::from(format!("{indent}(( {index}++ )) || true")).to_frag(),
+                        block,
+                        RawFragment::from(increment).to_frag(),
                         for_loop_suffix,
                     ],
                     false,
                 )
                 .to_frag()
             }
-            _ => BlockFragment::new(
-                vec![for_loop_prefix, self.block.translate(meta), for_loop_suffix],
-                false,
-            )
-            .to_frag(),
+            _ => BlockFragment::new(vec![for_loop_prefix, block, for_loop_suffix], false).to_frag(),
         }
     }
 }
@@ -186,8 +262,11 @@ impl TypeCheckModule for IterLoop {
         self.iter_expr.typecheck(meta)?;
 
         // Determine iterator type after typechecking
-        self.iter_type = match self.iter_expr.get_type() {
+        let iter_expr_type = self.iter_expr.get_type();
+        self.iter_type = match iter_expr_type.clone() {
             Type::Array(kind) => *kind,
+            Type::Map(kind) if self.iter_index.is_some() => *kind,
+            Type::Map(_) => Type::Text,
             t if t.is_allowed_in(&Type::array_of(Type::Generic)) => Type::Generic,
             _ => {
                 let pos = self.iter_expr.get_position();
@@ -202,7 +281,12 @@ impl TypeCheckModule for IterLoop {
             );
             self.iter_global_id = meta.add_var(var);
             if let Some(index) = self.iter_index.as_ref() {
-                let var = VariableDecl::new(index.clone(), Type::Int).with_warn(
+                let kind = if iter_expr_type.is_map() {
+                    Type::Text
+                } else {
+                    Type::Int
+                };
+                let var = VariableDecl::new(index.clone(), kind).with_warn(
                     VariableDeclWarn::from_token(meta, self.iter_index_tok.clone()),
                 );
                 self.iter_index_global_id = meta.add_var(var);
@@ -32,6 +32,15 @@ pub trait IterLoopRange {
         from_val: isize,
         to_val: isize,
     ) -> FragmentKind;
+
+    /// Emulates the arithmetic `for` loop in POSIX sh
+    fn translate_posix_range_loop(
+        &self,
+        from: FragmentKind,
+        step: FragmentKind,
+        cond: FragmentKind,
+        body: FragmentKind,
+    ) -> FragmentKind;
 }
 
 impl IterLoopRange for IterLoop {
@@ -114,7 +123,6 @@ impl IterLoopRange for IterLoop {
 
         let body = self.block.translate(meta);
 
-        let init = fragments!(iter_name.clone(), "=", from_var, index_init);
         // We do a trick here by multiplying by dir so that we can use the same comparison operator
         // iter_name * dir_var < to_var * dir_var
         let cond = fragments!(
@@ -128,6 +136,10 @@ impl IterLoopRange for IterLoop {
             " * ",
             dir_var.clone()
         );
+        if meta.target.shell.is_posix() {
+            return self.translate_posix_range_loop(from_var, dir_var, cond, body);
+        }
+        let init = fragments!(iter_name.clone(), "=", from_var, index_init);
         let update = fragments!(iter_name, "+=", dir_var, index_update);
 
         fragments!(
@@ -173,13 +185,22 @@ impl IterLoopRange for IterLoop {
             )
         };
 
+        let cond = fragments!(iter_name.clone(), " ", op, " ", raw_fragment!("{to_val}"));
+        if meta.target.shell.is_posix() {
+            let step = if from_val <= to_val { 1 } else { -1 };
+            return self.translate_posix_range_loop(
+                raw_fragment!("{from_val}"),
+                raw_fragment!("{step}"),
+                cond,
+                body,
+            );
+        }
         let init = fragments!(
             iter_name.clone(),
             "=",
             raw_fragment!("{from_val}"),
             index_init
         );
-        let cond = fragments!(iter_name.clone(), " ", op, " ", raw_fragment!("{to_val}"));
         let update = fragments!(iter_name, step, index_update);
 
         fragments!(
@@ -194,4 +215,50 @@ impl IterLoopRange for IterLoop {
             "\ndone"
         )
     }
+    fn translate_posix_range_loop(
+        &self,
+        from: FragmentKind,
+        step: FragmentKind,
+        cond: FragmentKind,
+        body: FragmentKind,
+    ) -> FragmentKind {
+        let iter_name = get_variable_name(&self.iter_name, self.iter_global_id);
+        // Variables are advanced in the loop condition so that `continue` does not skip the update
+        let (index_init, index_update) = match (self.iter_index.as_ref(), self.iter_index_global_id)
+        {
+            (Some(index), Some(global_id)) => {
+                let idx_var = get_variable_name(index, Some(global_id));
+                (
+                    raw_fragment!("; {idx_var}=-1"),
+                    raw_fragment!("; {idx_var}=$(( {idx_var} + 1 ))"),
+                )
+            }
+            _ => (FragmentKind::Empty, FragmentKind::Empty),
+        };
+        BlockFragment::new(
+            vec![
+                fragments!(
+                    raw_fragment!("{iter_name}=$(( "),
+                    from,
+                    " - ",
+                    step.clone(),
+                    " ))",
+                    index_init
+                ),
+                fragments!(
+                    raw_fragment!("while {iter_name}=$(( {iter_name} + "),
+                    step,
+                    " ))",
+                    index_update,
+                    "; [ $(( ",
+                    cond,
+                    " )) -ne 0 ]; do"
+                ),
+                body,
+                fragments!("done"),
+            ],
+            false,
+        )
+        .to_frag()
+    }
 }
@@ -6,6 +6,7 @@ use crate::modules::prelude::*;
 use crate::modules::types::Type;
 use crate::raw_fragment;
 use crate::utils::context::{VariableDecl, VariableDeclWarn};
+use crate::utils::function_cache::EntryPoint;
 use crate::utils::metadata::ParserMetadata;
 use amber_meta::AutoKeyword;
 
@@ -72,18 +73,22 @@ impl TypeCheckModule for Main {
         }
 
         // Typecheck the main block content
-        meta.with_push_scope(true, |meta| {
-            // Create variables for main arguments
-            for arg in self.args.iter() {
-                let var = VariableDecl::new(arg.clone(), Type::Array(Box::new(Type::Text)))
-                    .with_const(true)
-                    .with_warn(VariableDeclWarn::from_token(meta, self.args_tok.clone()));
-                self.args_global_id = Some(meta.add_var(var).unwrap());
-            }
-            // Typecheck the block
-            self.block.typecheck(meta)?;
-            Ok(())
-        })
+        let (result, invoked) = meta.with_invoked_functions(|meta| {
+            meta.with_push_scope(true, |meta| {
+                // Create variables for main arguments
+                for arg in self.args.iter() {
+                    let var = VariableDecl::new(arg.clone(), Type::Array(Box::new(Type::Text)))
+                        .with_const(true)
+                        .with_warn(VariableDeclWarn::from_token(meta, self.args_tok.clone()));
+                    self.args_global_id = Some(meta.add_var(var).unwrap());
+                }
+                // Typecheck the block
+                self.block.typecheck(meta)?;
+                Ok(())
+            })
+        });
+        meta.fun_cache.add_entry_point(EntryPoint::Main, invoked);
+        result
     }
 }
 
@@ -99,6 +104,12 @@ impl TranslateModule for Main {
                 || FragmentKind::Empty,
                 |name| {
                     let id = self.args_global_id.unwrap_or(global_id);
+                    if meta.target.shell.is_posix() {
+                        // POSIX sh lacks typeset and arrays, so the arguments are joined with the unit separator
+                        return raw_fragment!(
+                            "{name}_{id}=$(printf '%s\\037' {quote}{dollar}0{quote} {quote}{dollar}@{quote}); readonly {name}_{id}"
+                        );
+                    }
                     raw_fragment!(
                         // typeset is supported by all 3 shells, no need for extra logic
                         "typeset -r {name}_{id}=({quote}{dollar}0{quote} {quote}{dollar}@{quote})"
@@ -1,6 +1,6 @@
 use heraclitus_compiler::{
     error,
-    prelude::{Failure, Message, Token},
+    prelude::{Failure, Message, Metadata, Token},
 };
 
 use crate::utils::ParserMetadata;
@@ -9,6 +9,7 @@ pub mod block;
 pub mod builtin;
 pub mod command;
 pub mod condition;
+pub mod enumeration;
 pub mod expression;
 pub mod function;
 pub mod imports;
@@ -18,6 +19,7 @@ pub mod main;
 pub mod prelude;
 pub mod shorthand;
 pub mod statement;
+pub mod structure;
 pub mod test;
 pub mod typecheck;
 pub mod types;
@@ -40,3 +42,15 @@ pub fn handle_symbol_scope_declaration(
 
     Ok(())
 }
+
+/// Checks if the current token is placed in a different line than the previous one
+pub fn is_after_line_break(meta: &ParserMetadata) -> bool {
+    let index = meta.get_index();
+    let previous = index
+        .checked_sub(1)
+        .and_then(|index| meta.context.expr.get(index));
+    match (previous, meta.context.expr.get(index)) {
+        (Some(previous), Some(current)) => previous.pos.0 < current.pos.0,
+        _ => false,
+    }
+}
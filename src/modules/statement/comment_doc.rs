@@ -1,6 +1,8 @@
 use crate::modules::prelude::*;
+use crate::utils::cc_flags::{get_ccflag_name, CCFlags};
 use crate::utils::*;
 use heraclitus_compiler::prelude::*;
+use std::collections::HashSet;
 
 #[derive(Debug, Clone)]
 pub struct CommentDoc {
@@ -110,3 +112,40 @@ impl DocumentationModule for CommentDoc {
         self.value.trim_end().to_string() + "\n"
     }
 }
+
+/// Checks if the statement starting at the token index directly follows a doc comment
+pub fn is_preceded_by_comment_doc(meta: &ParserMetadata, index: usize) -> bool {
+    let Some(line) = meta.get_token_at(index).map(|token| token.pos.0) else {
+        return false;
+    };
+    meta.context.expr[..index]
+        .iter()
+        .rev()
+        .find(|token| !token.word.starts_with('\n'))
+        .is_some_and(|token| token.word.starts_with("///") && token.pos.0 + 1 == line)
+}
+
+/// Warns about a public item without a doc comment if the lint is enabled.
+/// The standard library is not checked, since it is not part of the compiled project.
+pub fn warn_missing_docs(
+    meta: &mut ParserMetadata,
+    tok: Option<Token>,
+    item: String,
+    flags: &HashSet<CCFlags>,
+) {
+    let is_std = meta
+        .context
+        .path
+        .as_ref()
+        .is_some_and(|path| path.starts_with("std/"));
+    if !meta.warn_missing_docs || is_std || flags.contains(&CCFlags::AllowMissingDocs) {
+        return;
+    }
+    let flag_name = get_ccflag_name(CCFlags::AllowMissingDocs);
+    let message = Message::new_warn_at_token(meta, tok)
+        .message(format!("Missing documentation for {item}"))
+        .comment(format!(
+            "Add a '///' comment above it or use '{flag_name}' compiler flag to suppress this warning"
+        ));
+    meta.add_message(message);
+}
@@ -3,13 +3,18 @@ use super::comment_doc::CommentDoc;
 use crate::docs::module::DocumentationModule;
 use crate::modules::builtin::{
     cd::Cd, clear::Clear, cp::Cp, disown::Disown, echo::Echo, exit::Exit, lock::Lock, mv::Mv,
-    rm::Rm, sleep::Sleep, touch::Touch, wait::Await,
+    rm::Rm, sleep::Sleep, touch::Touch, unset::Unset, wait::Await,
 };
 use crate::modules::command::cmd::Command;
 use crate::modules::command::modifier::CommandModifier;
-use crate::modules::condition::{ifchain::IfChain, ifcond::IfCondition};
+use crate::modules::condition::{
+    ifchain::IfChain, ifcond::IfCondition, match_stmt::MatchStatement,
+};
+use crate::modules::enumeration::declaration::EnumDeclaration;
 use crate::modules::expression::expr::Expr;
-use crate::modules::function::{declaration::FunctionDeclaration, fail::Fail, ret::Return};
+use crate::modules::function::{
+    declaration::FunctionDeclaration, defer::Defer, fail::Fail, ret::Return, signal::OnSignal,
+};
 use crate::modules::imports::import::Import;
 use crate::modules::loops::{
     break_stmt::Break, continue_stmt::Continue, infinite_loop::InfiniteLoop, iter_loop::IterLoop,
@@ -21,6 +26,7 @@ use crate::modules::shorthand::{
     add::ShorthandAdd, div::ShorthandDiv, modulo::ShorthandModulo, mul::ShorthandMul,
     sub::ShorthandSub,
 };
+use crate::modules::structure::declaration::StructDeclaration;
 use crate::modules::test::Test;
 use crate::modules::variable::{
     init::VariableInit, init_destruct::VariableInitDestruct, set::VariableSet,
@@ -42,6 +48,7 @@ pub enum StmtType {
     VariableSetDestruct(VariableSetDestruct),
     IfCondition(IfCondition),
     IfChain(IfChain),
+    MatchStatement(MatchStatement),
     ShorthandAdd(ShorthandAdd),
     ShorthandSub(ShorthandSub),
     ShorthandMul(ShorthandMul),
@@ -53,8 +60,12 @@ pub enum StmtType {
     Break(Break),
     Continue(Continue),
     FunctionDeclaration(FunctionDeclaration),
+    StructDeclaration(StructDeclaration),
+    EnumDeclaration(EnumDeclaration),
     Return(Return),
     Fail(Fail),
+    Defer(Defer),
+    OnSignal(OnSignal),
     Import(Import),
     Main(Main),
     Test(Test),
@@ -74,11 +85,14 @@ pub enum StmtType {
     Await(Await),
     Cp(Cp),
     Disown(Disown),
+    Unset(Unset),
 }
 
 #[derive(Debug, Clone)]
 pub struct Statement {
     pub value: Option<StmtType>,
+    /// Position of the first token of the statement
+    pub position: Option<PositionInfo>,
 }
 
 impl Statement {
@@ -88,17 +102,37 @@ impl Statement {
             _ => None,
         }
     }
+
+    /// Name of the public function or constant and whether it has a doc comment
+    pub fn get_public_item(&self) -> Option<(String, bool)> {
+        match &self.value {
+            Some(StmtType::FunctionDeclaration(inner)) => inner.get_public_item(),
+            Some(StmtType::VariableInit(inner)) => inner.get_public_item(),
+            _ => None,
+        }
+    }
+
+    pub fn get_docs_function(&self) -> Option<&FunctionDeclaration> {
+        match &self.value {
+            Some(StmtType::FunctionDeclaration(inner)) => Some(inner),
+            _ => None,
+        }
+    }
 }
 
 impl SyntaxModule<ParserMetadata> for Statement {
     syntax_name!("Statement");
 
     fn new() -> Self {
-        Statement { value: None }
+        Statement {
+            value: None,
+            position: None,
+        }
     }
 
     #[allow(unused_assignments)]
     fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.position = Some(PositionInfo::from_metadata(meta));
         // Order matters here
         parse_statement!(
             [
@@ -106,10 +140,14 @@ impl SyntaxModule<ParserMetadata> for Statement {
                 Import,
                 // Functions
                 FunctionDeclaration,
+                StructDeclaration,
+                EnumDeclaration,
                 Main,
                 Test,
                 Return,
                 Fail,
+                Defer,
+                OnSignal,
                 // Loops
                 InfiniteLoop,
                 IterLoop,
@@ -119,6 +157,7 @@ impl SyntaxModule<ParserMetadata> for Statement {
                 // Conditions
                 IfChain,
                 IfCondition,
+                MatchStatement,
                 // Command
                 Echo,
                 Mv,
@@ -134,6 +173,7 @@ impl SyntaxModule<ParserMetadata> for Statement {
                 Cp,
                 Touch,
                 Disown,
+                Unset,
                 // Variables
                 VariableInitDestruct,
                 VariableSetDestruct,
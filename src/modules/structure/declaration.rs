@@ -0,0 +1,138 @@
+use crate::modules::is_after_line_break;
+use crate::modules::prelude::*;
+use crate::modules::types::{parse_type, StructType, Type};
+use crate::modules::variable::{handle_identifier_name, variable_name_extensions};
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "struct"]
+#[kind = "stmt"]
+pub struct StructDeclaration {
+    name: String,
+    tok: Option<Token>,
+}
+
+impl StructDeclaration {
+    fn parse_fields(&self, meta: &mut ParserMetadata) -> Result<Vec<(String, Type)>, Failure> {
+        let mut fields: Vec<(String, Type)> = vec![];
+        token(meta, "{")?;
+        loop {
+            // Skip comments and newlines
+            if token_by(meta, |token| {
+                token.starts_with("//") || token.starts_with('\n')
+            })
+            .is_ok()
+            {
+                continue;
+            }
+            if token(meta, "}").is_ok() {
+                break;
+            }
+            let tok = meta.get_current_token();
+            let name = variable(meta, variable_name_extensions())?;
+            handle_identifier_name(meta, &name, tok.clone())?;
+            if fields.iter().any(|(field, _)| *field == name) {
+                return error!(
+                    meta,
+                    tok,
+                    format!(
+                        "Field '{name}' is already declared in struct '{}'",
+                        self.name
+                    )
+                );
+            }
+            token(meta, ":")?;
+            let kind = parse_type(meta)?;
+            if !matches!(
+                kind,
+                Type::Text | Type::Num | Type::Int | Type::Bool | Type::Enum(_)
+            ) {
+                return error!(meta, tok => {
+                    message: format!("Field '{name}' cannot be of type '{kind}'"),
+                    comment: "Struct fields can only be of type 'Text', 'Num', 'Int', 'Bool' or an enum due to the Bash limitations"
+                });
+            }
+            fields.push((name, kind));
+            if token(meta, "}").is_ok() {
+                break;
+            }
+            // Entries are separated with a comma or a line break
+            if token(meta, ",").is_err() && !is_after_line_break(meta) {
+                return error!(
+                    meta,
+                    meta.get_current_token(),
+                    "Expected ',' or '}' after struct field"
+                );
+            }
+        }
+        Ok(fields)
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for StructDeclaration {
+    syntax_name!("Struct Declaration");
+
+    fn new() -> Self {
+        StructDeclaration {
+            name: String::new(),
+            tok: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        token(meta, "struct")?;
+        self.tok = meta.get_current_token();
+        self.name = variable(meta, variable_name_extensions())?;
+        if !self.name.starts_with(|chr: char| chr.is_ascii_uppercase()) {
+            return error!(meta, self.tok.clone() => {
+                message: format!("Struct name '{}' must start with an uppercase letter", self.name),
+                comment: "Struct names are written in pascal case like 'DeployTarget'"
+            });
+        }
+        if meta.context.structs.contains_key(&self.name)
+            || meta.context.enums.contains_key(&self.name)
+        {
+            return error!(
+                meta,
+                self.tok.clone(),
+                format!("Type '{}' is already declared", self.name)
+            );
+        }
+        let fields = self.parse_fields(meta)?;
+        if fields.is_empty() {
+            return error!(
+                meta,
+                self.tok.clone(),
+                format!("Struct '{}' must have at least one field", self.name)
+            );
+        }
+        // Structs are registered while parsing, so that they can be used in type annotations
+        let def = StructType {
+            name: self.name.clone(),
+            fields,
+        };
+        meta.context.structs.insert(self.name.clone(), def);
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for StructDeclaration {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        if !meta.is_global_scope() {
+            return error!(
+                meta,
+                self.tok.clone(),
+                "Structs can only be declared in the global scope"
+            );
+        }
+        Ok(())
+    }
+}
+
+impl TranslateModule for StructDeclaration {
+    fn translate(&self, _meta: &mut TranslateMetadata) -> FragmentKind {
+        FragmentKind::Empty
+    }
+}
+
+crate::impl_documentation_noop!(StructDeclaration);
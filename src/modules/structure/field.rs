@@ -0,0 +1,107 @@
+use crate::modules::expression::expr::Expr;
+use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed};
+use crate::modules::variable::variable_name_extensions;
+use crate::raw_fragment;
+use crate::translate::fragments::var_expr::VarIndexValue;
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct FieldAccess {
+    pub left: Box<Expr>,
+    pub name: String,
+    pub index: usize,
+    kind: Type,
+    tok: Option<Token>,
+}
+
+impl Typed for FieldAccess {
+    fn get_type(&self) -> Type {
+        self.kind.clone()
+    }
+}
+
+impl FieldAccess {
+    pub fn set_left(&mut self, left: Expr) {
+        *self.left = left;
+    }
+
+    pub fn parse_operator(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        token(meta, ".")?;
+        Ok(())
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for FieldAccess {
+    syntax_name!("Field Access");
+
+    fn new() -> Self {
+        FieldAccess {
+            left: Box::new(Expr::new()),
+            name: String::new(),
+            index: 0,
+            kind: Type::Null,
+            tok: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.tok = meta.get_current_token();
+        match variable(meta, variable_name_extensions()) {
+            Ok(name) => self.name = name,
+            Err(_) => return error!(meta, self.tok.clone(), "Expected a field name after '.'"),
+        }
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for FieldAccess {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.left.typecheck(meta)?;
+        let Type::Struct(def) = self.left.get_type() else {
+            let pos = self.left.get_position();
+            return error_pos!(
+                meta,
+                pos,
+                format!(
+                    "Cannot access field '{}' of a non-struct expression of type '{}'",
+                    self.name,
+                    self.left.get_type()
+                )
+            );
+        };
+        match def.get_field(&self.name) {
+            Some((index, kind)) => {
+                self.index = index;
+                self.kind = kind.clone();
+                Ok(())
+            }
+            None => error!(
+                meta,
+                self.tok.clone(),
+                format!("Struct '{}' has no field '{}'", def.name, self.name)
+            ),
+        }
+    }
+}
+
+impl TranslateModule for FieldAccess {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let index = VarIndexValue::Index(raw_fragment!("{}", self.index));
+        let mut var = match self.left.translate(meta) {
+            FragmentKind::VarExpr(var) => var,
+            left_frag => {
+                let id = meta.gen_value_id();
+                let name = format!("struct_{id}");
+                let stmt = VarStmtFragment::new(&name, self.left.get_type(), left_frag)
+                    .with_ephemeral(true);
+                meta.stmt_queue.push_back(stmt.clone().to_frag());
+                VarExprFragment::from_stmt(&stmt)
+            }
+        };
+        var.kind = self.get_type();
+        var.with_index_by_value(index).to_frag()
+    }
+}
+
+crate::impl_documentation_noop!(FieldAccess);
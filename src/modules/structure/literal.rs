@@ -0,0 +1,148 @@
+use crate::modules::expression::expr::Expr;
+use crate::modules::expression::literal::array::translate_array_items;
+use crate::modules::prelude::*;
+use crate::modules::types::{StructType, Type, Typed};
+use crate::modules::variable::variable_name_extensions;
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct StructLiteral {
+    def: Option<StructType>,
+    /// Field values in the order of the struct declaration
+    values: Vec<Expr>,
+    tok: Option<Token>,
+}
+
+impl Typed for StructLiteral {
+    fn get_type(&self) -> Type {
+        self.def.clone().map(Type::Struct).unwrap_or_default()
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for StructLiteral {
+    syntax_name!("Struct Literal");
+
+    fn new() -> Self {
+        StructLiteral {
+            def: None,
+            values: vec![],
+            tok: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.tok = meta.get_current_token();
+        let name = variable(meta, variable_name_extensions())?;
+        let Some(def) = meta.context.structs.get(&name).cloned() else {
+            return Err(Failure::Quiet(PositionInfo::from_token(
+                meta,
+                self.tok.clone(),
+            )));
+        };
+        token(meta, "{")?;
+        let mut values: Vec<Option<Expr>> = vec![None; def.fields.len()];
+        loop {
+            // Skip comments and newlines
+            if token_by(meta, |token| {
+                token.starts_with("//") || token.starts_with('\n')
+            })
+            .is_ok()
+            {
+                continue;
+            }
+            if token(meta, "}").is_ok() {
+                break;
+            }
+            let tok = meta.get_current_token();
+            let field = variable(meta, variable_name_extensions())?;
+            let Some((index, _)) = def.get_field(&field) else {
+                return error!(
+                    meta,
+                    tok,
+                    format!("Struct '{}' has no field '{field}'", def.name)
+                );
+            };
+            if values[index].is_some() {
+                return error!(meta, tok, format!("Field '{field}' is already set"));
+            }
+            if token(meta, ":").is_err() {
+                return error!(
+                    meta,
+                    meta.get_current_token(),
+                    "Expected ':' after field name"
+                );
+            }
+            let mut value = Expr::new();
+            syntax(meta, &mut value)?;
+            values[index] = Some(value);
+            if token(meta, "}").is_ok() {
+                break;
+            }
+            if token(meta, ",").is_err() {
+                return error!(
+                    meta,
+                    meta.get_current_token(),
+                    "Expected ',' or '}' after field value"
+                );
+            }
+        }
+        let missing = get_missing_fields(&def, &values);
+        if !missing.is_empty() {
+            return error!(meta, self.tok.clone() => {
+                message: format!("Missing fields in struct '{}'", def.name),
+                comment: format!("Provide values for {}", missing.join(", "))
+            });
+        }
+        self.values = values.into_iter().flatten().collect();
+        self.def = Some(def);
+        Ok(())
+    }
+}
+
+// Lists the quoted names of fields that were not given any value
+fn get_missing_fields(def: &StructType, values: &[Option<Expr>]) -> Vec<String> {
+    def.fields
+        .iter()
+        .zip(values)
+        .filter(|(_, value)| value.is_none())
+        .map(|((name, _), _)| format!("'{name}'"))
+        .collect()
+}
+
+impl TypeCheckModule for StructLiteral {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let def = self.def.clone().expect("Struct literal was not parsed");
+        for ((name, kind), value) in def.fields.iter().zip(self.values.iter_mut()) {
+            value.typecheck(meta)?;
+            let value_type = value.get_type();
+            if !value_type.is_allowed_in(kind) {
+                let pos = value.get_position();
+                return error_pos!(
+                    meta,
+                    pos,
+                    format!(
+                        "Field '{name}' of struct '{}' expects a value of type '{kind}', found '{value_type}'",
+                        def.name
+                    )
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TranslateModule for StructLiteral {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let id = meta.gen_value_id();
+        let values = self
+            .values
+            .iter()
+            .map(|value| value.translate_eval(meta, false))
+            .collect::<Vec<FragmentKind>>();
+        let values = translate_array_items(meta, values);
+        let var_stmt = VarStmtFragment::new("struct", self.get_type(), values).with_global_id(id);
+        meta.push_ephemeral_variable(var_stmt).to_frag()
+    }
+}
+
+crate::impl_documentation_noop!(StructLiteral);
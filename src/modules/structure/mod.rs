@@ -0,0 +1,3 @@
+pub mod declaration;
+pub mod field;
+pub mod literal;
@@ -1,5 +1,6 @@
 use crate::modules::block::Block;
 use crate::modules::prelude::*;
+use crate::utils::function_cache::EntryPoint;
 use crate::utils::metadata::ParserMetadata;
 use amber_meta::AutoKeyword;
 use heraclitus_compiler::prelude::*;
@@ -84,17 +85,25 @@ impl TypeCheckModule for Test {
         }
 
         // Typecheck the test block content
-        meta.with_push_scope(true, |meta| {
-            meta.context.is_main_ctx = true;
-            meta.context.is_test_ctx = true;
-            meta.context.is_trust_ctx = true;
-            // Typecheck the block
-            self.block.typecheck(meta)?;
-            meta.context.is_main_ctx = false;
-            meta.context.is_test_ctx = false;
-            meta.context.is_trust_ctx = false;
-            Ok(())
-        })
+        let (result, invoked) = meta.with_invoked_functions(|meta| {
+            meta.with_push_scope(true, |meta| {
+                meta.context.is_main_ctx = true;
+                meta.context.is_test_ctx = true;
+                meta.context.is_trust_ctx = true;
+                // Typecheck the block
+                self.block.typecheck(meta)?;
+                meta.context.is_main_ctx = false;
+                meta.context.is_test_ctx = false;
+                meta.context.is_trust_ctx = false;
+                Ok(())
+            })
+        });
+        // Tests of imported files are never run
+        if !self.is_skipped {
+            let entry_point = EntryPoint::Test(self.name.clone());
+            meta.fun_cache.add_entry_point(entry_point, invoked);
+        }
+        result
     }
 }
 
@@ -9,3 +9,9 @@ pub trait TypeCheckModule {
     /// It can mutate the module to store type information.
     fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult;
 }
+
+impl<T: TypeCheckModule> TypeCheckModule for Box<T> {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        (**self).typecheck(meta)
+    }
+}
@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use crate::utils::ParserMetadata;
 use heraclitus_compiler::prelude::*;
-use itertools::Itertools;
+use itertools::{izip, Itertools};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum Type {
@@ -13,21 +13,87 @@ pub enum Type {
     Num,
     Int,
     Array(Box<Type>),
+    /// Map from text keys to values of the given type
+    Map(Box<Type>),
     Union(Vec<Type>),
+    /// Struct with named fields
+    Struct(StructType),
+    /// Enum with a fixed set of variants
+    Enum(EnumType),
+    /// Function that can be passed as a value
+    Function(FunctionType),
     Generic,
 }
 
+/// Declared struct with its fields in the order of declaration
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StructType {
+    pub name: String,
+    pub fields: Vec<(String, Type)>,
+}
+
+impl StructType {
+    /// Returns the position and the type of the field
+    pub fn get_field(&self, name: &str) -> Option<(usize, &Type)> {
+        self.fields
+            .iter()
+            .enumerate()
+            .find(|(_, (field, _))| field == name)
+            .map(|(index, (_, kind))| (index, kind))
+    }
+}
+
+/// Declared enum with its variants in the order of declaration
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnumType {
+    pub name: String,
+    pub variants: Vec<String>,
+}
+
+impl EnumType {
+    pub fn has_variant(&self, name: &str) -> bool {
+        self.variants.iter().any(|variant| variant == name)
+    }
+}
+
+/// Signature of a function value like `Fun(Int, Text): Bool`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FunctionType {
+    /// Id of the declared function that the value refers to.
+    /// Signatures written by the user are not bound to any function.
+    pub id: Option<usize>,
+    pub args: Vec<Type>,
+    pub returns: Box<Type>,
+}
+
+impl FunctionType {
+    /// Checks if the function can be used where the signature is expected
+    pub fn matches_signature(&self, signature: &FunctionType) -> bool {
+        self.args.len() == signature.args.len()
+            && izip!(&self.args, &signature.args).all(|(arg, expected)| expected.is_allowed_in(arg))
+            && (*self.returns == Type::Generic || self.returns.is_allowed_in(&signature.returns))
+    }
+}
+
 impl Type {
     #[inline]
     pub fn array_of(kind: Type) -> Self {
         Self::Array(Box::new(kind))
     }
 
+    #[inline]
+    pub fn map_of(kind: Type) -> Self {
+        Self::Map(Box::new(kind))
+    }
+
     pub fn is_subset_of(&self, other: &Type) -> bool {
         match (self, other) {
             (Type::Generic, Type::Generic) => false,
             (_, Type::Generic) => true,
             (Type::Int, Type::Num) => true,
+            (Type::Function(current), Type::Function(other)) if other.id.is_none() => {
+                current.matches_signature(other)
+            }
             (Type::Array(current), Type::Array(other)) => match (&**current, &**other) {
                 (current, Type::Generic) if *current != Type::Generic => true,
                 (Type::Int, Type::Num) => true,
@@ -78,7 +144,9 @@ impl Type {
         }
 
         if let (Type::Array(const_type), Type::Array(other_type)) = (self, other) {
-            return **const_type == Type::Generic && **other_type != Type::Generic;
+            // Rows of nested arrays like `[[]]` follow the same rules
+            return (**const_type == Type::Generic && **other_type != Type::Generic)
+                || (const_type.is_array() && const_type.is_allowed_in(other_type));
         }
 
         false
@@ -88,11 +156,60 @@ impl Type {
         matches!(self, Type::Array(_))
     }
 
+    /// Checks if the type is an array of arrays like `[[Int]]`
+    pub fn is_nested_array(&self) -> bool {
+        matches!(self, Type::Array(inner) if inner.is_array())
+    }
+
+    /// Checks if the items of the array are stored as encoded rows,
+    /// which is the case for nested arrays and arrays of structs
+    pub fn has_encoded_rows(&self) -> bool {
+        matches!(self, Type::Array(inner) if inner.is_array() || inner.is_struct())
+    }
+
+    /// Checks if the value of this type is stored in a single shell word
+    pub fn is_scalar(&self) -> bool {
+        matches!(
+            self,
+            Type::Text | Type::Bool | Type::Num | Type::Int | Type::Enum(_)
+        )
+    }
+
+    pub fn is_map(&self) -> bool {
+        matches!(self, Type::Map(_))
+    }
+
+    pub fn is_struct(&self) -> bool {
+        matches!(self, Type::Struct(_))
+    }
+
+    pub fn is_enum(&self) -> bool {
+        matches!(self, Type::Enum(_))
+    }
+
+    pub fn is_function(&self) -> bool {
+        matches!(self, Type::Function(_))
+    }
+
+    /// Returns the type of the shell variable that holds the value.
+    /// Structs are stored as indexed arrays of their fields,
+    /// enums as the name of their variant, functions as the name
+    /// of the shell function and both nested arrays and arrays
+    /// of structs as indexed arrays of encoded rows.
+    pub fn into_shell_type(self) -> Type {
+        match self {
+            Type::Struct(_) => Type::array_of(Type::Text),
+            ref kind if kind.has_encoded_rows() => Type::array_of(Type::Text),
+            Type::Enum(_) | Type::Function(_) => Type::Text,
+            kind => kind,
+        }
+    }
+
     pub fn is_strictly_typed(&self) -> bool {
         match self {
             Type::Generic => false,
             Type::Union(_) => false,
-            Type::Array(inner) => inner.is_strictly_typed(),
+            Type::Array(inner) | Type::Map(inner) => inner.is_strictly_typed(),
             _ => true,
         }
     }
@@ -107,6 +224,8 @@ impl Type {
             (target, Type::Union(types)) => types.iter().any(|t| target.can_intersect(t)),
             // Array types
             (Type::Array(inner_a), Type::Array(inner_b)) => inner_a.can_intersect(inner_b),
+            // Map types
+            (Type::Map(inner_a), Type::Map(inner_b)) => inner_a.can_intersect(inner_b),
             // Generic can be anything
             (Type::Generic, _) | (_, Type::Generic) => true,
             // Different primitive types never intersect
@@ -130,6 +249,13 @@ impl Display for Type {
                     write!(f, "[{t}]")
                 }
             }
+            Type::Map(t) => write!(f, "[Text: {t}]"),
+            Type::Struct(def) => write!(f, "{}", def.name),
+            Type::Enum(def) => write!(f, "{}", def.name),
+            Type::Function(def) => {
+                let args = def.args.iter().map(|t| t.to_string()).join(", ");
+                write!(f, "Fun({args}): {}", def.returns)
+            }
             Type::Union(types) => write!(f, "{}", types.iter().map(|t| t.to_string()).join(" | ")),
             Type::Generic => write!(f, "Generic"),
         }
@@ -143,8 +269,12 @@ pub trait Typed {
 // Tries to parse the type - if it fails, it fails loudly
 pub fn parse_type(meta: &mut ParserMetadata) -> Result<Type, Failure> {
     let tok = meta.get_current_token();
-    try_parse_type(meta).map_err(|_| {
-        Failure::Loud(Message::new_err_at_token(meta, tok).message("Expected a data type"))
+    try_parse_type(meta).map_err(|err| match err {
+        // Keep the reason why the type is not supported
+        Failure::Loud(_) => err,
+        Failure::Quiet(_) => {
+            Failure::Loud(Message::new_err_at_token(meta, tok).message("Expected a data type"))
+        }
     })
 }
 
@@ -207,15 +337,52 @@ fn try_parse_simple_type(meta: &mut ParserMetadata) -> Result<Type, Failure> {
                         Ok(Type::Array(Box::new(Type::Generic)))
                     } else {
                         match try_parse_type(meta) {
+                            Ok(key_type) if token(meta, ":").is_ok() => {
+                                try_parse_map_type(meta, tok, key_type)
+                            }
+                            Ok(Type::Array(inner)) if inner.is_scalar() => {
+                                if token(meta, "]").is_err() {
+                                    meta.set_index(index);
+                                    return Err(Failure::Quiet(PositionInfo::at_eof(meta)));
+                                }
+                                if meta.nested_array_usage.is_none() {
+                                    meta.nested_array_usage =
+                                        Some(PositionInfo::from_token(meta, tok));
+                                }
+                                Ok(Type::array_of(Type::Array(inner)))
+                            }
+                            // Leave the ambiguous `[[]]` to be parsed as an array literal
+                            Ok(Type::Array(inner)) if *inner == Type::Generic => {
+                                meta.set_index(index);
+                                Err(Failure::Quiet(PositionInfo::at_eof(meta)))
+                            }
                             Ok(Type::Array(_)) => error!(
                                 meta,
-                                tok, "Arrays cannot be nested due to the Bash limitations"
+                                tok, "Arrays can only be nested one level deep due to the Bash limitations"
+                            ),
+                            Ok(Type::Map(_)) => error!(
+                                meta,
+                                tok, "Arrays cannot contain maps due to the Bash limitations"
                             ),
+                            Ok(Type::Struct(def)) => {
+                                if token(meta, "]").is_err() {
+                                    meta.set_index(index);
+                                    return Err(Failure::Quiet(PositionInfo::at_eof(meta)));
+                                }
+                                if meta.struct_array_usage.is_none() {
+                                    meta.struct_array_usage =
+                                        Some(PositionInfo::from_token(meta, tok));
+                                }
+                                Ok(Type::array_of(Type::Struct(def)))
+                            }
                             Ok(Type::Union(_)) => {
                                 error!(meta, tok, "Arrays don't support mixed type values")
                             }
                             Ok(result_type) => {
-                                token(meta, "]")?;
+                                if token(meta, "]").is_err() {
+                                    meta.set_index(index);
+                                    return Err(Failure::Quiet(PositionInfo::at_eof(meta)));
+                                }
                                 Ok(Type::Array(Box::new(result_type)))
                             }
                             Err(_) => {
@@ -246,14 +413,31 @@ fn try_parse_simple_type(meta: &mut ParserMetadata) -> Result<Type, Failure> {
                         tok, "'Boolean' is not a valid data type. Did you mean 'Bool'?"
                     )
                 }
+                "Fun" => {
+                    meta.increment_index();
+                    try_parse_function_type(meta)
+                }
                 array @ ("List" | "Array") => {
                     error!(meta, tok => {
                         message: format!("'{array}'<T> is not a valid data type. Did you mean '[T]'?"),
                         comment: "Where 'T' is the type of the array elements"
                     })
                 }
-                // The quiet error
-                _ => Err(Failure::Quiet(PositionInfo::at_eof(meta))),
+                name => {
+                    let kind = if let Some(def) = meta.context.structs.get(name) {
+                        Some(Type::Struct(def.clone()))
+                    } else {
+                        meta.context.enums.get(name).cloned().map(Type::Enum)
+                    };
+                    match kind {
+                        Some(kind) => {
+                            meta.increment_index();
+                            Ok(kind)
+                        }
+                        // The quiet error
+                        None => Err(Failure::Quiet(PositionInfo::at_eof(meta))),
+                    }
+                }
             }
         }
         None => Err(Failure::Quiet(PositionInfo::at_eof(meta))),
@@ -262,6 +446,66 @@ fn try_parse_simple_type(meta: &mut ParserMetadata) -> Result<Type, Failure> {
     res
 }
 
+// Parses the rest of the function type `Fun(T, U): V` after the keyword
+fn try_parse_function_type(meta: &mut ParserMetadata) -> Result<Type, Failure> {
+    token(meta, "(")?;
+    let mut args = vec![];
+    if token(meta, ")").is_err() {
+        loop {
+            args.push(parse_type(meta)?);
+            if token(meta, ")").is_ok() {
+                break;
+            }
+            token(meta, ",")?;
+        }
+    }
+    // Functions without the return type return nothing
+    let returns = match token(meta, ":") {
+        Ok(_) => {
+            let tok = meta.get_current_token();
+            match try_parse_simple_type(meta) {
+                Ok(kind) => kind,
+                Err(Failure::Loud(err)) => return Err(Failure::Loud(err)),
+                Err(Failure::Quiet(_)) => return error!(meta, tok, "Expected a data type"),
+            }
+        }
+        Err(_) => Type::Null,
+    };
+    Ok(Type::Function(FunctionType {
+        id: None,
+        args,
+        returns: Box::new(returns),
+    }))
+}
+
+// Parses the rest of the map type `[Text: T]` after the colon
+fn try_parse_map_type(
+    meta: &mut ParserMetadata,
+    tok: Option<Token>,
+    key_type: Type,
+) -> Result<Type, Failure> {
+    if key_type != Type::Text {
+        return error!(meta, tok => {
+            message: format!("Map keys must be of type 'Text', not '{key_type}'"),
+            comment: "Maps are stored as associative arrays, which only support text keys"
+        });
+    }
+    match parse_type(meta)? {
+        Type::Array(_) | Type::Map(_) | Type::Struct(_) => error!(
+            meta,
+            tok, "Map values cannot be arrays, maps or structs due to the Bash limitations"
+        ),
+        Type::Union(_) => error!(meta, tok, "Maps don't support mixed type values"),
+        value_type => {
+            token(meta, "]")?;
+            if meta.map_usage.is_none() {
+                meta.map_usage = Some(PositionInfo::from_token(meta, tok));
+            }
+            Ok(Type::map_of(value_type))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Type;
@@ -2,6 +2,8 @@ use crate::modules::prelude::*;
 use crate::modules::typecheck::TypeCheckModule;
 use crate::modules::types::{Type, Typed};
 use crate::modules::variable::{handle_variable_reference, variable_name_extensions};
+use crate::raw_fragment;
+use crate::utils::context::FunctionDecl;
 use heraclitus_compiler::prelude::*;
 
 #[derive(Debug, Clone)]
@@ -10,6 +12,8 @@ pub struct VariableGet {
     kind: Type,
     global_id: Option<usize>,
     is_ref: bool,
+    /// Declared function that is referenced by its name
+    function: Option<FunctionDecl>,
     tok: Option<Token>,
 }
 
@@ -34,6 +38,7 @@ impl SyntaxModule<ParserMetadata> for VariableGet {
             kind: Type::Null,
             global_id: None,
             is_ref: false,
+            function: None,
             tok: None,
         }
     }
@@ -47,6 +52,17 @@ impl SyntaxModule<ParserMetadata> for VariableGet {
 
 impl TypeCheckModule for VariableGet {
     fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        // Functions can be passed around as values by their name
+        if meta.get_var(&self.name).is_none() {
+            if let Some(function) = meta.get_fun_declaration(&self.name).cloned() {
+                let definition = meta.symbol_table.resolve_function(&function);
+                let position = PositionInfo::from_token(meta, self.tok.clone());
+                meta.symbol_table.add_reference(position, definition);
+                self.kind = function.get_value_type();
+                self.function = Some(function);
+                return Ok(());
+            }
+        }
         let variable = handle_variable_reference(meta, &self.tok, &self.name)?;
         self.global_id = variable.global_id;
         self.is_ref = variable.is_ref;
@@ -56,7 +72,12 @@ impl TypeCheckModule for VariableGet {
 }
 
 impl TranslateModule for VariableGet {
-    fn translate(&self, _meta: &mut TranslateMetadata) -> FragmentKind {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        // Function values are stored as the name of the shell function
+        if let Some(function) = &self.function {
+            let prefix = meta.gen_variable_prefix(&function.name);
+            return raw_fragment!("{}{}__{}", prefix, function.name, function.id);
+        }
         VarExprFragment::new(&self.name, self.get_type())
             .with_global_id(self.global_id)
             .with_ref(self.is_ref)
@@ -1,8 +1,9 @@
 use heraclitus_compiler::prelude::*;
 use std::collections::HashSet;
 
-use super::{handle_identifier_name, variable_name_extensions};
-use crate::modules::expression::expr::Expr;
+use super::{handle_identifier_name, validate_map_assignment, variable_name_extensions};
+use crate::modules::expression::expr::{Expr, ExprType};
+use crate::modules::statement::comment_doc::{is_preceded_by_comment_doc, warn_missing_docs};
 use crate::modules::types::Typed;
 use crate::modules::{handle_symbol_scope_declaration, prelude::*};
 use crate::utils::cc_flags::{get_ccflag_by_name, get_ccflag_name, CCFlags};
@@ -18,6 +19,8 @@ pub struct VariableInit {
     is_fun_ctx: bool,
     is_const: bool,
     is_public: bool,
+    /// Whether the declaration follows a doc comment
+    is_documented: bool,
     tok: Option<Token>,
     flags: HashSet<CCFlags>,
 }
@@ -45,12 +48,14 @@ impl SyntaxModule<ParserMetadata> for VariableInit {
             is_fun_ctx: false,
             is_const: false,
             is_public: false,
+            is_documented: false,
             tok: None,
             flags: HashSet::new(),
         }
     }
 
     fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.is_documented = is_preceded_by_comment_doc(meta, meta.get_index());
         while let Ok(flag) = token_by(meta, |val| val.starts_with("#[")) {
             self.flags
                 .insert(get_ccflag_by_name(&flag[2..flag.len() - 1]));
@@ -72,6 +77,10 @@ impl SyntaxModule<ParserMetadata> for VariableInit {
             });
         }
         self.name = variable(meta, variable_name_extensions())?;
+        if self.is_public && !self.is_documented {
+            let item = format!("public constant '{}'", self.name);
+            warn_missing_docs(meta, self.tok.clone(), item, &self.flags);
+        }
         if let Err(err) = token(meta, "=") {
             return error_pos!(
                 meta,
@@ -85,9 +94,21 @@ impl SyntaxModule<ParserMetadata> for VariableInit {
     }
 }
 
+impl VariableInit {
+    /// Name of the public constant and whether it has a doc comment.
+    /// Constants that allow missing docs are left out.
+    pub fn get_public_item(&self) -> Option<(String, bool)> {
+        if !self.is_public || self.flags.contains(&CCFlags::AllowMissingDocs) {
+            return None;
+        }
+        Some((self.name.clone(), self.is_documented))
+    }
+}
+
 impl TypeCheckModule for VariableInit {
     fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
         self.expr.typecheck(meta)?;
+        validate_map_assignment(meta, &self.expr)?;
 
         handle_identifier_name(meta, &self.name, self.tok.clone())?;
         handle_symbol_scope_declaration(meta, &self.name, self.tok.clone())?;
@@ -107,10 +128,16 @@ impl TypeCheckModule for VariableInit {
 
 impl TranslateModule for VariableInit {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
-        let expr = self.expr.translate(meta);
+        let expr = match &self.expr.value {
+            // Map literals are assigned directly, because maps cannot be copied
+            Some(ExprType::Map(map)) => map.translate_entries(meta),
+            _ => self.expr.translate(meta),
+        };
         VarStmtFragment::new(&self.name, self.expr.get_type(), expr)
             .with_global_id(self.global_id)
             .with_local(self.is_fun_ctx)
+            // Entries are set through the index, which does not count as a use of the variable
+            .with_optimization_when_unused(!self.expr.get_type().is_map())
             .to_frag()
     }
 }
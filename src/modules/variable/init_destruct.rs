@@ -110,6 +110,20 @@ impl TypeCheckModule for VariableInitDestruct {
                     comment: "Please add an explicit type annotation to this array value before destructuring"
                 });
             }
+            Type::Array(inner) if inner.is_array() => {
+                let pos = self.expr.get_position();
+                return error_pos!(meta, pos => {
+                    message: "Cannot destructure a nested array",
+                    comment: "Access the rows by their index instead"
+                });
+            }
+            Type::Array(inner) if inner.is_struct() => {
+                let pos = self.expr.get_position();
+                return error_pos!(meta, pos => {
+                    message: "Cannot destructure an array of structs",
+                    comment: "Access the structs by their index instead"
+                });
+            }
             Type::Array(inner) => *inner,
             _ => {
                 let pos = self.expr.get_position();
@@ -29,8 +29,10 @@ pub fn variable_name_keywords() -> Vec<&'static str> {
         "cd",
         "const",
         "continue",
+        "defer",
         "echo",
         "else",
+        "enum",
         "exit",
         "exited",
         "fail",
@@ -49,6 +51,7 @@ pub fn variable_name_keywords() -> Vec<&'static str> {
         "lock",
         "loop",
         "main",
+        "match",
         "mv",
         "nameof",
         "touch",
@@ -61,6 +64,7 @@ pub fn variable_name_keywords() -> Vec<&'static str> {
         "silent",
         "sleep",
         "status",
+        "struct",
         "sudo",
         "succeeded",
         "suppress",
@@ -116,9 +120,15 @@ pub fn handle_variable_reference(
     match meta.get_var_used(name) {
         Some(variable_unit) => {
             let mut var = variable_unit.clone();
+            if meta.capture_usage.is_none() && meta.is_captured_var(name) {
+                meta.capture_usage = Some(PositionInfo::from_token(meta, tok.clone()));
+            }
             if let Some(narrowed) = meta.get_narrowed_type(name) {
                 var.kind = narrowed.clone();
             }
+            let definition = meta.symbol_table.resolve_variable(&var);
+            let position = PositionInfo::from_token(meta, tok.clone());
+            meta.symbol_table.add_reference(position, definition);
             Ok(var)
         }
         None => {
@@ -246,6 +256,29 @@ pub fn validate_index_accessor(
     Ok(())
 }
 
+pub fn validate_key_accessor(meta: &ParserMetadata, key: &Expr) -> SyntaxResult {
+    if key.get_type() != Type::Text {
+        let position = key.get_position();
+        return error_pos!(meta, position => {
+            message: "Map key must be of type 'Text'",
+            comment: format!("The map key must be a text and not {}", key.get_type())
+        });
+    }
+    Ok(())
+}
+
+/// Maps can only be assigned from map literals, as associative arrays cannot be copied
+pub fn validate_map_assignment(meta: &ParserMetadata, expr: &Expr) -> SyntaxResult {
+    if expr.get_type().is_map() && !matches!(expr.value, Some(ExprType::Map(_))) {
+        let position = expr.get_position();
+        return error_pos!(meta, position => {
+            message: "Maps cannot be copied",
+            comment: "Pass the map to functions by reference or copy its entries in a loop"
+        });
+    }
+    Ok(())
+}
+
 fn allow_index_accessor(index: &Expr, range: bool) -> bool {
     match (&index.kind, &index.value) {
         (t, _) if t.is_allowed_in(&Type::Int) => true,
@@ -1,11 +1,15 @@
 use super::{
     handle_index_accessor, handle_variable_reference, prevent_constant_mutation,
-    validate_index_accessor, variable_name_extensions,
+    validate_index_accessor, validate_key_accessor, validate_map_assignment,
+    variable_name_extensions,
 };
+use crate::modules::expression::expr::{Expr, ExprType};
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
+use crate::raw_fragment;
+use crate::translate::module::TranslateModule;
+use crate::translate::nested::translate_row_encoding;
 use crate::utils::{ParserMetadata, TranslateMetadata};
-use crate::{modules::expression::expr::Expr, translate::module::TranslateModule};
 use heraclitus_compiler::prelude::*;
 
 #[derive(Debug, Clone)]
@@ -14,9 +18,12 @@ pub struct VariableSet {
     expr: Box<Expr>,
     global_id: Option<usize>,
     index: Option<Expr>,
+    /// Name of the struct field and its position after typechecking
+    field: Option<(String, usize)>,
     is_ref: bool,
     var_type: Type,
     tok: Option<Token>,
+    field_tok: Option<Token>,
 }
 
 impl SyntaxModule<ParserMetadata> for VariableSet {
@@ -28,9 +35,11 @@ impl SyntaxModule<ParserMetadata> for VariableSet {
             expr: Box::new(Expr::new()),
             global_id: None,
             index: None,
+            field: None,
             is_ref: false,
             var_type: Type::Null,
             tok: None,
+            field_tok: None,
         }
     }
 
@@ -38,6 +47,11 @@ impl SyntaxModule<ParserMetadata> for VariableSet {
         self.tok = meta.get_current_token();
         self.name = variable(meta, variable_name_extensions())?;
         self.index = handle_index_accessor(meta, false)?;
+        if self.index.is_none() && token(meta, ".").is_ok() {
+            self.field_tok = meta.get_current_token();
+            let field = variable(meta, variable_name_extensions())?;
+            self.field = Some((field, 0));
+        }
 
         token(meta, "=")?;
         syntax(meta, &mut *self.expr)?;
@@ -59,6 +73,53 @@ impl TypeCheckModule for VariableSet {
         prevent_constant_mutation(meta, &self.tok, &self.name, variable.is_const)?;
         meta.mark_var_modified(&self.name);
 
+        if let Some((field, index)) = &mut self.field {
+            let Type::Struct(def) = &self.var_type else {
+                return error!(
+                    meta,
+                    self.tok.clone(),
+                    format!(
+                        "Cannot assign a value to a field of a non-struct variable of type '{}'",
+                        self.var_type
+                    )
+                );
+            };
+            let Some((field_index, kind)) = def.get_field(field) else {
+                return error!(
+                    meta,
+                    self.field_tok.clone(),
+                    format!("Struct '{}' has no field '{field}'", def.name)
+                );
+            };
+            *index = field_index;
+            let expr_type = self.expr.get_type();
+            if !expr_type.is_allowed_in(kind) {
+                let pos = self.expr.get_position();
+                return error_pos!(
+                    meta,
+                    pos,
+                    format!(
+                        "Cannot assign value of type '{expr_type}' to a field of type '{kind}'"
+                    )
+                );
+            }
+            return Ok(());
+        }
+
+        if let (Some(key_expr), Type::Map(kind)) = (&self.index, &self.var_type) {
+            validate_key_accessor(meta, key_expr)?;
+            let expr_type = self.expr.get_type();
+            if !expr_type.is_allowed_in(kind) {
+                let tok = self.expr.get_position();
+                return error_pos!(
+                    meta,
+                    tok,
+                    format!("Cannot assign value of type '{expr_type}' to a map of '{kind}'")
+                );
+            }
+            return Ok(());
+        }
+
         if let Some(ref index_expr) = self.index {
             if !matches!(variable.kind, Type::Array(_)) {
                 let left_type = variable.kind.clone();
@@ -104,6 +165,7 @@ impl TypeCheckModule for VariableSet {
                 }
             }
         } else {
+            validate_map_assignment(meta, &self.expr)?;
             // Check for type inference
             if let (Type::Array(inner_var), Type::Array(inner_right)) = (&self.var_type, &expr_type)
             {
@@ -133,9 +195,24 @@ impl TypeCheckModule for VariableSet {
 
 impl TranslateModule for VariableSet {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
-        let index = self.index.as_ref().map(|v| v.translate(meta));
-        let expr = self.expr.translate(meta);
-        VarStmtFragment::new(&self.name, self.expr.get_type(), expr)
+        let index = match &self.field {
+            Some((_, index)) => Some(raw_fragment!("{index}")),
+            None => self.index.as_ref().map(|v| v.translate(meta)),
+        };
+        let mut kind = self.expr.get_type();
+        let expr = match &self.expr.value {
+            // Map literals are assigned directly, because maps cannot be copied
+            Some(ExprType::Map(map)) => map.translate_entries(meta),
+            // Rows of nested arrays and structs are stored in their encoded form
+            _ if index.is_some() && self.var_type.has_encoded_rows() => {
+                let row = self.expr.translate(meta);
+                let encoded = translate_row_encoding(meta, row, kind);
+                kind = Type::Text;
+                encoded
+            }
+            _ => self.expr.translate(meta),
+        };
+        VarStmtFragment::new(&self.name, kind, expr)
             .with_global_id(self.global_id)
             .with_ref(self.is_ref)
             .with_index(index)
@@ -0,0 +1,321 @@
+use crate::modules::prelude::*;
+use crate::translate::compute::{match_awk_computation, translate_awk_computation};
+use crate::translate::fragments::interpolable::InterpolablePart;
+use crate::translate::fragments::var_expr::VarIndexValue;
+
+// This optimizer evaluates computations whose operands are known at compile time.
+//
+// We handle the following cases:
+// 1. Arithmetic on integer literals (`$(( 2 * 60 ))` -> `120`)
+// 2. Awk computations on number literals (`$(awk ... 1.5 2)` -> `3`)
+// 3. Concatenation of string literals (`"foo""bar"` -> `"foobar"`)
+// 4. Conditional branches whose condition became a literal (`if [ 1 != 0 ]`)
+
+pub fn fold_constants(ast: &mut FragmentKind) {
+    match ast {
+        FragmentKind::Block(block) => {
+            for statement in block.statements.iter_mut() {
+                fold_constants(statement);
+            }
+        }
+        FragmentKind::Conditional(cond) => {
+            for frag in cond.fragments_mut() {
+                fold_constants(frag);
+            }
+            *ast = remove_dead_branches(std::mem::take(cond));
+        }
+        FragmentKind::Function(function) => {
+            for frag in function.fragments_mut() {
+                fold_constants(frag);
+            }
+        }
+        FragmentKind::List(list) => {
+            for item in list.values.iter_mut() {
+                fold_constants(item);
+            }
+            if list.is_concatenation() {
+                merge_string_literals(&mut list.values);
+            }
+        }
+        FragmentKind::Interpolable(interpolable) => {
+            for part in interpolable.parts.iter_mut() {
+                if let InterpolablePart::Interp(frag) = part {
+                    fold_constants(frag);
+                }
+            }
+        }
+        FragmentKind::Arithmetic(arith) => {
+            if let Some(left) = arith.left.as_mut() {
+                fold_constants(left);
+            }
+            if let Some(right) = arith.right.as_mut() {
+                fold_constants(right);
+            }
+            let left = arith.left.as_ref().as_ref().and_then(get_integer);
+            let right = arith.right.as_ref().as_ref().and_then(get_integer);
+            if let Some(value) = compute_integer(&arith.op, left, right) {
+                *ast = RawFragment::from(value.to_string()).to_frag();
+            }
+        }
+        FragmentKind::Subprocess(subprocess) => {
+            if let Some((op, left, right)) = match_awk_computation(subprocess) {
+                let quoted = subprocess.quoted;
+                let mut left = left.clone();
+                let mut right = right.cloned();
+                fold_constants(&mut left);
+                if let Some(right) = right.as_mut() {
+                    fold_constants(right);
+                }
+                let value = compute_number(&op, get_number(&left), right.as_ref().map(get_number));
+                *ast = match value.and_then(format_awk_number) {
+                    Some(value) => RawFragment::from(value).to_frag(),
+                    None => {
+                        let right = right.unwrap_or(FragmentKind::Empty);
+                        translate_awk_computation(op, left, right, quoted)
+                    }
+                };
+            } else {
+                fold_constants(&mut subprocess.fragment);
+            }
+        }
+        FragmentKind::VarStmt(var_stmt) => {
+            fold_constants(&mut var_stmt.value);
+            if let Some(index) = var_stmt.index.as_mut() {
+                fold_constants(index);
+            }
+        }
+        FragmentKind::VarExpr(var_expr) => {
+            if let Some(index) = var_expr.index.as_mut() {
+                match index.as_mut() {
+                    VarIndexValue::Index(index) | VarIndexValue::Key(index) => {
+                        fold_constants(index)
+                    }
+                    VarIndexValue::Range(start, end) => {
+                        fold_constants(start);
+                        fold_constants(end);
+                    }
+                }
+            }
+        }
+        FragmentKind::Log(log) => fold_constants(&mut log.value),
+        FragmentKind::Raw(_) | FragmentKind::Comment(_) | FragmentKind::Empty => {}
+    }
+}
+
+/// Drops branches whose condition is a literal. Returns the fragment
+/// that should replace the whole conditional statement.
+fn remove_dead_branches(mut cond: ConditionalFragment) -> FragmentKind {
+    let mut branches = vec![];
+    let mut otherwise = cond.otherwise.take();
+    for branch in std::mem::take(&mut cond.branches) {
+        match get_integer(&branch.condition).map(|value| value != 0) {
+            Some(false) => continue,
+            Some(true) => {
+                otherwise = Some((branch.comments, Box::new(branch.block)));
+                break;
+            }
+            None => branches.push(branch),
+        }
+    }
+    cond.branches = branches;
+    cond.otherwise = otherwise;
+    if !cond.branches.is_empty() {
+        return cond.to_frag();
+    }
+    // The remaining block always runs so it no longer needs to be indented
+    match cond.otherwise {
+        Some((comments, block)) => {
+            let block = match *block {
+                FragmentKind::Block(block) => BlockFragment {
+                    increase_indent: false,
+                    needs_noop: false,
+                    is_conditional: false,
+                    ..block
+                }
+                .to_frag(),
+                block => block,
+            };
+            BlockFragment::new([comments, vec![block]].concat(), false).to_frag()
+        }
+        None => FragmentKind::Empty,
+    }
+}
+
+/// Merges adjacent string literals, which the shell concatenates anyway
+fn merge_string_literals(values: &mut Vec<FragmentKind>) {
+    let mut result: Vec<FragmentKind> = Vec::with_capacity(values.len());
+    for value in std::mem::take(values) {
+        // Chained concatenations are nested lists that may have been merged already
+        let value = match value {
+            FragmentKind::List(mut list) if list.values.len() == 1 => list.values.remove(0),
+            value => value,
+        };
+        if let (Some(FragmentKind::Interpolable(last)), FragmentKind::Interpolable(next)) =
+            (result.last_mut(), &value)
+        {
+            if is_string_literal(last) && is_string_literal(next) {
+                let FragmentKind::Interpolable(next) = value else {
+                    unreachable!("Expected Interpolable");
+                };
+                last.parts.extend(next.parts);
+                continue;
+            }
+        }
+        result.push(value);
+    }
+    *values = result;
+}
+
+fn is_string_literal(interpolable: &InterpolableFragment) -> bool {
+    interpolable.quoted
+        && interpolable.render_type == InterpolableRenderType::StringLiteral
+        && interpolable
+            .parts
+            .iter()
+            .all(|part| matches!(part, InterpolablePart::String(_)))
+}
+
+fn get_integer(frag: &FragmentKind) -> Option<i64> {
+    let FragmentKind::Raw(raw) = frag else {
+        return None;
+    };
+    // Leading zeros would make the shell read the number as octal
+    let digits = raw.value.strip_prefix('-').unwrap_or(&raw.value);
+    let is_decimal = !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && (digits == "0" || !digits.starts_with('0'));
+    is_decimal.then(|| raw.value.parse().ok()).flatten()
+}
+
+fn get_number(frag: &FragmentKind) -> Option<f64> {
+    let FragmentKind::Raw(raw) = frag else {
+        return None;
+    };
+    let digits = raw.value.strip_prefix('-').unwrap_or(&raw.value);
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, "0"));
+    let is_decimal = [whole, fraction]
+        .iter()
+        .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    is_decimal.then(|| raw.value.parse().ok()).flatten()
+}
+
+/// Evaluates an operation the same way as shell arithmetic expansion does.
+/// Returns `None` when the result would be a runtime error.
+fn compute_integer(op: &ArithOp, left: Option<i64>, right: Option<i64>) -> Option<i64> {
+    let value = match (op, left, right) {
+        (ArithOp::Neg, None, Some(value)) => value.checked_neg()?,
+        (ArithOp::Not, None, Some(value)) => (value == 0).into(),
+        (_, Some(left), Some(right)) => match op {
+            ArithOp::Add => left.checked_add(right)?,
+            ArithOp::Sub => left.checked_sub(right)?,
+            ArithOp::Mul => left.checked_mul(right)?,
+            ArithOp::Div => left.checked_div(right)?,
+            ArithOp::Modulo => left.checked_rem(right)?,
+            ArithOp::Gt => (left > right).into(),
+            ArithOp::Ge => (left >= right).into(),
+            ArithOp::Lt => (left < right).into(),
+            ArithOp::Le => (left <= right).into(),
+            ArithOp::Eq => (left == right).into(),
+            ArithOp::Neq => (left != right).into(),
+            ArithOp::And => (left != 0 && right != 0).into(),
+            ArithOp::Or => (left != 0 || right != 0).into(),
+            ArithOp::Neg | ArithOp::Not => return None,
+        },
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Evaluates an operation the same way as the awk program does.
+fn compute_number(op: &ArithOp, left: Option<f64>, right: Option<Option<f64>>) -> Option<f64> {
+    let value = match (op, left?, right) {
+        (ArithOp::Neg, value, None) => -value,
+        (ArithOp::Not, value, None) => f64::from(value == 0.0),
+        (_, left, Some(Some(right))) => match op {
+            ArithOp::Add => left + right,
+            ArithOp::Sub => left - right,
+            ArithOp::Mul => left * right,
+            // Awk exits with an error on division by zero
+            ArithOp::Div | ArithOp::Modulo if right == 0.0 => return None,
+            ArithOp::Div => left / right,
+            ArithOp::Modulo => left % right,
+            ArithOp::Gt => f64::from(left > right),
+            ArithOp::Ge => f64::from(left >= right),
+            ArithOp::Lt => f64::from(left < right),
+            ArithOp::Le => f64::from(left <= right),
+            ArithOp::Eq => f64::from(left == right),
+            ArithOp::Neq => f64::from(left != right),
+            ArithOp::And | ArithOp::Or | ArithOp::Neg | ArithOp::Not => return None,
+        },
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Formats a number the way awk's `print` does: integral values are printed
+/// as integers and the rest is formatted with `%.6g`.
+fn format_awk_number(value: f64) -> Option<String> {
+    // Awk implementations disagree on how to print negative zero and huge integers
+    if !value.is_finite() || (value == 0.0 && value.is_sign_negative()) {
+        return None;
+    }
+    if value.fract() == 0.0 {
+        return (value.abs() < 1e15).then(|| format!("{}", value as i64));
+    }
+    let scientific = format!("{value:.5e}");
+    let (mantissa, exponent) = scientific.split_once('e')?;
+    let exponent: i32 = exponent.parse().ok()?;
+    let trim = |number: String| {
+        let number = number.trim_end_matches('0');
+        number.trim_end_matches('.').to_string()
+    };
+    if !(-4..6).contains(&exponent) {
+        let sign = if exponent < 0 { '-' } else { '+' };
+        let mantissa = trim(mantissa.to_string());
+        return Some(format!("{mantissa}e{sign}{:02}", exponent.abs()));
+    }
+    let precision = (5 - exponent) as usize;
+    Some(trim(format!("{value:.precision$}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_awk_number() {
+        assert_eq!(format_awk_number(3.0), Some("3".to_string()));
+        assert_eq!(format_awk_number(-120.0), Some("-120".to_string()));
+        assert_eq!(format_awk_number(0.5), Some("0.5".to_string()));
+        assert_eq!(format_awk_number(0.1 + 0.2), Some("0.3".to_string()));
+        assert_eq!(format_awk_number(1.0 / 3.0), Some("0.333333".to_string()));
+        assert_eq!(
+            format_awk_number(1234567.5),
+            Some("1.23457e+06".to_string())
+        );
+        assert_eq!(format_awk_number(0.00001), Some("1e-05".to_string()));
+        assert_eq!(format_awk_number(-0.0), None);
+        assert_eq!(format_awk_number(f64::INFINITY), None);
+    }
+
+    #[test]
+    fn test_get_integer_rejects_octal() {
+        assert_eq!(get_integer(&RawFragment::new("10").to_frag()), Some(10));
+        assert_eq!(get_integer(&RawFragment::new("-7").to_frag()), Some(-7));
+        assert_eq!(get_integer(&RawFragment::new("0").to_frag()), Some(0));
+        assert_eq!(get_integer(&RawFragment::new("010").to_frag()), None);
+        assert_eq!(get_integer(&RawFragment::new("1.5").to_frag()), None);
+    }
+
+    #[test]
+    fn test_compute_integer_matches_shell() {
+        assert_eq!(compute_integer(&ArithOp::Div, Some(-7), Some(2)), Some(-3));
+        assert_eq!(
+            compute_integer(&ArithOp::Modulo, Some(-7), Some(2)),
+            Some(-1)
+        );
+        assert_eq!(compute_integer(&ArithOp::Div, Some(1), Some(0)), None);
+        assert_eq!(compute_integer(&ArithOp::Neg, None, Some(5)), Some(-5));
+        assert_eq!(compute_integer(&ArithOp::Not, None, Some(5)), Some(0));
+    }
+}
@@ -62,5 +62,13 @@ pub fn remove_ephemeral_variables(ast: &mut FragmentKind) {
         for item in &mut block.statements {
             remove_ephemeral_variables(item);
         }
+    } else if let FragmentKind::Conditional(cond) = ast {
+        for item in cond.fragments_mut() {
+            remove_ephemeral_variables(item);
+        }
+    } else if let FragmentKind::Function(function) = ast {
+        for item in function.fragments_mut() {
+            remove_ephemeral_variables(item);
+        }
     }
 }
@@ -0,0 +1,517 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::modules::prelude::*;
+use crate::translate::fragments::function::InlineSignature;
+use crate::translate::fragments::interpolable::InterpolablePart;
+use crate::translate::fragments::var_expr::VarIndexValue;
+use crate::utils::function_cache::InstanceId;
+
+// This optimizer substitutes the bodies of small functions at the places
+// where they are called. The arguments are assigned to the parameters
+// directly and the returned value is left in the return variable, so that
+// the call and its plumbing are no longer needed.
+//
+// We inline functions that:
+// 1. Are trivial, having only a few statements (`ret="${text}"; return 0`)
+// 2. Are called once and have a moderately small body
+// Definitions of functions whose calls were all inlined are removed.
+
+/// Maximum number of statements of a function that is inlined at every call
+const TRIVIAL_SIZE: usize = 4;
+/// Maximum number of statements of a function that is inlined at its only call
+const SINGLE_USE_SIZE: usize = 16;
+/// Inlined bodies can contain calls of other functions that are inlined in the next round
+const MAX_ROUNDS: usize = 3;
+
+struct InlineCandidate {
+    signature: InlineSignature,
+    body: Vec<FragmentKind>,
+}
+
+pub fn inline_functions(ast: &mut FragmentKind) {
+    let mut inlined = HashSet::new();
+    for _ in 0..MAX_ROUNDS {
+        let mut candidates = HashMap::new();
+        collect_candidates(ast, &mut candidates);
+        candidates.retain(|instance, candidate| {
+            let size = count_statements(&candidate.body);
+            let uses = count_calls(ast, &|called| called == *instance);
+            size <= TRIVIAL_SIZE || (uses == 1 && size <= SINGLE_USE_SIZE)
+        });
+        if candidates.is_empty() {
+            break;
+        }
+        let count = inlined.len();
+        inline_calls(ast, &candidates, None, &mut inlined);
+        if inlined.len() == count {
+            break;
+        }
+    }
+    let unused = inlined
+        .into_iter()
+        .filter(|instance| count_calls(ast, &|called| called == *instance) == 0)
+        .collect::<HashSet<InstanceId>>();
+    remove_definitions(ast, &unused);
+}
+
+fn collect_candidates(ast: &FragmentKind, candidates: &mut HashMap<InstanceId, InlineCandidate>) {
+    match ast {
+        FragmentKind::Block(block) => {
+            for statement in block.statements.iter() {
+                collect_candidates(statement, candidates);
+            }
+        }
+        FragmentKind::Conditional(cond) => {
+            for frag in cond.fragments() {
+                collect_candidates(frag, candidates);
+            }
+        }
+        FragmentKind::Function(function) => {
+            for frag in function.fragments() {
+                collect_candidates(frag, candidates);
+            }
+            let Some(signature) = function.inline.clone() else {
+                return;
+            };
+            // Recursive functions would be inlined into themselves, even through other variants
+            let (id, _) = function.instance;
+            if function
+                .fragments()
+                .any(|frag| count_calls(frag, &|(called, _)| called == id) > 0)
+            {
+                return;
+            }
+            let statements = function
+                .body
+                .iter()
+                .flat_map(|frag| match frag {
+                    FragmentKind::Block(block) => block.statements.clone(),
+                    frag => vec![frag.clone()],
+                })
+                .collect();
+            if let Some(body) = remove_returns(statements) {
+                candidates.insert(function.instance, InlineCandidate { signature, body });
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_return(frag: &FragmentKind) -> bool {
+    match frag {
+        FragmentKind::Raw(raw) => raw.value == "return" || raw.value.starts_with("return "),
+        FragmentKind::List(list) => list.values.first().is_some_and(is_return),
+        _ => false,
+    }
+}
+
+/// Checks if the fragment returns from the function anywhere inside of it (eg. in a loop)
+fn contains_return(frag: &FragmentKind) -> bool {
+    match frag {
+        FragmentKind::Raw(_) => is_return(frag),
+        FragmentKind::Block(block) => block.statements.iter().any(contains_return),
+        FragmentKind::Conditional(cond) => cond.fragments().any(contains_return),
+        FragmentKind::List(list) => is_return(frag) || list.values.iter().any(contains_return),
+        FragmentKind::Subprocess(subprocess) => contains_return(&subprocess.fragment),
+        FragmentKind::Interpolable(interpolable) => {
+            interpolable.parts.iter().any(|part| match part {
+                InterpolablePart::String(_) => false,
+                InterpolablePart::Interp(frag) => contains_return(frag),
+            })
+        }
+        FragmentKind::VarStmt(var_stmt) => contains_return(&var_stmt.value),
+        FragmentKind::Arithmetic(arith) => [arith.left.as_ref(), arith.right.as_ref()]
+            .into_iter()
+            .flatten()
+            .any(contains_return),
+        FragmentKind::Log(log) => contains_return(&log.value),
+        FragmentKind::Function(_)
+        | FragmentKind::VarExpr(_)
+        | FragmentKind::Comment(_)
+        | FragmentKind::Empty => false,
+    }
+}
+
+/// Checks if every path through the statements returns from the function
+fn always_returns(statements: &[FragmentKind]) -> bool {
+    statements.iter().any(|statement| match statement {
+        FragmentKind::Block(block) if !block.increase_indent => always_returns(&block.statements),
+        FragmentKind::Conditional(cond) => {
+            let is_returning = |block: &FragmentKind| match block {
+                FragmentKind::Block(block) => always_returns(&block.statements),
+                _ => false,
+            };
+            cond.branches
+                .iter()
+                .all(|branch| is_returning(&branch.block))
+                && cond
+                    .otherwise
+                    .as_ref()
+                    .is_some_and(|(_, block)| is_returning(block))
+        }
+        statement => is_return(statement),
+    })
+}
+
+/// Restructures the statements so that the function ends at the end of them.
+/// Statements that follow a conditional return are moved to the branches that
+/// do not return. Returns `None` if a return cannot be removed (eg. in a loop).
+fn remove_returns(statements: Vec<FragmentKind>) -> Option<Vec<FragmentKind>> {
+    let mut result = vec![];
+    let mut statements = statements.into_iter();
+    while let Some(statement) = statements.next() {
+        if is_return(&statement) {
+            return Some(result);
+        }
+        if !contains_return(&statement) {
+            result.push(statement);
+            continue;
+        }
+        match statement {
+            FragmentKind::Block(block) if !block.increase_indent => {
+                let rest = block.statements.into_iter().chain(statements).collect();
+                result.extend(remove_returns(rest)?);
+            }
+            FragmentKind::Conditional(mut cond) => {
+                let rest = statements.collect::<Vec<_>>();
+                for branch in cond.branches.iter_mut() {
+                    remove_branch_returns(&mut branch.block, &rest)?;
+                }
+                match cond.otherwise.as_mut() {
+                    Some((_, block)) => remove_branch_returns(block, &rest)?,
+                    None if !rest.is_empty() => {
+                        let block = BlockFragment::new(remove_returns(rest)?, true)
+                            .with_needs_noop(true)
+                            .with_condition(true);
+                        cond.set_otherwise(vec![], block.to_frag());
+                    }
+                    None => {}
+                }
+                result.push(cond.to_frag());
+            }
+            _ => return None,
+        }
+        return Some(result);
+    }
+    Some(result)
+}
+
+fn remove_branch_returns(block: &mut FragmentKind, rest: &[FragmentKind]) -> Option<()> {
+    let FragmentKind::Block(block) = block else {
+        return None;
+    };
+    let mut statements = std::mem::take(&mut block.statements);
+    if !always_returns(&statements) {
+        statements.extend(rest.iter().cloned());
+    }
+    block.statements = remove_returns(statements)?;
+    block.needs_noop = true;
+    Some(())
+}
+
+fn count_statements(statements: &[FragmentKind]) -> usize {
+    statements
+        .iter()
+        .map(|statement| match statement {
+            FragmentKind::Empty | FragmentKind::Comment(_) => 0,
+            FragmentKind::Block(block) => count_statements(&block.statements),
+            FragmentKind::Conditional(cond) => {
+                let blocks = cond
+                    .branches
+                    .iter()
+                    .map(|branch| &branch.block)
+                    .chain(cond.otherwise.iter().map(|(_, block)| block.as_ref()));
+                1 + blocks
+                    .map(|block| count_statements(std::slice::from_ref(block)))
+                    .sum::<usize>()
+            }
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Counts the calls of the function instances that match the predicate
+fn count_calls(ast: &FragmentKind, is_called: &impl Fn(InstanceId) -> bool) -> usize {
+    match ast {
+        FragmentKind::Raw(raw) => raw
+            .function
+            .map_or(0, |called| usize::from(is_called(called))),
+        FragmentKind::VarExpr(var_expr) => match var_expr.index.as_deref() {
+            Some(VarIndexValue::Index(index) | VarIndexValue::Key(index)) => {
+                count_calls(index, is_called)
+            }
+            Some(VarIndexValue::Range(start, end)) => {
+                count_calls(start, is_called) + count_calls(end, is_called)
+            }
+            None => 0,
+        },
+        FragmentKind::VarStmt(var_stmt) => {
+            count_calls(&var_stmt.value, is_called)
+                + var_stmt
+                    .index
+                    .as_ref()
+                    .map_or(0, |index| count_calls(index, is_called))
+        }
+        FragmentKind::Block(block) => block
+            .statements
+            .iter()
+            .map(|frag| count_calls(frag, is_called))
+            .sum(),
+        FragmentKind::Conditional(cond) => cond
+            .fragments()
+            .map(|frag| count_calls(frag, is_called))
+            .sum(),
+        FragmentKind::Function(function) => function
+            .fragments()
+            .map(|frag| count_calls(frag, is_called))
+            .sum(),
+        FragmentKind::Interpolable(interpolable) => interpolable
+            .parts
+            .iter()
+            .map(|part| match part {
+                InterpolablePart::String(_) => 0,
+                InterpolablePart::Interp(frag) => count_calls(frag, is_called),
+            })
+            .sum(),
+        FragmentKind::List(list) => list
+            .values
+            .iter()
+            .map(|frag| count_calls(frag, is_called))
+            .sum(),
+        FragmentKind::Subprocess(subprocess) => count_calls(&subprocess.fragment, is_called),
+        FragmentKind::Arithmetic(arith) => [arith.left.as_ref(), arith.right.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(|frag| count_calls(frag, is_called))
+            .sum(),
+        FragmentKind::Log(log) => count_calls(&log.value, is_called),
+        FragmentKind::Comment(_) | FragmentKind::Empty => 0,
+    }
+}
+
+/// Matches a call statement and returns the called function instance and the arguments
+fn match_call(statement: &FragmentKind) -> Option<(InstanceId, &[FragmentKind])> {
+    let FragmentKind::List(list) = statement else {
+        return None;
+    };
+    match list.values.as_slice() {
+        // Calls that are silenced or suppressed are redirected as a whole
+        [FragmentKind::Raw(name), FragmentKind::Raw(space), FragmentKind::List(args), FragmentKind::Empty, FragmentKind::Empty]
+            if space.value == " " =>
+        {
+            Some((name.function?, args.values.as_slice()))
+        }
+        _ => None,
+    }
+}
+
+fn inline_calls(
+    ast: &mut FragmentKind,
+    candidates: &HashMap<InstanceId, InlineCandidate>,
+    function: Option<InstanceId>,
+    inlined: &mut HashSet<InstanceId>,
+) {
+    match ast {
+        FragmentKind::Block(block) => {
+            let mut statements = vec![];
+            for mut statement in std::mem::take(&mut block.statements) {
+                let expanded = match_call(&statement)
+                    .filter(|(called, _)| function != Some(*called))
+                    .and_then(|(called, args)| {
+                        let candidate = candidates.get(&called)?;
+                        let expanded = expand_call(candidate, args, function.is_some())?;
+                        inlined.insert(called);
+                        Some(expanded)
+                    });
+                match expanded {
+                    Some(expanded) => statements.extend(expanded),
+                    None => {
+                        inline_calls(&mut statement, candidates, function, inlined);
+                        statements.push(statement);
+                    }
+                }
+            }
+            block.statements = statements;
+        }
+        FragmentKind::Conditional(cond) => {
+            for frag in cond.fragments_mut() {
+                inline_calls(frag, candidates, function, inlined);
+            }
+        }
+        FragmentKind::Function(definition) => {
+            let instance = definition.instance;
+            for frag in definition.fragments_mut() {
+                inline_calls(frag, candidates, Some(instance), inlined);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Creates the statements that replace the call of the function
+fn expand_call(
+    candidate: &InlineCandidate,
+    args: &[FragmentKind],
+    is_local: bool,
+) -> Option<Vec<FragmentKind>> {
+    let signature = &candidate.signature;
+    if signature.params.len() != args.len() {
+        return None;
+    }
+    let mut result = vec![];
+    for (param, arg) in signature.params.iter().zip(args) {
+        let mut param = param.clone();
+        param.value = Box::new(arg.clone());
+        param.is_local = is_local;
+        result.push(param.to_frag());
+    }
+    for mut statement in candidate.body.iter().cloned() {
+        prepare_statement(&mut statement, &signature.return_name, is_local);
+        result.push(statement);
+    }
+    Some(result)
+}
+
+fn prepare_statement(statement: &mut FragmentKind, return_name: &str, is_local: bool) {
+    match statement {
+        FragmentKind::VarStmt(var_stmt) if var_stmt.get_name() == return_name => {
+            // The returned value is read right after the call
+            var_stmt.is_ephemeral = true;
+            var_stmt.optimize_unused = true;
+        }
+        // Variables of the function cannot be local outside of functions
+        FragmentKind::VarStmt(var_stmt) => var_stmt.is_local &= is_local,
+        FragmentKind::Block(block) => {
+            for statement in block.statements.iter_mut() {
+                prepare_statement(statement, return_name, is_local);
+            }
+        }
+        FragmentKind::Conditional(cond) => {
+            for frag in cond.fragments_mut() {
+                prepare_statement(frag, return_name, is_local);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn remove_definitions(ast: &mut FragmentKind, unused: &HashSet<InstanceId>) {
+    match ast {
+        FragmentKind::Block(block) => {
+            for statement in block.statements.iter_mut() {
+                remove_definitions(statement, unused);
+            }
+        }
+        FragmentKind::Conditional(cond) => {
+            for frag in cond.fragments_mut() {
+                remove_definitions(frag, unused);
+            }
+        }
+        FragmentKind::Function(function) if unused.contains(&function.instance) => {
+            *ast = FragmentKind::Empty;
+        }
+        FragmentKind::Function(function) => {
+            for frag in function.fragments_mut() {
+                remove_definitions(frag, unused);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(value: &str) -> FragmentKind {
+        RawFragment::new(value).to_frag()
+    }
+
+    fn call(name: &str, instance: InstanceId) -> FragmentKind {
+        let name = RawFragment::new(name).with_function(instance).to_frag();
+        let args = ListFragment::new(vec![]).with_spaces().to_frag();
+        ListFragment::new(vec![
+            name,
+            raw(" "),
+            args,
+            FragmentKind::Empty,
+            FragmentKind::Empty,
+        ])
+        .to_frag()
+    }
+
+    fn function(name: &str, instance: InstanceId, body: Vec<FragmentKind>) -> FragmentKind {
+        let signature = InlineSignature {
+            params: vec![],
+            return_name: format!("ret_{name}"),
+        };
+        FunctionFragment::new(name, instance, vec![], body)
+            .with_inline(Some(signature))
+            .to_frag()
+    }
+
+    #[test]
+    fn test_count_calls_with_prefix_colliding_names() {
+        let ast = BlockFragment::new(
+            vec![
+                call("foo__1_v0", (1, 0)),
+                call("foo__12_v0", (12, 0)),
+                call("foo_bar__1_v0", (13, 0)),
+                raw("echo foo__1_v0"),
+            ],
+            false,
+        )
+        .to_frag();
+        assert_eq!(count_calls(&ast, &|called| called == (1, 0)), 1);
+        assert_eq!(count_calls(&ast, &|called| called == (12, 0)), 1);
+        assert_eq!(count_calls(&ast, &|(id, _)| id == 13), 1);
+    }
+
+    #[test]
+    fn test_collect_candidates_with_names_containing_variant_marker() {
+        let ast = BlockFragment::new(
+            vec![
+                function("get_v__2_v0", (2, 0), vec![raw("ret_get_v__2_v0=1")]),
+                // Calls another variant of itself, so it is recursive
+                function("get_v__2_v1", (2, 1), vec![call("get_v__2_v0", (2, 0))]),
+                function("my_v__3_v0", (3, 0), vec![call("get_v__2_v1", (2, 1))]),
+            ],
+            false,
+        )
+        .to_frag();
+        let mut candidates = HashMap::new();
+        collect_candidates(&ast, &mut candidates);
+        let mut instances = candidates.into_keys().collect::<Vec<_>>();
+        instances.sort();
+        assert_eq!(instances, [(2, 0), (3, 0)]);
+    }
+
+    #[test]
+    fn test_is_return() {
+        assert!(is_return(&raw("return")));
+        assert!(is_return(&raw("return 0")));
+        assert!(!is_return(&raw("returned=1")));
+        assert!(!is_return(&raw("echo return")));
+    }
+
+    #[test]
+    fn test_remove_returns_stops_at_return() {
+        let statements = vec![raw("a=1"), raw("return 0"), raw("b=2")];
+        assert_eq!(remove_returns(statements), Some(vec![raw("a=1")]));
+    }
+
+    #[test]
+    fn test_remove_returns_rejects_return_in_loop() {
+        let body = BlockFragment::new(vec![raw("return 0")], true).to_frag();
+        let for_loop =
+            ListFragment::new(vec![raw("for i in 1 2; do"), body, raw("done")]).to_frag();
+        assert!(contains_return(&for_loop));
+        assert_eq!(remove_returns(vec![for_loop, raw("return 1")]), None);
+    }
+
+    #[test]
+    fn test_remove_returns_rejects_indented_block() {
+        let body = BlockFragment::new(vec![raw("return 0")], true).to_frag();
+        assert_eq!(remove_returns(vec![body]), None);
+    }
+}
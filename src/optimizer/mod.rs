@@ -1,11 +1,17 @@
 use crate::modules::prelude::*;
+use constant_folding::fold_constants;
 use ephemeral_vars::remove_ephemeral_variables;
+use inlining::inline_functions;
 use unused_vars::remove_unused_variables;
 
+pub mod constant_folding;
 pub mod ephemeral_vars;
+pub mod inlining;
 pub mod unused_vars;
 
 pub fn optimize_fragments(ast: &mut FragmentKind) {
+    inline_functions(ast);
+    fold_constants(ast);
     remove_unused_variables(ast);
     remove_ephemeral_variables(ast);
 }
@@ -124,6 +124,14 @@ fn remove_non_existing_variables(ast: &mut FragmentKind, meta: &mut UnusedVariab
                 if !meta.is_var_used(var_stmt.get_name()) {
                     remove_indexes.push(index);
                 }
+            } else if let FragmentKind::Conditional(cond) = statement {
+                for frag in cond.fragments_mut() {
+                    remove_non_existing_variables(frag, meta);
+                }
+            } else if let FragmentKind::Function(function) = statement {
+                for frag in function.fragments_mut() {
+                    remove_non_existing_variables(frag, meta);
+                }
             } else {
                 remove_non_existing_variables(statement, meta);
             }
@@ -159,6 +167,16 @@ fn find_unused_variables(ast: &FragmentKind, meta: &mut UnusedVariablesMetadata)
                     .push_back(SymbolType::ConditionalBlock(CondBlockBehavior::End));
             }
         }
+        FragmentKind::Conditional(cond) => {
+            for item in cond.fragments() {
+                find_unused_variables(item, meta);
+            }
+        }
+        FragmentKind::Function(function) => {
+            for item in function.fragments() {
+                find_unused_variables(item, meta);
+            }
+        }
         FragmentKind::List(list) => {
             for item in list.values.iter() {
                 find_unused_variables(item, meta);
@@ -186,7 +204,7 @@ fn find_unused_variables(ast: &FragmentKind, meta: &mut UnusedVariablesMetadata)
                     Ok(())
                 })
                 .unwrap();
-                let dependencies = meta.dependent_variables.drain(..).collect();
+                let dependencies = std::mem::take(&mut meta.dependent_variables);
                 meta.symbols
                     .push_back(SymbolType::Statement(var_stmt.get_name(), dependencies));
             } else {
@@ -205,7 +223,9 @@ fn find_unused_variables(ast: &FragmentKind, meta: &mut UnusedVariablesMetadata)
             }
             if let Some(index) = &var_expr.index {
                 match index.as_ref() {
-                    VarIndexValue::Index(index) => find_unused_variables(index, meta),
+                    VarIndexValue::Index(index) | VarIndexValue::Key(index) => {
+                        find_unused_variables(index, meta)
+                    }
                     VarIndexValue::Range(start, end) => {
                         find_unused_variables(start, meta);
                         find_unused_variables(end, meta);
@@ -0,0 +1,9 @@
+__amber_defers=
+__amber_defers_run() {
+    while [ "${__amber_defers}" != "$1" ]; do
+        __amber_defer=${__amber_defers%% *}
+        __amber_defers=${__amber_defers#* }
+        "${__amber_defer}"
+    done
+    return "${2:-0}"
+}
@@ -0,0 +1,25 @@
+__US=$(printf '\037')
+__amber_len() {
+    IFS=$__US; set -f; set -- $1; echo $#
+}
+__amber_at() {
+    __index=$(( $2 )); IFS=$__US; set -f; set -- $1
+    [ "$__index" -lt 0 ] && __index=$(( $# + __index ))
+    shift "$__index"; printf '%s' "$1"
+}
+__amber_slice() {
+    __offset=$(( $2 )); __length=$(( $3 )); IFS=$__US; set -f; set -- $1; shift "$__offset"
+    while [ "$__length" -gt 0 ] && [ $# -gt 0 ]; do printf '%s%s' "$1" "$__US"; shift; __length=$((__length - 1)); done
+}
+__amber_join() {
+    IFS=$__US; set -f; set -- $1; IFS=' '; printf '%s' "$*"
+}
+__amber_set_at() {
+    eval "__array=\${$1}"; __head=; __index=$(( $2 ))
+    [ "$__index" -lt 0 ] && __index=$(( $(__amber_len "$__array") + __index ))
+    while [ "$__index" -gt 0 ]; do __head=$__head${__array%%"$__US"*}$__US; __array=${__array#*"$__US"}; __index=$((__index - 1)); done
+    eval "$1=\$__head\$3\$__US\${__array#*\"\$__US\"}"
+}
+__amber_lines() {
+    while IFS= read -r __line || [ -n "$__line" ]; do printf '%s%s' "$__line" "$__US"; done
+}
@@ -0,0 +1,2 @@
+EXEC_SHELL="sh"
+EXEC_SHELL_VERSION=""
@@ -0,0 +1,13 @@
+__amber_trace=
+__amber_trace_start() {
+    __amber_trace="    at $1
+"
+}
+__amber_trace_push() {
+    __amber_trace="${__amber_trace}    at $1
+"
+}
+__amber_trace_exit() {
+    printf 'Failed with exit code %s\n%s' "$1" "${__amber_trace}" >&2
+    exit "$1"
+}
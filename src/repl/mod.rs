@@ -0,0 +1,230 @@
+pub mod session;
+
+use crate::compiler::{AmberCompiler, CompilerOptions};
+use crate::modules::block::Block;
+use crate::modules::builtin::echo::Echo;
+use crate::modules::function::declaration::FunctionDeclaration;
+use crate::modules::prelude::*;
+use crate::modules::statement::stmt::StmtType;
+use crate::modules::types::{Type, Typed};
+use crate::translate::check_all_blocks;
+use crate::utils::context::ScopeUnit;
+use crate::utils::ShellType;
+use crate::ReplCommand;
+use colored::Colorize;
+use heraclitus_compiler::prelude::*;
+use session::ShellSession;
+use std::collections::HashSet;
+use std::error::Error;
+use std::io::{stdin, stdout, BufRead, Write};
+
+const PROMPT: &str = "amber> ";
+const CONTINUATION_PROMPT: &str = "...... ";
+
+/// Incremental compiler that keeps the declarations of the previous entries in scope
+pub struct Repl {
+    meta: ParserMetadata,
+    translate_meta: TranslateMetadata,
+    target: Option<ShellType>,
+    /// Declared functions with the number of their instances that were already emitted
+    functions: Vec<(FunctionDeclaration, usize)>,
+    /// Imported files whose code was already emitted
+    emitted_files: HashSet<usize>,
+    preamble_emitted: bool,
+    sudo_emitted: bool,
+    shell_metadata_emitted: bool,
+    defer_emitted: bool,
+}
+
+impl Repl {
+    pub fn new(target: Option<ShellType>) -> Self {
+        let mut meta = ParserMetadata::new(vec![], None, None);
+        // The global scope is never popped so that declarations stay visible to later entries
+        meta.context.scopes.push(ScopeUnit::new());
+        let options = Self::options(target);
+        let translate_meta =
+            TranslateMetadata::new(ParserMetadata::new(vec![], None, None), &options);
+        Repl {
+            meta,
+            translate_meta,
+            target,
+            functions: vec![],
+            emitted_files: HashSet::new(),
+            preamble_emitted: false,
+            sudo_emitted: false,
+            shell_metadata_emitted: false,
+            defer_emitted: false,
+        }
+    }
+
+    fn options(target: Option<ShellType>) -> CompilerOptions {
+        // Optimizations would remove variables that are used by the following entries
+        let mut options = CompilerOptions::default().with_target(target);
+        options.no_optimize = true;
+        options
+    }
+
+    /// Compiles a single entry to shell code that continues the code of the previous entries
+    pub fn compile(&mut self, code: &str) -> Result<(Vec<Message>, String), Message> {
+        let compiler = AmberCompiler::new(code.to_string(), None, Self::options(self.target));
+        let tokens = compiler.tokenize()?;
+        self.meta.context.expr = tokens;
+        self.meta.context.index = 0;
+        self.meta.eval_code = compiler.cc.code.clone();
+        if let Err(Failure::Loud(err)) = check_all_blocks(&self.meta) {
+            return Err(err);
+        }
+        // Declarations, functions and imports of an entry that fails to compile are discarded
+        let snapshot = self.meta.clone();
+        let block = match self.parse_entry() {
+            Ok(block) => block,
+            Err(err) => {
+                self.meta = snapshot;
+                return Err(err);
+            }
+        };
+        let messages = std::mem::take(&mut self.meta.messages);
+        Ok((messages, self.translate_entry(&compiler, block)))
+    }
+
+    fn parse_entry(&mut self) -> Result<Block, Message> {
+        let mut block = Block::new().with_no_syntax();
+        block.parse(&mut self.meta).map_err(Failure::unwrap_loud)?;
+        block
+            .typecheck(&mut self.meta)
+            .map_err(Failure::unwrap_loud)?;
+        AmberCompiler::check_target_support(&mut self.meta, self.translate_meta.target.shell)?;
+        // Show the value of a lone expression
+        if let [statement] = block.statements.as_mut_slice() {
+            if let Some(StmtType::Expr(expr)) = &statement.value {
+                if expr.get_type() != Type::Null {
+                    statement.value = Some(StmtType::Echo(Echo::from_expr(expr.clone())));
+                }
+            }
+        }
+        Ok(block)
+    }
+
+    fn translate_entry(&mut self, compiler: &AmberCompiler, block: Block) -> String {
+        let mut fragments = vec![];
+        std::mem::swap(&mut self.meta.fun_cache, &mut self.translate_meta.fun_cache);
+        // Functions are emitted again when the entry created new instances of them
+        for (function, emitted) in self.functions.iter_mut() {
+            let instances = self.translate_meta.fun_cache.get_instances(function.id);
+            let count = instances.map_or(0, |instances| instances.len());
+            if count > *emitted {
+                let fragment = function.translate(&mut self.translate_meta);
+                fragments.push(BlockFragment::new(vec![fragment], true).to_frag());
+                *emitted = count;
+            }
+        }
+        let mut blocks = vec![];
+        for index in self.meta.import_cache.topological_sort() {
+            let file = &self.meta.import_cache.files[index];
+            if let Some(metadata) = &file.metadata {
+                if self.emitted_files.insert(index) {
+                    blocks.push(metadata.block.clone());
+                }
+            }
+        }
+        blocks.push(block);
+        for block in blocks {
+            fragments.push(block.translate(&mut self.translate_meta));
+            for statement in block.statements {
+                if let Some(StmtType::FunctionDeclaration(function)) = statement.value {
+                    let instances = self.translate_meta.fun_cache.get_instances(function.id);
+                    let count = instances.map_or(0, |instances| instances.len());
+                    self.functions.push((function, count));
+                }
+            }
+        }
+        // The preamble is known once the entry has been translated
+        let sudo = self.translate_meta.sudo_used && !self.sudo_emitted;
+        let shell_metadata =
+            self.translate_meta.shell_metadata_used && !self.shell_metadata_emitted;
+        let defer = self.translate_meta.defer_used && !self.defer_emitted;
+        if !self.preamble_emitted || sudo || shell_metadata || defer {
+            let shell = self.translate_meta.target.shell;
+            let preamble = compiler.gen_preamble(sudo, shell_metadata, defer, &shell);
+            fragments.insert(0, preamble);
+            self.preamble_emitted = true;
+            self.sudo_emitted |= sudo;
+            self.shell_metadata_emitted |= shell_metadata;
+            self.defer_emitted |= defer;
+        }
+        let result = BlockFragment::new(fragments, false)
+            .to_frag()
+            .to_string(&mut self.translate_meta);
+        std::mem::swap(&mut self.meta.fun_cache, &mut self.translate_meta.fun_cache);
+        result
+    }
+}
+
+/// Determines if the entry has unclosed brackets or literals and continues on the next line
+pub fn needs_more_input(code: &str) -> bool {
+    let compiler = AmberCompiler::new(code.to_string(), None, CompilerOptions::default());
+    match compiler.cc.tokenize() {
+        Ok(tokens) => {
+            let depth = tokens
+                .iter()
+                .fold(0, |depth, token| match token.word.as_str() {
+                    "(" | "[" | "{" => depth + 1,
+                    ")" | "]" | "}" => depth - 1,
+                    _ => depth,
+                });
+            depth > 0
+        }
+        Err((LexerErrorType::Unclosed, _)) => true,
+        Err((LexerErrorType::Singleline, _)) => false,
+    }
+}
+
+fn prompt(text: &str) {
+    print!("{}", text.dimmed());
+    stdout().flush().ok();
+}
+
+pub fn handle_repl(command: ReplCommand) -> Result<i32, Box<dyn Error>> {
+    let mut repl = Repl::new(command.target);
+    let mut session = ShellSession::spawn(command.target)?;
+    println!(
+        "Amber {} (type ':quit' or press Ctrl-D to exit)",
+        crate::get_version()
+    );
+    let mut entry = String::new();
+    let mut lines = stdin().lock().lines();
+    loop {
+        prompt(if entry.is_empty() {
+            PROMPT
+        } else {
+            CONTINUATION_PROMPT
+        });
+        let Some(line) = lines.next().transpose()? else {
+            println!();
+            break;
+        };
+        if entry.is_empty() && [":quit", ":q", ":exit"].contains(&line.trim()) {
+            break;
+        }
+        entry.push_str(&line);
+        entry.push('\n');
+        if entry.trim().is_empty() {
+            entry.clear();
+            continue;
+        }
+        if needs_more_input(&entry) {
+            continue;
+        }
+        match repl.compile(&entry) {
+            Ok((messages, code)) => {
+                messages.iter().for_each(|message| message.show());
+                if let Some(exit_code) = session.run(&code)? {
+                    return Ok(exit_code);
+                }
+            }
+            Err(err) => err.show(),
+        }
+        entry.clear();
+    }
+    session.close()
+}
@@ -0,0 +1,100 @@
+use crate::compiler::AmberCompiler;
+use crate::utils::ShellType;
+use std::error::Error;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::process::{Child, Command};
+use tempfile::TempDir;
+
+/// Reads the code of each entry from the first pipe and reports the completion to the second one.
+/// The standard input stays connected to the terminal so that scripts can read from it.
+const SESSION_SCRIPT: &str = r#"exec 3<"$1" 4>"$2"
+while IFS= read -r __amber_repl_entry <&3; do
+    . "$3"
+    printf '%s\n' "$?" >&4
+done"#;
+
+/// Long-lived shell process that runs the compiled entries of the REPL
+pub struct ShellSession {
+    child: Child,
+    entries: Option<File>,
+    completions: BufReader<File>,
+    /// Private directory of the pipes, which is removed when the session is dropped
+    dir: TempDir,
+}
+
+impl ShellSession {
+    pub fn spawn(target: Option<ShellType>) -> Result<Self, Box<dyn Error>> {
+        let dir = tempfile::Builder::new().prefix("amber-repl-").tempdir()?;
+        let (entries, completions, code) = (
+            dir.path().join("entries"),
+            dir.path().join("completions"),
+            dir.path().join("entry.sh"),
+        );
+        let status = Command::new("mkfifo")
+            .arg(&entries)
+            .arg(&completions)
+            .status()?;
+        if !status.success() {
+            return Err("Failed to create pipes for the shell session".into());
+        }
+        let Some(mut command) = AmberCompiler::find_shell(target) else {
+            return Err(io::Error::new(ErrorKind::NotFound, "Failed to find shell").into());
+        };
+        let child = command
+            .arg("-c")
+            .arg(SESSION_SCRIPT)
+            .arg("amber")
+            .arg(&entries)
+            .arg(&completions)
+            .arg(&code)
+            .spawn()?;
+        // The pipes are opened in the same order as in the session script
+        let entries = OpenOptions::new().write(true).open(&entries)?;
+        let completions = BufReader::new(File::open(&completions)?);
+        Ok(ShellSession {
+            child,
+            entries: Some(entries),
+            completions,
+            dir,
+        })
+    }
+
+    /// Runs the code in the shell and waits until it is done.
+    /// Returns the exit code if the code terminated the shell.
+    pub fn run(&mut self, code: &str) -> Result<Option<i32>, Box<dyn Error>> {
+        fs::write(self.dir.path().join("entry.sh"), code)?;
+        let Some(entries) = self.entries.as_mut() else {
+            return Ok(Some(1));
+        };
+        if writeln!(entries, "run")
+            .and_then(|_| entries.flush())
+            .is_err()
+        {
+            return self.wait().map(Some);
+        }
+        let mut line = String::new();
+        match self.completions.read_line(&mut line)? {
+            0 => self.wait().map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Ends the session and returns the exit code of the shell
+    pub fn close(mut self) -> Result<i32, Box<dyn Error>> {
+        self.wait()
+    }
+
+    fn wait(&mut self) -> Result<i32, Box<dyn Error>> {
+        // Closing the pipe ends the loop of the session script
+        self.entries = None;
+        Ok(self.child.wait()?.code().unwrap_or(1))
+    }
+}
+
+impl Drop for ShellSession {
+    fn drop(&mut self) {
+        self.entries = None;
+        self.child.wait().ok();
+    }
+}
@@ -3,9 +3,14 @@ use crate::TestCommand;
 use colored::Colorize;
 use heraclitus_compiler::prelude::Message;
 use rayon::prelude::*;
+use reporter::{render_report, TestReporter, TestResult};
 use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+pub mod reporter;
 
 pub fn find_amber_files(dir: &PathBuf, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
     if dir.is_dir() {
@@ -24,9 +29,7 @@ pub fn find_amber_files(dir: &PathBuf, files: &mut Vec<PathBuf>) -> std::io::Res
     Ok(())
 }
 
-pub fn get_tests_to_run(
-    command: &TestCommand,
-) -> Result<Vec<(PathBuf, String, String)>, Vec<Message>> {
+fn get_files_to_test(command: &TestCommand) -> Result<Vec<PathBuf>, Vec<Message>> {
     let input_path = &command.input;
     let mut files = vec![];
     if input_path.is_dir() {
@@ -36,11 +39,24 @@ pub fn get_tests_to_run(
         files.push(input_path.clone());
     }
     files.sort();
+    Ok(files)
+}
+
+fn matches_test_case(command: &TestCommand, name: &str) -> bool {
+    match &command.test_case {
+        Some(test_case) => name.trim().starts_with(test_case.trim()),
+        None => true,
+    }
+}
+
+pub fn get_tests_to_run(
+    command: &TestCommand,
+) -> Result<Vec<(PathBuf, String, String)>, Vec<Message>> {
+    let files = get_files_to_test(command)?;
 
     // Discovery phase
     let mut tests = vec![];
     let mut errors = vec![];
-    let test_case = &command.test_case;
     for file in &files {
         let code = match fs::read_to_string(file) {
             Ok(c) => c,
@@ -55,7 +71,8 @@ pub fn get_tests_to_run(
         };
 
         let options = CompilerOptions::from_args(&command.no_proc, false, true, None)
-            .with_target(command.target);
+            .with_target(command.target)
+            .with_project_config(command.project_config);
         let compiler = AmberCompiler::new(
             code.clone(),
             Some(file.to_string_lossy().to_string()),
@@ -66,10 +83,8 @@ pub fn get_tests_to_run(
             Ok(tokens) => match compiler.parse(tokens) {
                 Ok((_, meta)) => {
                     for name in meta.test_names {
-                        if let Some(test_case) = test_case {
-                            if !name.trim().starts_with(test_case.trim()) {
-                                continue;
-                            }
+                        if !matches_test_case(command, &name) {
+                            continue;
                         }
 
                         tests.push((file.clone(), name, code.clone()));
@@ -94,8 +109,196 @@ pub fn get_tests_to_run(
     Ok(tests)
 }
 
+/// Usage example from a doc comment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocExample {
+    pub code: String,
+    /// Example marked with `no_run` that is only compiled
+    pub no_run: bool,
+}
+
+/// Extracts the Amber code blocks from a doc comment. Blocks marked with
+/// `ignore` (as in ```` ```ab ignore ````) are skipped.
+pub fn get_doc_examples(comment: &str) -> Vec<DocExample> {
+    let mut examples = vec![];
+    // Code of the open block and whether it is ignored
+    let mut block: Option<(DocExample, bool)> = None;
+    for line in comment.lines() {
+        let trimmed = line.trim();
+        match block.take() {
+            Some((example, is_ignored)) if trimmed.starts_with("```") => {
+                if !is_ignored {
+                    examples.push(example);
+                }
+            }
+            Some((mut example, is_ignored)) => {
+                example.code.push_str(line);
+                example.code.push('\n');
+                block = Some((example, is_ignored));
+            }
+            None => {
+                let Some(info) = trimmed.strip_prefix("```") else {
+                    continue;
+                };
+                let mut attributes = info.split(|c: char| c == ',' || c.is_whitespace());
+                // Code blocks of other languages are skipped
+                let is_amber = attributes.next() == Some("ab");
+                let attributes = attributes.collect::<Vec<_>>();
+                let example = DocExample {
+                    code: String::new(),
+                    no_run: attributes.contains(&"no_run"),
+                };
+                block = Some((example, !is_amber || attributes.contains(&"ignore")));
+            }
+        }
+    }
+    examples
+}
+
+/// Wraps the example in a main block, so that it can propagate failures with `?`.
+/// Examples that declare their own imports, functions or main block are left as they are.
+fn get_doc_example_code(file_name: &str, example: &str) -> String {
+    let is_item = |line: &str| {
+        ["import ", "pub ", "fun ", "main"]
+            .iter()
+            .any(|keyword| line.starts_with(keyword))
+    };
+    let body = if example.lines().any(is_item) {
+        example.to_string()
+    } else {
+        let lines = example
+            .lines()
+            .map(|line| match line.is_empty() {
+                true => String::new(),
+                false => format!("    {line}"),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("main {{\n{lines}\n}}\n")
+    };
+    format!("import * from \"{file_name}\"\n{body}")
+}
+
+/// Path under which the examples of a documented file are compiled. It lies
+/// next to the file, so that the file can be imported by its name.
+pub fn get_doctest_path(file: &Path) -> PathBuf {
+    let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+    file.with_file_name(format!("{stem}.doctest.ab"))
+}
+
+/// Test case of a file. Doc tests keep the documented file in `file`.
+#[derive(Debug, Clone)]
+pub struct TestCase {
+    pub file: PathBuf,
+    pub name: String,
+    pub code: String,
+    /// Test that is only compiled
+    pub no_run: bool,
+}
+
+/// Collects the usage examples from the doc comments of public functions
+pub fn get_doctests_to_run(command: &TestCommand) -> Result<Vec<TestCase>, Vec<Message>> {
+    let files = get_files_to_test(command)?;
+
+    let mut tests = vec![];
+    let mut errors = vec![];
+    for file in &files {
+        let code = match fs::read_to_string(file) {
+            Ok(c) => c,
+            Err(e) => {
+                errors.push(Message::new_err_msg(format!(
+                    "Failed to read file {}: {}",
+                    file.display(),
+                    e
+                )));
+                continue;
+            }
+        };
+
+        let options = CompilerOptions::from_args(&command.no_proc, false, false, None)
+            .with_target(command.target)
+            .with_project_config(command.project_config);
+        let compiler = AmberCompiler::new(code, Some(file.to_string_lossy().to_string()), options);
+        let (block, meta) = match compiler
+            .tokenize()
+            .and_then(|tokens| compiler.parse(tokens))
+        {
+            Ok(result) => result,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+
+        let file_name = file.file_name().unwrap_or_default().to_string_lossy();
+        let mut functions = block.get_docs_functions(&meta);
+        functions.sort_by_key(|function| function.position);
+        for function in functions.iter().filter(|function| function.is_public) {
+            let Some(comment) = &function.comment else {
+                continue;
+            };
+            let examples = get_doc_examples(comment);
+            let count = examples.len();
+            for (index, example) in examples.into_iter().enumerate() {
+                let name = if count == 1 {
+                    format!("{} example", function.name)
+                } else {
+                    format!("{} example {}", function.name, index + 1)
+                };
+                if !matches_test_case(command, &name) {
+                    continue;
+                }
+                tests.push(TestCase {
+                    file: file.clone(),
+                    name,
+                    code: get_doc_example_code(&file_name, &example.code),
+                    no_run: example.no_run,
+                });
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(tests)
+}
+
+/// Writes the report of the selected reporter to the output file or stdout
+fn write_report(command: &TestCommand, results: &[TestResult]) -> std::io::Result<()> {
+    let Some(report) = render_report(command.reporter, results) else {
+        return Ok(());
+    };
+    match &command.output {
+        Some(output) => fs::write(output, report),
+        None => std::io::stdout().write_all(report.as_bytes()),
+    }
+}
+
 pub fn handle_test(command: TestCommand) -> Result<i32, Box<dyn Error>> {
-    let tests = match get_tests_to_run(&command) {
+    if command.reporter == TestReporter::Human && command.output.is_some() {
+        Message::new_err_msg("The human reporter cannot write its report to a file")
+            .comment("Use '--reporter junit', '--reporter tap' or '--reporter json' instead")
+            .show();
+        return Ok(1);
+    }
+    let tests = if command.doc {
+        get_doctests_to_run(&command)
+    } else {
+        get_tests_to_run(&command).map(|tests| {
+            tests
+                .into_iter()
+                .map(|(file, name, code)| TestCase {
+                    file,
+                    name,
+                    code,
+                    no_run: false,
+                })
+                .collect()
+        })
+    };
+    let tests = match tests {
         Ok(t) => t,
         Err(errors) => {
             for e in errors {
@@ -105,76 +308,113 @@ pub fn handle_test(command: TestCommand) -> Result<i32, Box<dyn Error>> {
         }
     };
 
+    // Machine readable reports printed to stdout replace the progress
+    let show_progress = command.reporter == TestReporter::Human || command.output.is_some();
     let total = tests.len();
     if total == 0 {
-        println!("No tests found");
+        if show_progress {
+            println!("No tests found");
+        }
+        write_report(&command, &[])?;
         return Ok(0);
     }
 
     let failed = std::sync::Mutex::new(vec![]);
 
-    tests
-        .par_iter()
-        .enumerate()
-        .for_each(|(i, (file, name, code))| {
-            let test_name_display = if name.is_empty() {
-                format!("{}", file.display())
-            } else {
-                format!("{} ({})", file.display(), name)
-            };
+    let results = tests.par_iter().enumerate().map(|(i, test)| {
+        let TestCase {
+            file,
+            name,
+            code,
+            no_run,
+        } = test;
+        let test_name_display = if name.is_empty() {
+            format!("{}", file.display())
+        } else {
+            format!("{} ({})", file.display(), name)
+        };
 
+        // Doc examples are plain scripts, so they run without the test mode
+        let (options, path) = if command.doc {
+            let options = CompilerOptions::from_args(&command.no_proc, false, false, None)
+                .with_project_config(command.project_config);
+            (options, get_doctest_path(file))
+        } else {
             let options =
                 CompilerOptions::from_args(&command.no_proc, false, true, Some(name.clone()))
-                    .with_target(command.target);
-            let compiler = AmberCompiler::new(
-                code.clone(),
-                Some(file.to_string_lossy().to_string()),
-                options,
-            );
-
-            let result = match compiler.compile() {
-                Ok((_, bash_code)) => match AmberCompiler::find_shell(command.target) {
-                    Some(mut command) => match command.arg("-c").arg(&bash_code).output() {
-                        Ok(output) => {
-                            if output.status.success() {
-                                Ok(())
+                    .with_project_config(command.project_config);
+            (options, file.clone())
+        };
+        let compiler = AmberCompiler::new(
+            code.clone(),
+            Some(path.to_string_lossy().to_string()),
+            options.with_target(command.target),
+        );
+
+        let start = Instant::now();
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        // Reason of a failed run for the reports, which capture the output separately
+        let mut run_failure = None;
+        let result = match compiler.compile() {
+            Ok(_) if *no_run => Ok(()),
+            Ok((_, bash_code)) => match AmberCompiler::find_shell(command.target) {
+                Some(mut command) => match command.arg("-c").arg(&bash_code).output() {
+                    Ok(output) => {
+                        stdout = String::from_utf8_lossy(&output.stdout).to_string();
+                        stderr = String::from_utf8_lossy(&output.stderr).to_string();
+                        if output.status.success() {
+                            Ok(())
+                        } else {
+                            let exit = match output.status.code() {
+                                Some(code) => format!("Exited with code {code}"),
+                                None => "Terminated by a signal".to_string(),
+                            };
+                            let err_msg = format!("{stdout}\n{stderr}").trim().to_string();
+                            // Keep the assertion text that the test printed before failing
+                            run_failure = Some(if err_msg.is_empty() {
+                                exit
                             } else {
-                                let err_msg = format!(
-                                    "{}\n{}",
-                                    String::from_utf8_lossy(&output.stdout),
-                                    String::from_utf8_lossy(&output.stderr)
-                                )
-                                .trim()
-                                .to_string();
-                                if err_msg.is_empty() {
-                                    Err(Message::new_err_msg("(No output)".dimmed().to_string()))
-                                } else {
-                                    Err(Message::new_err_msg(err_msg))
-                                }
+                                format!("{err_msg}\n{exit}")
+                            });
+                            if err_msg.is_empty() {
+                                Err(Message::new_err_msg("(No output)".dimmed().to_string()))
+                            } else {
+                                Err(Message::new_err_msg(err_msg))
                             }
                         }
-                        Err(e) => Err(Message::new_err_msg(format!(
-                            "Error executing shell: {}",
-                            e
-                        ))),
-                    },
-                    None => Err(Message::new_err_msg("Failed to find shell command")),
+                    }
+                    Err(e) => Err(Message::new_err_msg(format!(
+                        "Error executing shell: {}",
+                        e
+                    ))),
                 },
-                Err(e) => Err(e),
-            };
+                None => Err(Message::new_err_msg("Failed to find shell command")),
+            },
+            Err(e) => Err(e),
+        };
+        let failure = result.as_ref().err().map(|msg| {
+            run_failure.unwrap_or_else(|| {
+                msg.message
+                    .clone()
+                    .unwrap_or_else(|| "Failed to compile".to_string())
+            })
+        });
 
-            match result {
-                Ok(_) => {
-                    println!(
-                        "[{}/{}] {} {} ... {}",
-                        i + 1,
-                        total,
-                        "✓".green(),
-                        test_name_display,
-                        "Success".green()
-                    );
-                }
-                Err(msg) => {
+        match result {
+            Ok(_) if !show_progress => {}
+            Ok(_) => {
+                println!(
+                    "[{}/{}] {} {} ... {}",
+                    i + 1,
+                    total,
+                    "✓".green(),
+                    test_name_display,
+                    "Success".green()
+                );
+            }
+            Err(msg) => {
+                if show_progress {
                     println!(
                         "[{}/{}] {} {} ... {}",
                         i + 1,
@@ -183,12 +423,27 @@ pub fn handle_test(command: TestCommand) -> Result<i32, Box<dyn Error>> {
                         test_name_display,
                         "Failed".red()
                     );
-                    failed.lock().unwrap().push((i + 1, test_name_display, msg));
                 }
+                failed.lock().unwrap().push((i + 1, test_name_display, msg));
             }
-        });
+        }
+
+        TestResult {
+            file: file.clone(),
+            name: name.clone(),
+            duration: start.elapsed(),
+            stdout,
+            stderr,
+            failure,
+        }
+    });
+    let results = results.collect::<Vec<_>>();
+    write_report(&command, &results)?;
 
     let failed_vec = failed.lock().unwrap();
+    if !show_progress {
+        return Ok(i32::from(!failed_vec.is_empty()));
+    }
     if !failed_vec.is_empty() {
         println!();
         for (i, name, msg) in failed_vec.iter() {
@@ -0,0 +1,212 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use serde_json::json;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum TestReporter {
+    /// Colored progress and summary of the failed tests
+    #[default]
+    Human,
+    /// JUnit XML report with a test suite per file
+    Junit,
+    /// Test Anything Protocol version 13
+    Tap,
+    /// Test results as a single JSON document
+    Json,
+}
+
+/// Outcome of a single test case
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    pub file: PathBuf,
+    pub name: String,
+    pub duration: Duration,
+    pub stdout: String,
+    pub stderr: String,
+    /// Reason of the failure or `None` if the test passed
+    pub failure: Option<String>,
+}
+
+impl TestResult {
+    /// Name of the test or the file for tests without a name
+    pub fn display_name(&self) -> String {
+        if self.name.is_empty() {
+            self.file.display().to_string()
+        } else {
+            self.name.clone()
+        }
+    }
+}
+
+/// Renders the report of the test results. Returns `None` for the human reporter,
+/// which prints the results while the tests are running.
+pub fn render_report(reporter: TestReporter, results: &[TestResult]) -> Option<String> {
+    match reporter {
+        TestReporter::Human => None,
+        TestReporter::Junit => Some(render_junit(results)),
+        TestReporter::Tap => Some(render_tap(results)),
+        TestReporter::Json => Some(render_json(results)),
+    }
+}
+
+fn get_seconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64())
+}
+
+fn render_junit(results: &[TestResult]) -> String {
+    let mut files: Vec<(&PathBuf, Vec<&TestResult>)> = vec![];
+    for result in results {
+        match files.iter_mut().find(|(file, _)| **file == result.file) {
+            Some((_, file_results)) => file_results.push(result),
+            None => files.push((&result.file, vec![result])),
+        }
+    }
+    let get_summary = |results: &[&TestResult]| {
+        let failures = results.iter().filter(|result| result.failure.is_some());
+        let duration = results.iter().map(|result| result.duration).sum();
+        format!(
+            "tests=\"{}\" failures=\"{}\" time=\"{}\"",
+            results.len(),
+            failures.count(),
+            get_seconds(duration)
+        )
+    };
+    let mut xml = vec![
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>".to_string(),
+        format!(
+            "<testsuites name=\"amber\" {}>",
+            get_summary(&results.iter().collect::<Vec<_>>())
+        ),
+    ];
+    for (file, file_results) in files {
+        let file = escape_xml(&file.display().to_string());
+        xml.push(format!(
+            "  <testsuite name=\"{file}\" {}>",
+            get_summary(&file_results)
+        ));
+        for result in file_results {
+            xml.push(format!(
+                "    <testcase name=\"{}\" classname=\"{file}\" file=\"{file}\" time=\"{}\">",
+                escape_xml(&result.display_name()),
+                get_seconds(result.duration)
+            ));
+            if let Some(failure) = &result.failure {
+                let message = failure.lines().next().unwrap_or_default();
+                xml.push(format!(
+                    "      <failure message=\"{}\">{}</failure>",
+                    escape_xml(message),
+                    escape_xml(failure)
+                ));
+            }
+            if !result.stdout.is_empty() {
+                xml.push(format!(
+                    "      <system-out>{}</system-out>",
+                    escape_xml(&result.stdout)
+                ));
+            }
+            if !result.stderr.is_empty() {
+                xml.push(format!(
+                    "      <system-err>{}</system-err>",
+                    escape_xml(&result.stderr)
+                ));
+            }
+            xml.push("    </testcase>".to_string());
+        }
+        xml.push("  </testsuite>".to_string());
+    }
+    xml.push("</testsuites>".to_string());
+    xml.join("\n") + "\n"
+}
+
+/// Escapes the text for XML and drops the control characters that XML 1.0 does not allow
+fn escape_xml(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .fold(String::new(), |mut result, c| {
+            match c {
+                '&' => result.push_str("&amp;"),
+                '<' => result.push_str("&lt;"),
+                '>' => result.push_str("&gt;"),
+                '"' => result.push_str("&quot;"),
+                '\'' => result.push_str("&apos;"),
+                c => result.push(c),
+            }
+            result
+        })
+}
+
+fn render_tap(results: &[TestResult]) -> String {
+    let mut tap = vec![
+        "TAP version 13".to_string(),
+        format!("1..{}", results.len()),
+    ];
+    for (index, result) in results.iter().enumerate() {
+        let status = if result.failure.is_some() {
+            "not ok"
+        } else {
+            "ok"
+        };
+        let name = if result.name.is_empty() {
+            result.file.display().to_string()
+        } else {
+            format!("{} ({})", result.file.display(), result.name)
+        };
+        // The hash sign would start a directive
+        tap.push(format!(
+            "{status} {} - {}",
+            index + 1,
+            name.replace('#', "\\#")
+        ));
+        tap.push("  ---".to_string());
+        tap.push(format!(
+            "  file: {}",
+            json!(result.file.display().to_string())
+        ));
+        tap.push(format!("  duration_ms: {}", result.duration.as_millis()));
+        let fields = [
+            ("message", result.failure.as_deref().unwrap_or_default()),
+            ("stdout", &result.stdout),
+            ("stderr", &result.stderr),
+        ];
+        for (key, value) in fields {
+            if value.is_empty() {
+                continue;
+            }
+            tap.push(format!("  {key}: |-"));
+            for line in value.trim_end().lines() {
+                tap.push(format!("    {line}"));
+            }
+        }
+        tap.push("  ...".to_string());
+    }
+    tap.join("\n") + "\n"
+}
+
+fn render_json(results: &[TestResult]) -> String {
+    let tests = results
+        .iter()
+        .map(|result| {
+            json!({
+                "file": result.file.display().to_string(),
+                "name": result.name,
+                "status": if result.failure.is_some() { "failed" } else { "passed" },
+                "duration": result.duration.as_secs_f64(),
+                "stdout": result.stdout,
+                "stderr": result.stderr,
+                "message": result.failure,
+            })
+        })
+        .collect::<Vec<_>>();
+    let failed = results
+        .iter()
+        .filter(|result| result.failure.is_some())
+        .count();
+    let report = json!({
+        "passed": results.len() - failed,
+        "failed": failed,
+        "tests": tests,
+    });
+    serde_json::to_string_pretty(&report).expect("Test report is always valid JSON") + "\n"
+}
@@ -4,6 +4,7 @@
 
 use crate::compiler::{AmberCompiler, CompilerOptions};
 use crate::testing::get_tests_to_run;
+use crate::testing::reporter::TestReporter;
 use crate::TestCommand;
 use std::path::PathBuf;
 
@@ -80,8 +81,13 @@ fn test_filtering() {
         input: input.clone(),
         args: vec![],
         no_proc: vec![],
+        project_config: false,
         target: None,
         test_case: Some("foo".to_string()),
+        watch: false,
+        doc: false,
+        reporter: TestReporter::Human,
+        output: None,
     };
     let tests = get_tests_to_run(&command)
         .map_err(|e| format!("{:?}", e))
@@ -543,3 +549,275 @@ fn test_cli_param_injection() {
 
     let _ = temp_file.close();
 }
+
+#[test]
+fn test_cli_message_format_json() {
+    let temp_file = NamedTempFile::new().expect("Failed to create temp file");
+    std::fs::write(temp_file.path(), "let value = 1\necho value\n")
+        .expect("Failed to write test file");
+    let path = temp_file.path().to_str().unwrap();
+
+    let mut cmd = Command::new(amber_bin());
+    let output = cmd
+        .args(["check", path, "--message-format", "json"])
+        .output()
+        .expect("Failed to run amber");
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let messages = stderr
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![serde_json::json!({
+            "file": path,
+            "line": 2,
+            "column": 1,
+            "end_line": 2,
+            "end_column": 5,
+            "severity": "warning",
+            "message": "Calling a builtin without parentheses is deprecated",
+            "comment": null
+        })]
+    );
+
+    let _ = temp_file.close();
+}
+
+#[test]
+fn test_cli_message_format_json_error() {
+    let temp_file = NamedTempFile::new().expect("Failed to create temp file");
+    std::fs::write(temp_file.path(), "let value: Int = \"text\"\n")
+        .expect("Failed to write test file");
+
+    let mut cmd = Command::new(amber_bin());
+    let output = cmd
+        .args([
+            "build",
+            temp_file.path().to_str().unwrap(),
+            "-",
+            "--message-format=json",
+        ])
+        .output()
+        .expect("Failed to run amber");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let message: serde_json::Value = serde_json::from_str(stderr.trim()).unwrap();
+    assert_eq!(message["severity"], "error");
+    assert_eq!(message["line"], 1);
+    assert!(message["message"].is_string());
+
+    let _ = temp_file.close();
+}
+
+#[test]
+fn test_cli_repl_keeps_state_between_lines() {
+    let mut cmd = Command::new(amber_bin());
+    let output = cmd
+        .arg("repl")
+        .write_stdin(
+            "let name = \"world\"\nfun greet(who) {\n    return \"hello {who}\"\n}\ngreet(name)\n",
+        )
+        .output()
+        .expect("Failed to run amber");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("hello world\n"), "{stdout}");
+}
+
+#[test]
+fn test_cli_repl_exit_code() {
+    let mut cmd = Command::new(amber_bin());
+    cmd.arg("repl")
+        .write_stdin("exit(3)\necho(\"unreachable\")\n")
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("unreachable").not());
+}
+
+#[test]
+fn test_cli_build_watch_rebuilds_on_import_change() {
+    let dir = tempfile::tempdir().unwrap();
+    let (main, module, output) = (
+        dir.path().join("main.ab"),
+        dir.path().join("module.ab"),
+        dir.path().join("main.sh"),
+    );
+    let code = format!("import * from \"{}\"\necho(value)\n", module.display());
+    std::fs::write(&main, code).unwrap();
+    std::fs::write(&module, "pub const value = \"first\"\n").unwrap();
+
+    let mut child = std::process::Command::new(amber_bin())
+        .args(["build", "--watch", main.to_str().unwrap()])
+        .arg(&output)
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .expect("Failed to run amber");
+    let wait_for = |text: &str| {
+        for _ in 0..100 {
+            let built = std::fs::read_to_string(&output).unwrap_or_default();
+            if built.contains(text) {
+                return true;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        false
+    };
+    let first_build = wait_for("first");
+    std::fs::write(&module, "pub const value = \"second\"\n").unwrap();
+    let second_build = wait_for("second");
+    child.kill().unwrap();
+    child.wait().unwrap();
+    assert!(first_build);
+    assert!(second_build);
+}
+
+#[test]
+fn test_cli_build_source_map_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let (main, output) = (dir.path().join("main.ab"), dir.path().join("main.sh"));
+    let code = "let value = 1\n\nif value > 0 {\n    trust $ exit 3 $\n}\n";
+    std::fs::write(&main, code).unwrap();
+
+    let mut cmd = Command::new(amber_bin());
+    cmd.args(["build", "--source-map", main.to_str().unwrap()])
+        .arg(&output)
+        .assert()
+        .success();
+
+    let script = std::fs::read_to_string(&output).unwrap();
+    assert!(!script.contains("# amber:"));
+    let map = std::fs::read_to_string(dir.path().join("main.sh.map")).unwrap();
+    let map: serde_json::Value = serde_json::from_str(&map).unwrap();
+    assert_eq!(map["file"], "main.sh");
+    assert_eq!(map["sources"][0], main.to_str().unwrap());
+    let exit_line = script
+        .lines()
+        .position(|line| line.trim() == "exit 3")
+        .expect("Script should exit with code 3")
+        + 1;
+    let mapping = map["mappings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|mapping| mapping["line"] == exit_line)
+        .expect("Exit line should be mapped");
+    assert_eq!(mapping["source_line"], 4);
+    assert_eq!(mapping["source_column"], 5);
+}
+
+#[test]
+fn test_cli_build_source_map_inline() {
+    let mut cmd = Command::new(amber_bin());
+    cmd.args([
+        "build",
+        "--source-map=inline",
+        "src/tests/validity/hello_world.ab",
+        "-",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains(
+        "# amber:src/tests/validity/hello_world.ab:",
+    ));
+}
+
+#[test]
+fn test_cli_build_source_map_file_requires_output_file() {
+    let mut cmd = Command::new(amber_bin());
+    cmd.args([
+        "build",
+        "--source-map",
+        "src/tests/validity/hello_world.ab",
+        "-",
+    ])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains(
+        "Source map file cannot be written when the output is stdout",
+    ));
+}
+
+#[test]
+fn test_cli_run_stack_trace() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = dir.path().join("main.ab");
+    let code = r#"fun read_config(path: Text): Text? {
+    $ cat {path} $?
+    return "ok"
+}
+
+fun load(): Text? {
+    return read_config("/nonexistent/amber.conf")?
+}
+
+main {
+    const config = load()?
+    echo(config)
+}
+"#;
+    std::fs::write(&main, code).unwrap();
+    let path = main.to_str().unwrap();
+
+    let mut cmd = Command::new(amber_bin());
+    cmd.args(["run", "--stack-trace", path])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains(format!(
+            "Failed with exit code 1\n    at read_config ({path}:2:5)\n    at load ({path}:7:12)\n    at main ({path}:11:20)\n"
+        )));
+}
+
+#[test]
+fn test_cli_run_stack_trace_from_fail() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = dir.path().join("main.ab");
+    let code = "fun check(value: Int): Null? {\n    if value > 2 {\n        fail 4\n    }\n}\n\nmain {\n    check(3)?\n}\n";
+    std::fs::write(&main, code).unwrap();
+    let path = main.to_str().unwrap();
+
+    let mut cmd = Command::new(amber_bin());
+    cmd.args(["run", "--target", "sh", "--stack-trace", path])
+        .assert()
+        .code(4)
+        .stderr(format!(
+            "Failed with exit code 4\n    at check ({path}:3:9)\n    at main ({path}:8:5)\n"
+        ));
+}
+
+#[test]
+fn test_cli_run_without_stack_trace() {
+    let mut cmd = Command::new(amber_bin());
+    cmd.args(["eval", "main { fail 2 }"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_cli_docs_coverage_fail_under() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = dir.path().join("main.ab");
+    let code = "/// Documented\npub fun first() {}\n\npub fun second() {}\n";
+    std::fs::write(&main, code).unwrap();
+    let path = main.to_str().unwrap();
+
+    let mut cmd = Command::new(amber_bin());
+    cmd.args(["docs", "--coverage", "--fail-under", "50", path])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1/2     50.0%"));
+
+    let mut cmd = Command::new(amber_bin());
+    cmd.args(["docs", "--coverage", "--fail-under", "80", path])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Documentation coverage 50.0% is below the required 80%",
+        ));
+}
@@ -1,5 +1,7 @@
 /// Tests for Amber scripts that check snapshot of generated bash code.
+use crate::compiler::source_map::{SourceMap, SourceMapping};
 use crate::compiler::{escape_shell_arg, AmberCompiler, CompilerOptions};
+use crate::docs::DocsFormat;
 use crate::modules::prelude::TranslateModule;
 use crate::modules::types::Type;
 use crate::translate::fragments::fragment::FragmentRenderable;
@@ -162,6 +164,259 @@ fn test_bash_32_ref_array_negative_index_uses_pass_through_deref_access() {
     );
 }
 
+#[test]
+fn test_bash_32_rejects_maps() {
+    let code = "let map = [\"key\": \"value\"]\necho(map[\"key\"])";
+    let options = CompilerOptions::default().with_target(Some(ShellType::BashLegacy));
+    let compiler = AmberCompiler::new(code.to_string(), None, options);
+    let tokens = compiler.tokenize().expect("tokenize failed");
+    let (ast, meta) = compiler.parse(tokens).expect("parse failed");
+    let (ast, meta) = compiler.typecheck(ast, meta).expect("typecheck failed");
+    let error = compiler
+        .translate(ast, meta)
+        .expect_err("Maps should not compile for bash-3.2");
+
+    assert_eq!(
+        error.message.as_deref(),
+        Some("Maps are not supported by the 'bash-3.2' target")
+    );
+    assert!(translate_compiler_output_with_target(code, Some(ShellType::BashModern)).is_some());
+}
+
+#[test]
+fn test_ksh_rejects_lambda_captures() {
+    let code = "fun scale(x: Int, factor: Int): Int {\n    let mul = fun(y: Int): Int { return y * factor }\n    return mul(x)\n}\necho(scale(2, 3))";
+    let options = CompilerOptions::default().with_target(Some(ShellType::Ksh));
+    let compiler = AmberCompiler::new(code.to_string(), None, options);
+    let tokens = compiler.tokenize().expect("tokenize failed");
+    let (ast, meta) = compiler.parse(tokens).expect("parse failed");
+    let (ast, meta) = compiler.typecheck(ast, meta).expect("typecheck failed");
+    let error = compiler
+        .translate(ast, meta)
+        .expect_err("Lambda captures should not compile for ksh");
+
+    assert_eq!(
+        error.message.as_deref(),
+        Some("Lambdas capturing local variables are not supported by the 'ksh' target")
+    );
+    assert!(translate_compiler_output_with_target(code, Some(ShellType::BashModern)).is_some());
+}
+
+#[test]
+fn test_posix_rejects_maps_nested_arrays_and_struct_arrays() {
+    let cases = [
+        (
+            "let map = [\"key\": \"value\"]\necho(map[\"key\"])",
+            "Maps are not supported by the 'posix' target",
+        ),
+        (
+            "let grid = [[1, 2], [3, 4]]\necho(grid[1][0])",
+            "Nested arrays are not supported by the 'posix' target",
+        ),
+        (
+            "struct Point { x: Int }\nlet points = [Point { x: 1 }]\necho(points[0].x)",
+            "Arrays of structs are not supported by the 'posix' target",
+        ),
+    ];
+    for (code, message) in cases {
+        let options = CompilerOptions::default().with_target(Some(ShellType::Posix));
+        let compiler = AmberCompiler::new(code.to_string(), None, options);
+        let tokens = compiler.tokenize().expect("tokenize failed");
+        let (ast, meta) = compiler.parse(tokens).expect("parse failed");
+        let (ast, meta) = compiler.typecheck(ast, meta).expect("typecheck failed");
+        let error = compiler
+            .translate(ast, meta)
+            .expect_err("Code should not compile for posix");
+
+        assert_eq!(error.message.as_deref(), Some(message));
+    }
+}
+
+#[test]
+fn test_posix_output_avoids_bash_extensions() {
+    let code = r#"
+        fun push(ref items: [Text], item: Text) {
+            items += [item]
+        }
+        let items = ["alpha", "beta gamma"]
+        push(items, "delta")
+        items[0] = "first"
+        for index, item in items {
+            echo("{index}: {item}")
+        }
+        echo(len(items))
+        echo(items[-1])
+        echo(items[1..3])
+        for number in 1..=3 {
+            if number % 2 == 0 and "a" < "b" {
+                echo(number)
+            }
+        }
+    "#;
+    let output = translate_compiler_output_with_target(code, Some(ShellType::Posix))
+        .expect("Couldn't translate Amber code");
+    for bashism in ["[@]", "local ", "declare ", "[[ ", "<<<", "+=("] {
+        assert!(
+            !output.contains(bashism),
+            "POSIX output should not contain '{bashism}':\n{output}"
+        );
+    }
+    assert!(
+        !output
+            .lines()
+            .any(|line| line.trim_start().starts_with("((")),
+        "POSIX output should not contain arithmetic commands:\n{output}"
+    );
+    assert!(output.starts_with("#!/usr/bin/env sh"));
+
+    let Ok(result) = std::process::Command::new("dash")
+        .arg("-c")
+        .arg(&output)
+        .output()
+    else {
+        return;
+    };
+    assert_eq!(
+        String::from_utf8_lossy(&result.stdout),
+        "0: first\n1: beta gamma\n2: delta\n3\ndelta\nbeta gamma delta\n2\n"
+    );
+}
+
+#[test]
+fn test_posix_recursive_functions_keep_local_variables() {
+    let code = r#"
+        fun fib(n: Int): Int {
+            if n < 2: return n
+            const left = fib(n - 1)
+            const right = fib(n - 2)
+            return left + right
+        }
+        echo(fib(10))
+    "#;
+    let output = translate_compiler_output_with_target(code, Some(ShellType::Posix))
+        .expect("Couldn't translate Amber code");
+    assert!(!output.contains("local "));
+
+    let Ok(result) = std::process::Command::new("dash")
+        .arg("-c")
+        .arg(&output)
+        .output()
+    else {
+        return;
+    };
+    assert_eq!(String::from_utf8_lossy(&result.stdout), "55\n");
+}
+
+#[test]
+fn test_posix_rejects_bash_only_stdlib_functions() {
+    for (code, name) in [
+        (
+            "import { split } from \"std/text\"\necho(split(\"a,b\", \",\"))",
+            "split",
+        ),
+        (
+            "import { join } from \"std/text\"\necho(join([\"a\"], \",\"))",
+            "join",
+        ),
+        (
+            "import { split_lines } from \"std/text\"\necho(split_lines(\"a\"))",
+            "split_lines",
+        ),
+        (
+            "import { uppercase } from \"std/text\"\necho(uppercase(\"a\"))",
+            "uppercase",
+        ),
+    ] {
+        let options = CompilerOptions::default().with_target(Some(ShellType::Posix));
+        let compiler = AmberCompiler::new(code.to_string(), None, options);
+        let message = compiler
+            .compile()
+            .expect_err("Code should not compile for posix")
+            .message;
+        assert_eq!(
+            message.as_deref(),
+            Some(format!("Function '{name}' of the standard library is not supported by the 'posix' target").as_str())
+        );
+    }
+}
+
+#[test]
+fn test_posix_runs_portable_stdlib_functions() {
+    let code = r#"
+        import { trim, trim_right } from "std/text"
+        echo(trim("  hello  "))
+        echo(trim_right("world  ") + "!")
+    "#;
+    let output = translate_compiler_output_with_target(code, Some(ShellType::Posix))
+        .expect("Couldn't translate Amber code");
+
+    let Ok(result) = std::process::Command::new("dash")
+        .arg("-c")
+        .arg(&output)
+        .output()
+    else {
+        return;
+    };
+    assert_eq!(String::from_utf8_lossy(&result.stdout), "hello\nworld!\n");
+}
+
+#[test]
+fn test_int_arithmetic_does_not_spawn_awk() {
+    let code = r#"
+        let total = 0
+        for index in 0..10 {
+            if index % 2 == 0 and not (index >= 8) {
+                total += index * 2 - -1
+            }
+        }
+        total -= 1
+        total *= 2
+        total /= 3
+        total %= 100
+        const size = match total {
+            0: "zero"
+            else: "other"
+        }
+        echo(size)
+        echo(total != 3 or total <= 10)
+    "#;
+    for target in [
+        ShellType::BashModern,
+        ShellType::BashLegacy,
+        ShellType::Zsh,
+        ShellType::Ksh,
+        ShellType::Posix,
+    ] {
+        let output = translate_compiler_output_with_target(code, Some(target))
+            .expect("Couldn't translate Amber code");
+        assert!(
+            !output.contains("awk"),
+            "Integer arithmetic should not spawn awk for {target:?}:\n{output}"
+        );
+    }
+    let output = translate_amber_code("let number = 1.5\necho(number + 1)")
+        .expect("Couldn't translate Amber code");
+    assert!(output.contains("awk"));
+}
+
+#[test]
+fn test_zsh_map_keys_use_key_flag() {
+    let code = "main { echo(\"ok\") }";
+    let options = CompilerOptions::default().with_target(Some(ShellType::Zsh));
+    let compiler = AmberCompiler::new(code.to_string(), None, options);
+    let tokens = compiler.tokenize().expect("tokenize failed");
+    let (ast, meta) = compiler.parse(tokens).expect("parse failed");
+    let (_, meta) = compiler.typecheck(ast, meta).expect("typecheck failed");
+    let mut translate_meta = TranslateMetadata::new(meta, &compiler.options);
+
+    let rendered = VarExprFragment::new("map", Type::map_of(Type::Text))
+        .with_global_id(0)
+        .with_keys_getter(true)
+        .to_string(&mut translate_meta);
+
+    assert_eq!(rendered, "\"${(k)map_0[@]}\"");
+}
+
 #[test]
 fn test_zsh_declared_ref_array_assignment_defers_array_expansion_to_inner_eval() {
     let code = "main { echo(\"ok\") }";
@@ -206,6 +461,49 @@ fn test_bash_32_declared_ref_array_assignment_defers_array_expansion_to_inner_ev
     assert_eq!(rendered, r#"eval "${target}=(\"\${source[@]}\")""#);
 }
 
+#[test]
+fn test_source_map_markers_map_generated_lines() {
+    let code = "let items = [1, 2]\nfor item in items {\n    echo(item)\n}\n";
+    let options = CompilerOptions::default().with_source_map(true);
+    let compiler = AmberCompiler::new(code.to_string(), Some("main.ab".to_string()), options);
+    let (_, output) = compiler.compile().expect("Couldn't compile Amber code");
+    assert!(output.contains("# amber:main.ab:2:1\nfor "));
+    assert!(output.contains("    # amber:main.ab:3:5\n"));
+
+    let (stripped, source_map) = SourceMap::extract(&output);
+    assert!(!stripped.contains("# amber:"));
+    assert_eq!(source_map.sources, vec!["main.ab".to_string()]);
+    let lines = stripped.lines().collect::<Vec<_>>();
+    let loop_line = lines
+        .iter()
+        .position(|line| line.starts_with("for "))
+        .unwrap()
+        + 1;
+    let mapping_at = |line| {
+        source_map
+            .mappings
+            .iter()
+            .find(|mapping| mapping.line == line)
+    };
+    assert_eq!(
+        mapping_at(loop_line),
+        Some(&SourceMapping {
+            line: loop_line,
+            source: 0,
+            source_line: 2,
+            source_column: 1,
+        })
+    );
+    assert_eq!(
+        mapping_at(loop_line + 1),
+        Some(&SourceMapping {
+            line: loop_line + 1,
+            source: 0,
+            source_line: 3,
+            source_column: 5,
+        })
+    );
+}
 
 #[test]
 fn test_lock_default_path_uses_tmpdir_with_tmp_fallback() {
@@ -324,6 +622,62 @@ main {
     );
 }
 
+#[test]
+fn test_translate_drops_unreachable_functions() {
+    let code = r#"
+import * from "std/text"
+import * from "std/env"
+
+fun unused(): Text {
+    return replace("a-b", "-", "+")
+}
+
+fun tested(): Text {
+    return uppercase("tested")
+}
+
+test "uppercase" {
+    echo(tested())
+}
+
+main {
+    echo(trim(" used "))
+}
+"#;
+    // Inlining would remove small functions as well, so keep the optimizer out
+    let translate = |test_mode: bool| {
+        let mut options = CompilerOptions::default().with_target(Some(ShellType::BashModern));
+        options.no_optimize = true;
+        options.test_mode = test_mode;
+        let compiler = AmberCompiler::new(code.to_string(), None, options);
+        let tokens = compiler.tokenize().expect("Couldn't tokenize Amber code");
+        let (ast, meta) = compiler.parse(tokens).expect("Couldn't parse Amber code");
+        let (ast, meta) = compiler
+            .typecheck(ast, meta)
+            .expect("Couldn't typecheck Amber code");
+        compiler
+            .translate(ast, meta)
+            .expect("Couldn't translate Amber code")
+    };
+    let result = translate(false);
+
+    assert!(result.contains("trim__"));
+    for name in ["unused", "tested", "replace", "uppercase", "env_var_get"] {
+        assert!(
+            !result.contains(&format!("{name}__")),
+            "Output should not contain the unreachable function '{name}'"
+        );
+    }
+    assert!(
+        !result.contains("__sudo") && !result.contains("EXEC_SHELL"),
+        "Output should not contain the preambles of unreachable functions"
+    );
+
+    let result = translate(true);
+    assert!(result.contains("tested__") && result.contains("uppercase__"));
+    assert!(!result.contains("trim__") && !result.contains("unused__"));
+}
+
 #[test]
 fn test_find_shell() {
     let bash_cmd = AmberCompiler::find_shell(None);
@@ -347,6 +701,13 @@ fn test_target_from_shell_path() {
         AmberCompiler::target_from_shell_path("/bin/bash"),
         Some(ShellType::BashModern)
     );
+    for shell in ["/bin/sh", "/usr/bin/dash", "ash", "/bin/busybox"] {
+        assert_eq!(
+            AmberCompiler::target_from_shell_path(shell),
+            Some(ShellType::Posix)
+        );
+    }
+    assert_eq!(AmberCompiler::target_from_shell_path("/usr/bin/fish"), None);
 }
 
 #[test]
@@ -359,6 +720,10 @@ fn test_runtime_shell_command_uses_target_family_when_shell_is_unset() {
         AmberCompiler::runtime_shell_command(None, Some(ShellType::Zsh)),
         Some("zsh".to_string())
     );
+    assert_eq!(
+        AmberCompiler::runtime_shell_command(None, Some(ShellType::Posix)),
+        Some("sh".to_string())
+    );
 }
 
 #[test]
@@ -476,7 +841,12 @@ fn test_document_with_output() {
     let (block, meta) = compiler.parse(tokens).expect("parse failed");
     let (block, meta) = compiler.typecheck(block, meta).expect("typecheck failed");
 
-    compiler.document(block, meta, Some(temp_dir.to_string_lossy().to_string()));
+    compiler.document(
+        block,
+        meta,
+        Some(temp_dir.to_string_lossy().to_string()),
+        DocsFormat::Markdown,
+    );
 }
 
 #[test]
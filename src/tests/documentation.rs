@@ -28,3 +28,296 @@ mod documentation_tests {
         let _doc_fn: fn(&TestType, &ParserMetadata) -> String = TestType::document;
     }
 }
+
+mod html_tests {
+    use std::fs;
+
+    use crate::compiler::{AmberCompiler, CompilerOptions};
+    use crate::docs::html::HtmlSite;
+    use crate::docs::item::{DocsArgument, DocsFunction, DocsModule};
+
+    fn get_function(name: &str, comment: Option<&str>) -> DocsFunction {
+        DocsFunction {
+            name: name.to_string(),
+            args: vec![],
+            returns: None,
+            is_public: true,
+            is_failable: false,
+            comment: comment.map(String::from),
+            position: None,
+        }
+    }
+
+    fn get_site_file<'a>(files: &'a [(std::path::PathBuf, String)], path: &str) -> &'a str {
+        files
+            .iter()
+            .find(|(file, _)| file.to_string_lossy() == path)
+            .map(|(_, content)| content.as_str())
+            .unwrap_or_else(|| panic!("Site should contain '{path}'"))
+    }
+
+    #[test]
+    fn test_docs_function_signature_and_summary() {
+        let function = DocsFunction {
+            args: vec![
+                DocsArgument {
+                    name: "items".to_string(),
+                    kind: Some("[Text]".to_string()),
+                    is_ref: true,
+                    default: None,
+                },
+                DocsArgument {
+                    name: "sep".to_string(),
+                    kind: None,
+                    is_ref: false,
+                    default: Some("\", \"".to_string()),
+                },
+            ],
+            returns: Some("Text".to_string()),
+            is_failable: true,
+            ..get_function("join", Some("Joins the\nitems.\n\n### Usage"))
+        };
+        assert_eq!(
+            function.signature(),
+            "pub fun join(ref items: [Text], sep = \", \"): Text?"
+        );
+        assert_eq!(function.summary(), "Joins the items.");
+        assert_eq!(get_function("empty", Some("### Usage")).summary(), "");
+    }
+
+    #[test]
+    fn test_html_site_pages_and_links() {
+        let modules = vec![
+            DocsModule {
+                id: "std/text".to_string(),
+                path: "std/text".to_string(),
+                functions: vec![get_function("trim", None)],
+            },
+            DocsModule {
+                id: "lib/strings".to_string(),
+                path: "/project/lib/strings.ab".to_string(),
+                functions: vec![
+                    get_function("pad", Some("Calls `trim()` and `shout` on <text>.")),
+                    get_function("shout", None),
+                ],
+            },
+        ];
+        let files = HtmlSite::new(&modules).render();
+
+        let index = get_site_file(&files, "index.html");
+        assert!(index.contains("<a href=\"lib/strings/index.html\">lib/strings</a>"));
+        assert!(index.contains("<a href=\"std/text/index.html\">std/text</a>"));
+
+        let module = get_site_file(&files, "lib/strings/index.html");
+        assert!(module.contains("<a href=\"fn.pad.html\"><code>pub fun pad()</code></a>"));
+        assert!(module.contains("<link rel=\"stylesheet\" href=\"../../style.css\">"));
+
+        let pad = get_site_file(&files, "lib/strings/fn.pad.html");
+        assert!(pad.contains("<a href=\"../../std/text/fn.trim.html\"><code>trim()</code></a>"));
+        assert!(pad.contains("<a href=\"../../lib/strings/fn.shout.html\"><code>shout</code></a>"));
+        assert!(
+            pad.contains("on &lt;text&gt;."),
+            "HTML in comments should be escaped"
+        );
+
+        let search = get_site_file(&files, "search-index.js");
+        assert!(search.starts_with("window.searchIndex = ["));
+        assert!(search.contains("\"url\":\"std/text/fn.trim.html\""));
+        get_site_file(&files, "search.js");
+        get_site_file(&files, "style.css");
+    }
+
+    #[test]
+    fn test_docs_modules_from_import_graph() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("lib")).unwrap();
+        let lib = r#"
+import { trim } from "std/text"
+
+/// Pads the text.
+pub fun pad(text: Text, fill: Text = " ", widths: [Int] = [1, 2]): Text {
+    return trim(text) + fill
+}
+"#;
+        fs::write(dir.path().join("lib/strings.ab"), lib).unwrap();
+        let code = "import { pad } from \"./lib/strings.ab\"\necho(pad(\"a\"))\n";
+        let main_path = dir.path().join("main.ab");
+        fs::write(&main_path, code).unwrap();
+
+        let path = Some(main_path.to_string_lossy().to_string());
+        let compiler = AmberCompiler::new(code.to_string(), path, CompilerOptions::default());
+        let tokens = compiler.tokenize().unwrap();
+        let (block, meta) = compiler.parse(tokens).unwrap();
+        let (block, meta) = compiler.typecheck(block, meta).unwrap();
+        let forest = compiler.get_sorted_ast_forest(block, &meta);
+        let base_dir = fs::canonicalize(dir.path()).unwrap();
+        let modules = AmberCompiler::get_docs_modules(forest, &meta, &base_dir);
+
+        let ids = modules
+            .iter()
+            .map(|module| module.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["std/text", "lib/strings", "main"]);
+        let pad = &modules[1].functions[0];
+        assert_eq!(
+            pad.signature(),
+            "pub fun pad(text: Text, fill: Text = \" \", widths: [Int] = [1, 2]): Text"
+        );
+        assert_eq!(pad.comment.as_deref(), Some("Pads the text."));
+        assert_eq!(pad.position, Some((5, 9)));
+        let lib_path = base_dir.join("lib/strings.ab");
+        assert_eq!(modules[1].path, lib_path.to_string_lossy());
+    }
+}
+
+mod json_tests {
+    use serde_json::{json, Value};
+
+    use crate::docs::item::{DocsArgument, DocsFunction, DocsModule};
+    use crate::docs::json::render_json;
+
+    #[test]
+    fn test_render_json_exports_public_functions() {
+        let public = DocsFunction {
+            name: "pad".to_string(),
+            args: vec![DocsArgument {
+                name: "fill".to_string(),
+                kind: Some("Text".to_string()),
+                is_ref: false,
+                default: Some("\" \"".to_string()),
+            }],
+            returns: Some("Text".to_string()),
+            is_public: true,
+            is_failable: true,
+            comment: Some("Pads the text.".to_string()),
+            position: Some((3, 9)),
+        };
+        let private = DocsFunction {
+            name: "helper".to_string(),
+            is_public: false,
+            ..public.clone()
+        };
+        let modules = vec![DocsModule {
+            id: "lib/strings".to_string(),
+            path: "/project/lib/strings.ab".to_string(),
+            functions: vec![private, public],
+        }];
+        let result: Value = serde_json::from_str(&render_json(&modules)).unwrap();
+
+        assert_eq!(
+            result,
+            json!({
+                "modules": [{
+                    "id": "lib/strings",
+                    "path": "/project/lib/strings.ab",
+                    "functions": [{
+                        "name": "pad",
+                        "signature": "pub fun pad(fill: Text = \" \"): Text?",
+                        "args": [{
+                            "name": "fill",
+                            "type": "Text",
+                            "default": "\" \"",
+                            "ref": false,
+                        }],
+                        "returns": "Text",
+                        "failable": true,
+                        "doc": "Pads the text.",
+                        "location": {
+                            "file": "/project/lib/strings.ab",
+                            "line": 3,
+                            "column": 9,
+                        },
+                    }],
+                }],
+            })
+        );
+    }
+}
+
+mod coverage_tests {
+    use std::fs;
+
+    use crate::compiler::{AmberCompiler, CompilerOptions};
+    use crate::docs::coverage::{get_total_coverage, render_coverage, DocsCoverage};
+
+    #[test]
+    fn test_render_coverage() {
+        let modules = vec![
+            DocsCoverage::new(
+                "lib/strings".to_string(),
+                vec![
+                    ("pad".to_string(), true),
+                    ("trim".to_string(), false),
+                    ("SPACE".to_string(), false),
+                    ("WIDTH".to_string(), true),
+                ],
+            ),
+            DocsCoverage::new("main".to_string(), vec![]),
+        ];
+
+        assert_eq!(modules[0].documented, 2);
+        assert_eq!(modules[0].missing, ["trim", "SPACE"]);
+        assert_eq!(get_total_coverage(&modules), 50.0);
+        assert_eq!(get_total_coverage(&modules[1..]), 100.0);
+        assert_eq!(
+            render_coverage(&modules),
+            concat!(
+                "Module       Documented  Coverage\n",
+                "lib/strings         2/4     50.0%\n",
+                "main                0/0         -\n",
+                "Total               2/4     50.0%\n",
+                "\n",
+                "Missing documentation:\n",
+                "lib/strings: trim, SPACE\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_docs_coverage_of_project_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib = r#"
+import { trim } from "std/text"
+
+/// Pads the text.
+pub fun pad(text: Text): Text {
+    return trim(text) + " "
+}
+
+pub fun shout(text: Text): Text {
+    return text + "!"
+}
+
+#[allow_missing_docs]
+pub fun internal() {}
+
+fun private() {}
+
+/// Default width
+pub const WIDTH = 10
+
+pub const SPACE = " "
+"#;
+        fs::write(dir.path().join("lib.ab"), lib).unwrap();
+        let code = "import * from \"lib.ab\"\necho(pad(shout(SPACE)))\n";
+        let main_path = dir.path().join("main.ab");
+        fs::write(&main_path, code).unwrap();
+
+        let path = Some(main_path.to_string_lossy().to_string());
+        let compiler = AmberCompiler::new(code.to_string(), path, CompilerOptions::default());
+        let modules = compiler.generate_docs_coverage().unwrap();
+
+        assert_eq!(
+            modules,
+            [
+                DocsCoverage {
+                    id: "lib".to_string(),
+                    documented: 2,
+                    total: 4,
+                    missing: vec!["shout".to_string(), "SPACE".to_string()],
+                },
+                DocsCoverage::new("main".to_string(), vec![]),
+            ]
+        );
+    }
+}
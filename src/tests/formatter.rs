@@ -0,0 +1,140 @@
+use crate::formatter::{format_code, handle_fmt};
+use crate::testing::find_amber_files;
+use crate::FmtCommand;
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+fn format(code: &str) -> String {
+    format_code(code, None).unwrap()
+}
+
+#[test]
+fn spacing_and_indentation() {
+    let code = r#"
+fun   add(a:Int,b : Int):Int{
+return a+b
+}
+let x=[1,2,3]
+if x[0]>-1 and not false {echo("yes")}
+for i in 0 .. 10 {
+        echo(i)
+
+
+}
+let y = [
+1,
+2
+]
+"#;
+    let expected = r#"fun add(a: Int, b: Int): Int {
+    return a + b
+}
+let x = [1, 2, 3]
+if x[0] > -1 and not false { echo("yes") }
+for i in 0..10 {
+    echo(i)
+}
+let y = [
+    1,
+    2
+]
+"#;
+    assert_eq!(format(code), expected);
+}
+
+#[test]
+fn keeps_comments_flags_and_literals() {
+    let code = r#"#!/usr/bin/env amber
+/// Documentation
+///     indented
+#[allow_absurd_cast]
+pub fun foo(): Null? {   // trailing
+  // inside
+  let s = "a  {1+2}  b"
+  $echo   {s}  "x"$?
+
+
+  let range = 1..=-3
+}
+"#;
+    let expected = r#"#!/usr/bin/env amber
+/// Documentation
+///     indented
+#[allow_absurd_cast]
+pub fun foo(): Null? { // trailing
+    // inside
+    let s = "a  {1+2}  b"
+    $echo   {s}  "x"$?
+
+    let range = 1..=-3
+}
+"#;
+    assert_eq!(format(code), expected);
+}
+
+#[test]
+fn rewrites_deprecated_builtin_calls() {
+    let code = r#"main {
+    let name = "world"
+    echo "hello {name}"
+    echo nameof name
+    trust mv "a" "b"
+    let code = status
+    exit 1
+}
+"#;
+    let expected = r#"main {
+    let name = "world"
+    echo("hello {name}")
+    echo(nameof(name))
+    trust mv("a", "b")
+    let code = status()
+    exit(1)
+}
+"#;
+    assert_eq!(format(code), expected);
+}
+
+#[test]
+fn fails_on_syntax_error() {
+    assert!(format_code("fun foo( {", None).is_err());
+}
+
+#[test]
+fn formatting_is_stable() {
+    let mut files = vec![];
+    find_amber_files(&PathBuf::from("src/std"), &mut files).unwrap();
+    find_amber_files(&PathBuf::from("src/tests/validity"), &mut files).unwrap();
+    for file in files {
+        let code = fs::read_to_string(&file).unwrap();
+        let path = Some(file.to_string_lossy().to_string());
+        let formatted = format_code(&code, path.clone())
+            .unwrap_or_else(|err| panic!("Failed to format {file:?}: {:?}", err.message));
+        assert_eq!(
+            format_code(&formatted, path).unwrap(),
+            formatted,
+            "Formatting of {file:?} is not stable"
+        );
+    }
+}
+
+#[test]
+fn check_and_rewrite_files() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("main.ab");
+    fs::write(&file, "let x=1\necho(x)\n").unwrap();
+    let command = |check| FmtCommand {
+        inputs: vec![dir.path().to_path_buf()],
+        check,
+    };
+
+    assert_eq!(handle_fmt(command(true)).unwrap(), 1);
+    assert_eq!(fs::read_to_string(&file).unwrap(), "let x=1\necho(x)\n");
+
+    assert_eq!(handle_fmt(command(false)).unwrap(), 0);
+    assert_eq!(fs::read_to_string(&file).unwrap(), "let x = 1\necho(x)\n");
+
+    assert_eq!(handle_fmt(command(true)).unwrap(), 0);
+}
@@ -1,9 +1,11 @@
 use crate::built_info;
+use crate::docs::DocsFormat;
 use crate::execute_output;
 use crate::handle_completion_with_output;
 use crate::handle_docs;
 use crate::handle_eval;
 use crate::render_dash;
+use crate::utils::diagnostic::MessageFormat;
 use crate::write_output;
 use crate::DocsCommand;
 use crate::EvalCommand;
@@ -20,8 +22,14 @@ fn test_create_output_with_output_flag() {
         input: PathBuf::from("test.ab"),
         output: Some(PathBuf::from("output.sh")),
         no_proc: vec![],
+        project_config: false,
         minify: false,
         target: None,
+        message_format: MessageFormat::Human,
+        watch: false,
+        source_map: None,
+        stack_trace: false,
+        warn_missing_docs: false,
     };
 
     let result = create_output(&cmd);
@@ -37,8 +45,14 @@ fn test_create_output_with_stdin() {
         input: PathBuf::from("-"),
         output: None,
         no_proc: vec![],
+        project_config: false,
         minify: false,
         target: None,
+        message_format: MessageFormat::Human,
+        watch: false,
+        source_map: None,
+        stack_trace: false,
+        warn_missing_docs: false,
     };
 
     let result = create_output(&cmd);
@@ -54,8 +68,14 @@ fn test_create_output_default_extension() {
         input: PathBuf::from("test.amber"),
         output: None,
         no_proc: vec![],
+        project_config: false,
         minify: false,
         target: None,
+        message_format: MessageFormat::Human,
+        watch: false,
+        source_map: None,
+        stack_trace: false,
+        warn_missing_docs: false,
     };
 
     let result = create_output(&cmd);
@@ -70,7 +90,7 @@ fn test_compile_input_file() {
     let input_file = PathBuf::from("src/tests/functional/test.ab");
 
     let options = CompilerOptions::default();
-    let (code, messages) = compile_input(input_file, options);
+    let (code, messages) = compile_input(input_file, options, MessageFormat::Human);
 
     assert!(!code.is_empty());
     assert!(!messages);
@@ -143,12 +163,81 @@ fn test_handle_docs_with_usage_flag() {
         input: input_path,
         output: Some(output_path.clone()),
         usage: true,
+        format: DocsFormat::Markdown,
+        coverage: false,
+        fail_under: None,
     };
 
     let result = handle_docs(cmd);
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_handle_docs_html_format() {
+    let input_path = PathBuf::from("src/tests/stdlib/math_sum.ab");
+    let temp_dir = tempdir().unwrap();
+    let output_path = temp_dir.path().join("site");
+
+    let cmd = DocsCommand {
+        input: input_path,
+        output: Some(output_path.clone()),
+        usage: false,
+        format: DocsFormat::Html,
+        coverage: false,
+        fail_under: None,
+    };
+
+    let result = handle_docs(cmd);
+    assert!(result.is_ok());
+    assert!(output_path.join("index.html").is_file());
+    assert!(output_path.join("std/math/fn.math_sum.html").is_file());
+}
+
+#[test]
+fn test_handle_docs_json_format() {
+    let input_path = PathBuf::from("src/tests/stdlib/math_sum.ab");
+    let temp_dir = tempdir().unwrap();
+    let output_path = temp_dir.path().join("api");
+
+    let cmd = DocsCommand {
+        input: input_path,
+        output: Some(output_path.clone()),
+        usage: false,
+        format: DocsFormat::Json,
+        coverage: false,
+        fail_under: None,
+    };
+
+    let result = handle_docs(cmd);
+    assert!(result.is_ok());
+    let json = std::fs::read_to_string(output_path.join("docs.json")).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let modules = json["modules"].as_array().unwrap();
+    assert!(modules
+        .iter()
+        .any(|module| module["id"] == "std/math" && module["path"] == "std/math"));
+}
+
+#[test]
+fn test_handle_docs_coverage() {
+    let input_path = PathBuf::from("src/tests/stdlib/math_sum.ab");
+    let temp_dir = tempdir().unwrap();
+    let output_path = temp_dir.path().join("docs");
+
+    let cmd = DocsCommand {
+        input: input_path,
+        output: Some(output_path.clone()),
+        usage: false,
+        format: DocsFormat::Markdown,
+        coverage: true,
+        fail_under: None,
+    };
+
+    let result = handle_docs(cmd);
+    assert!(result.is_ok());
+    assert!(!output_path.exists());
+}
+
 #[test]
 fn test_execute_output_with_messages() {
     let code = "exit 0".to_string();
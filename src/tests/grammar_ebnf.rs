@@ -70,6 +70,7 @@ fn grammar_contains_all_expression_rules() {
         "status",
         "function_call",
         "function_call_failed",
+        "lambda",
     ];
     for rule in &expected {
         assert!(
@@ -206,7 +207,10 @@ fn grammar_contains_all_terminal_definitions() {
     assert!(g.contains("LETTER = 'A'..'Z' | 'a'..'z' ;"));
     assert!(g.contains("DIGIT = '0'..'9' ;"));
     assert!(g.contains("TYPE = SIMPLE_TYPE, { '|', SIMPLE_TYPE } ;"));
-    assert!(g.contains("SIMPLE_TYPE = 'Text' | 'Num' | 'Bool' | 'Null' | 'Int' | '[', TYPE, ']' ;"));
+    assert!(g.contains("SIMPLE_TYPE = 'Text' | 'Num' | 'Bool' | 'Null' | 'Int' | '[', TYPE, ']' | '[', 'Text', ':', TYPE, ']' | function_type | identifier ;"));
+    assert!(g.contains(
+        "function_type = 'Fun', '(', [ TYPE, { ',', TYPE } ], ')', [ ':', SIMPLE_TYPE ] ;"
+    ));
 }
 
 #[test]
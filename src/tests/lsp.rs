@@ -0,0 +1,174 @@
+use crate::lsp::protocol::{path_to_uri, read_message, uri_to_path, write_message};
+use crate::lsp::{serve, LanguageServer};
+use serde_json::{json, Value};
+use std::io::Cursor;
+
+const URI: &str = "file:///tmp/amber%20lsp/main.ab";
+
+const CODE: &str = r#"/// Adds two numbers
+fun add(a: Int, b: Int): Int {
+    return a + b
+}
+let result = add(1, 2)
+echo(result)
+"#;
+
+fn open_document(server: &mut LanguageServer, text: &str) -> Vec<Value> {
+    server.handle(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": URI, "languageId": "amber", "version": 1, "text": text } }
+    }))
+}
+
+fn request(server: &mut LanguageServer, method: &str, line: usize, character: usize) -> Value {
+    let responses = server.handle(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": {
+            "textDocument": { "uri": URI },
+            "position": { "line": line, "character": character }
+        }
+    }));
+    assert_eq!(responses.len(), 1);
+    responses[0]["result"].clone()
+}
+
+#[test]
+fn message_framing_roundtrip() {
+    let message = json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} });
+    let mut buffer = vec![];
+    write_message(&mut buffer, &message).unwrap();
+    assert!(buffer.starts_with(b"Content-Length: "));
+    let mut reader = Cursor::new(buffer);
+    assert_eq!(read_message(&mut reader).unwrap(), Some(message));
+    assert_eq!(read_message(&mut reader).unwrap(), None);
+}
+
+#[test]
+fn uri_conversion() {
+    assert_eq!(uri_to_path(URI), "/tmp/amber lsp/main.ab");
+    assert_eq!(path_to_uri("/tmp/amber lsp/main.ab"), URI);
+}
+
+#[test]
+fn publishes_diagnostics() {
+    let mut server = LanguageServer::new();
+    let notifications = open_document(&mut server, "let value = 1\nlet other: Int = \"text\"\n");
+    assert_eq!(notifications.len(), 1);
+    let params = &notifications[0]["params"];
+    assert_eq!(
+        notifications[0]["method"],
+        "textDocument/publishDiagnostics"
+    );
+    assert_eq!(params["uri"], URI);
+    let diagnostic = &params["diagnostics"][0];
+    assert_eq!(diagnostic["severity"], 1);
+    assert_eq!(diagnostic["range"]["start"]["line"], 1);
+    assert!(diagnostic["message"].as_str().unwrap().contains("'other'"));
+}
+
+#[test]
+fn publishes_warnings() {
+    let mut server = LanguageServer::new();
+    let notifications = open_document(&mut server, "echo 1\n");
+    let diagnostic = &notifications[0]["params"]["diagnostics"][0];
+    assert_eq!(diagnostic["severity"], 2);
+    assert_eq!(
+        diagnostic["range"],
+        json!({ "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 4 } })
+    );
+}
+
+#[test]
+fn hover_shows_signature_and_documentation() {
+    let mut server = LanguageServer::new();
+    open_document(&mut server, CODE);
+    let hover = request(&mut server, "textDocument/hover", 4, 14);
+    let contents = hover["contents"]["value"].as_str().unwrap();
+    assert_eq!(
+        contents,
+        "```ab\nfun add(a: Int, b: Int): Int\n```\n\nAdds two numbers"
+    );
+    let hover = request(&mut server, "textDocument/hover", 5, 6);
+    assert_eq!(hover["contents"]["value"], "```ab\nlet result: Int\n```");
+}
+
+#[test]
+fn hover_on_function_argument() {
+    let mut server = LanguageServer::new();
+    open_document(&mut server, CODE);
+    let hover = request(&mut server, "textDocument/hover", 2, 11);
+    assert_eq!(hover["contents"]["value"], "```ab\nlet a: Int\n```");
+}
+
+#[test]
+fn definition_points_to_declaration() {
+    let mut server = LanguageServer::new();
+    open_document(&mut server, CODE);
+    // Files that do not exist on disk cannot be pointed to
+    assert_eq!(
+        request(&mut server, "textDocument/definition", 5, 6),
+        Value::Null
+    );
+
+    let path = std::env::temp_dir().join("amber_lsp_definition.ab");
+    std::fs::write(&path, CODE).unwrap();
+    let uri = path_to_uri(&path.to_string_lossy());
+    server.handle(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": uri, "text": CODE } }
+    }));
+    let responses = server.handle(json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "textDocument/definition",
+        "params": { "textDocument": { "uri": uri }, "position": { "line": 5, "character": 6 } }
+    }));
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        responses[0]["result"],
+        json!({
+            "uri": uri,
+            "range": { "start": { "line": 4, "character": 4 }, "end": { "line": 4, "character": 10 } }
+        })
+    );
+}
+
+#[test]
+fn completion_includes_local_and_stdlib_symbols() {
+    let mut server = LanguageServer::new();
+    open_document(&mut server, CODE);
+    let completion = request(&mut server, "textDocument/completion", 5, 0);
+    let items = completion["items"].as_array().unwrap();
+    let add = items.iter().find(|item| item["label"] == "add").unwrap();
+    assert_eq!(add["kind"], 3);
+    let trim = items.iter().find(|item| item["label"] == "trim").unwrap();
+    assert_eq!(
+        trim["additionalTextEdits"][0]["newText"],
+        "import { trim } from \"std/text\"\n"
+    );
+}
+
+#[test]
+fn unknown_request_and_exit() {
+    let mut server = LanguageServer::new();
+    let responses = server.handle(json!({ "jsonrpc": "2.0", "id": 7, "method": "foo/bar" }));
+    assert_eq!(responses[0]["error"]["code"], -32601);
+    let mut input = vec![];
+    for message in [
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "shutdown" }),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+    ] {
+        write_message(&mut input, &message).unwrap();
+    }
+    let mut output = vec![];
+    let code = serve(&mut server, &mut Cursor::new(input), &mut output).unwrap();
+    assert_eq!(code, 0);
+    let mut output = Cursor::new(output);
+    let response = read_message(&mut output).unwrap().unwrap();
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["result"], Value::Null);
+}
@@ -12,12 +12,15 @@ pub mod compiling;
 pub mod documentation;
 mod erroring;
 pub mod extra;
+mod formatter;
 mod functional;
 pub mod main_helpers;
 pub mod modules;
 pub mod grammar_ebnf;
+mod lsp;
 pub mod optimizing;
 pub mod postprocessor;
+mod repl;
 mod runtime;
 mod stdlib;
 mod test_mode;
@@ -25,6 +28,7 @@ mod testing;
 pub mod translating;
 mod validity;
 mod warning;
+mod watch;
 
 pub mod utils;
 
@@ -1,4 +1,9 @@
-use crate::compiler::postprocessor::PostProcessor;
+use std::fs;
+use std::path::Path;
+
+use crate::compiler::config::ProjectConfig;
+use crate::compiler::postprocessor::{FailurePolicy, PostProcessor};
+use crate::compiler::{AmberCompiler, CompilerOptions};
 use crate::tests::eval_bash;
 
 use super::compile_code;
@@ -62,21 +67,21 @@ fn test_postprocessor_get_default() {
 }
 
 #[test]
-fn test_postprocessor_filter_default() {
+fn test_postprocessor_filter() {
     use wildmatch::WildMatchPattern;
 
     let processors = PostProcessor::get_default();
     let filter = vec![WildMatchPattern::new("nonexistent")];
-    let filtered = PostProcessor::filter_default(filter);
+    let filtered = PostProcessor::filter(processors.clone(), &filter);
     assert_eq!(filtered.len(), processors.len());
 }
 
 #[test]
-fn test_postprocessor_filter_default_removes_matching() {
+fn test_postprocessor_filter_removes_matching() {
     use wildmatch::WildMatchPattern;
 
     let filter = vec![WildMatchPattern::new("bshchk")];
-    let filtered = PostProcessor::filter_default(filter);
+    let filtered = PostProcessor::filter(PostProcessor::get_default(), &filter);
     let bshchk_exists = filtered.iter().any(|pp| pp.name == "bshchk");
     assert!(!bshchk_exists, "bshchk should be filtered out");
 }
@@ -101,3 +106,169 @@ fn test_each_installed_postprocessor() {
         }
     }
 }
+
+#[test]
+fn test_project_config_postprocessors() {
+    let content = r#"
+[[postprocessor]]
+name = "shellcheck"
+args = ["-s", "bash", "-"]
+check = true
+
+[[postprocessor]]
+name = "format"
+command = "scripts/format.sh"
+on-failure = "warn"
+"#;
+    let config = ProjectConfig::parse(content, Path::new("/project")).unwrap();
+    let [shellcheck, format] = config.postprocessors.as_slice() else {
+        panic!("Expected two postprocessors");
+    };
+
+    assert_eq!(shellcheck.name, "shellcheck");
+    assert_eq!(shellcheck.bin.to_string_lossy(), "shellcheck");
+    let args = shellcheck
+        .cmd()
+        .borrow()
+        .get_args()
+        .map(|arg| arg.to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(args, ["-s", "bash", "-"]);
+    assert!(shellcheck.check_only);
+    assert_eq!(shellcheck.on_failure, FailurePolicy::Error);
+
+    assert_eq!(format.bin.to_string_lossy(), "/project/scripts/format.sh");
+    assert!(!format.check_only);
+    assert_eq!(format.on_failure, FailurePolicy::Warn);
+}
+
+#[test]
+fn test_project_config_errors() {
+    let parse = |content: &str| ProjectConfig::parse(content, Path::new(".")).unwrap_err();
+
+    assert_eq!(
+        parse("[[postprocessor]]\ncommand = \"shfmt\""),
+        "Postprocessor is missing the 'name' field"
+    );
+    assert_eq!(
+        parse("[[postprocessor]]\nname = \"shfmt\"\non-failure = \"panic\""),
+        "Unknown failure policy 'panic' of postprocessor 'shfmt'. Expected 'error', 'warn' or 'ignore'"
+    );
+    assert_eq!(
+        parse("[[postprocessor]]\nname = \"shfmt\"\nargs = \"-i 4\""),
+        "Field 'args' of postprocessor 'shfmt' must be an array"
+    );
+    assert_eq!(
+        parse("[[postprocessor]]\nname = \"shfmt\"\norder = 1"),
+        "Unknown field 'order' of postprocessor 'shfmt'"
+    );
+    assert_eq!(parse("postprocessors = []"), "Unknown key 'postprocessors'");
+}
+
+#[test]
+fn test_postprocessor_pipeline_order() {
+    let declared = vec![
+        PostProcessor::new("shfmt", "shfmt"),
+        PostProcessor::new("bshchk", "bshchk").with_check_only(true),
+    ];
+    let pipeline = PostProcessor::get_pipeline(declared);
+    let names = pipeline
+        .iter()
+        .map(|pp| pp.name.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, ["bshchk", "shfmt"]);
+    assert!(
+        pipeline[0].check_only,
+        "declared bshchk should replace the default"
+    );
+}
+
+#[test]
+fn test_postprocessor_check_only_keeps_code() {
+    let check = PostProcessor::new("check", "true").with_check_only(true);
+    assert_eq!(check.execute("echo 1\n".to_string()).unwrap(), "echo 1\n");
+}
+
+#[test]
+fn test_postprocessor_required_is_not_skipped() {
+    let missing = PostProcessor::new("missing", "nonexistent_binary_12345").with_optional(false);
+    assert!(missing.execute("echo 1".to_string()).is_err());
+}
+
+#[test]
+fn test_project_config_in_compilation() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = r#"
+[[postprocessor]]
+name = "upper"
+command = "sed"
+args = ["s/hello/HELLO/"]
+
+[[postprocessor]]
+name = "gate"
+command = "sh"
+args = ["-c", "cat > /dev/null; echo 'Code rejected' >&2; exit 1"]
+check = true
+on-failure = "ignore"
+"#;
+    fs::write(dir.path().join("amber.toml"), config).unwrap();
+    let file = dir.path().join("main.ab");
+    fs::write(&file, "echo(\"hello\")").unwrap();
+    let path = Some(file.to_string_lossy().to_string());
+    let compile = |no_proc: &[&str]| {
+        let no_proc = no_proc.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        let options =
+            CompilerOptions::from_args(&no_proc, false, false, None).with_project_config(true);
+        let compiler = AmberCompiler::new("echo(\"hello\")".to_string(), path.clone(), options);
+        compiler.compile().map(|(_, code)| eval_bash(code).0)
+    };
+
+    assert_eq!(compile(&["bshchk"]).unwrap(), "HELLO");
+    assert_eq!(compile(&["bshchk", "up*"]).unwrap(), "hello");
+
+    fs::write(
+        dir.path().join("amber.toml"),
+        config.replace("ignore", "error"),
+    )
+    .unwrap();
+    let error = compile(&["bshchk"]).unwrap_err();
+    assert_eq!(
+        error.message.unwrap(),
+        "Postprocessor 'gate' failed\nCode rejected"
+    );
+}
+
+fn compile_with_upper_config(config_dir: &Path, file: &Path, project_config: bool) -> String {
+    let config = r#"
+[[postprocessor]]
+name = "upper"
+command = "sed"
+args = ["s/hello/HELLO/"]
+"#;
+    fs::write(config_dir.join("amber.toml"), config).unwrap();
+    fs::write(file, "echo(\"hello\")").unwrap();
+    let no_proc = vec!["bshchk".to_string()];
+    let options = CompilerOptions::from_args(&no_proc, false, false, None)
+        .with_project_config(project_config);
+    let path = Some(file.to_string_lossy().to_string());
+    let compiler = AmberCompiler::new("echo(\"hello\")".to_string(), path, options);
+    let (_, code) = compiler.compile().unwrap();
+    eval_bash(code).0
+}
+
+#[test]
+fn test_project_config_requires_opt_in() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("main.ab");
+    assert_eq!(compile_with_upper_config(dir.path(), &file, false), "hello");
+}
+
+#[test]
+fn test_project_config_in_parent_directory_is_ignored() {
+    let dir = tempfile::tempdir().unwrap();
+    let nested = dir.path().join("nested");
+    fs::create_dir(&nested).unwrap();
+    let file = nested.join("main.ab");
+    assert_eq!(compile_with_upper_config(dir.path(), &file, true), "hello");
+}
@@ -0,0 +1,94 @@
+use crate::compiler::AmberCompiler;
+use crate::repl::{needs_more_input, Repl};
+use crate::utils::ShellType;
+use pretty_assertions::assert_eq;
+
+/// Compiles the entries one by one and runs the resulting code in a single shell
+fn run_entries(entries: &[&str]) -> String {
+    let mut repl = Repl::new(None);
+    let code = entries
+        .iter()
+        .map(|entry| match repl.compile(entry) {
+            Ok((_, code)) => code,
+            Err(err) => panic!("Failed to compile {entry:?}: {:?}", err.message),
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    let output = AmberCompiler::find_shell(None)
+        .expect("Failed to find shell")
+        .arg("-c")
+        .arg(code)
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn declarations_stay_in_scope() {
+    let output = run_entries(&[
+        "let count = 2",
+        "fun add(a: Int, b: Int): Int { return a + b }",
+        "count += 1",
+        "echo(add(count, 10))",
+    ]);
+    assert_eq!(output, "13\n");
+}
+
+#[test]
+fn imports_stay_in_scope() {
+    let output = run_entries(&[
+        "import { uppercase } from \"std/text\"",
+        "echo(uppercase(\"abc\"))",
+        "import { array_contains } from \"std/array\"",
+        "echo(array_contains([1, 2], 2))",
+    ]);
+    assert_eq!(output, "ABC\n1\n");
+}
+
+#[test]
+fn generic_functions_get_new_instances() {
+    let output = run_entries(&[
+        "fun twice(value) { return value + value }",
+        "echo(twice(2))",
+        "echo(twice(\"ab\"))",
+        "echo(twice([1]))",
+    ]);
+    assert_eq!(output, "4\nabab\n1 1\n");
+}
+
+#[test]
+fn lone_expressions_are_printed() {
+    let output = run_entries(&["let x = 20", "x * 2 + 2", "\"text\"", "[1, 2]"]);
+    assert_eq!(output, "42\ntext\n1 2\n");
+}
+
+#[test]
+fn failed_entries_are_discarded() {
+    let mut repl = Repl::new(None);
+    assert!(repl.compile("let value = missing").is_err());
+    assert!(repl.compile("echo(value)").is_err());
+    assert!(repl.compile("let value = 1").is_ok());
+    assert!(repl.compile("echo(value)").is_ok());
+
+    assert!(repl
+        .compile("fun broken(): Int { return 1 }\necho(missing)")
+        .is_err());
+    assert!(repl.compile("echo(broken())").is_err());
+    assert!(repl.compile("fun broken(): Int { return 2 }").is_ok());
+    assert!(repl.compile("echo(broken())").is_ok());
+
+    let mut repl = Repl::new(Some(ShellType::Posix));
+    assert!(repl
+        .compile("let registry = [\"key\": \"value\"]\necho(missing)")
+        .is_err());
+    assert!(repl.compile("echo(1)").is_ok());
+}
+
+#[test]
+fn multiline_entries() {
+    assert!(needs_more_input("fun foo() {\n"));
+    assert!(needs_more_input("let x = [1,\n"));
+    assert!(needs_more_input("echo(\"text {\n"));
+    assert!(!needs_more_input("fun foo() {\n}\n"));
+    assert!(!needs_more_input("echo(1))\n"));
+}
@@ -34,3 +34,23 @@ fn test_runtime_errors(file: &str) {
         _ => panic!("Expected runtime error."),
     }
 }
+
+#[test]
+fn test_signal_handler_exits_with_signal_code() {
+    let code = r#"
+        main {
+            on signal EXIT {
+                echo("cleaned up")
+            }
+            on signal SIGINT, SIGTERM {
+                echo("terminated")
+            }
+            trust $ kill -TERM \$\$ $
+            echo("unreachable")
+        }
+    "#;
+    let mut compiler = AmberCompiler::new(code.to_string(), None, CompilerOptions::default());
+    let (output, status) = compiler.test_eval().expect("Couldn't run Amber code");
+    assert_eq!(output, "terminated\ncleaned up\n");
+    assert_eq!(status.code(), Some(143));
+}
@@ -1,4 +1,9 @@
-use crate::testing::{find_amber_files, get_tests_to_run, handle_test};
+use crate::compiler::{AmberCompiler, CompilerOptions};
+use crate::testing::reporter::{render_report, TestReporter, TestResult};
+use crate::testing::{
+    find_amber_files, get_doc_examples, get_doctest_path, get_doctests_to_run, get_tests_to_run,
+    handle_test,
+};
 use crate::TestCommand;
 use std::path::PathBuf;
 
@@ -25,8 +30,13 @@ mod test {
             input: test_file.clone(),
             args: vec![],
             no_proc: Vec::new(),
+            project_config: false,
             target: None,
             test_case: None,
+            watch: false,
+            doc: false,
+            reporter: TestReporter::Human,
+            output: None,
         };
 
         let result = get_tests_to_run(&command);
@@ -44,8 +54,13 @@ mod test {
             input: test_file.clone(),
             args: vec![],
             no_proc: Vec::new(),
+            project_config: false,
             target: None,
             test_case: Some("foo".to_string()),
+            watch: false,
+            doc: false,
+            reporter: TestReporter::Human,
+            output: None,
         };
 
         let result = get_tests_to_run(&command);
@@ -59,8 +74,13 @@ mod test {
             input: PathBuf::from("/non/existent/path.ab"),
             args: vec![],
             no_proc: Vec::new(),
+            project_config: false,
             target: None,
             test_case: None,
+            watch: false,
+            doc: false,
+            reporter: TestReporter::Human,
+            output: None,
         };
 
         let result = get_tests_to_run(&command);
@@ -76,8 +96,13 @@ mod test {
             input: test_dir.clone(),
             args: vec![],
             no_proc: Vec::new(),
+            project_config: false,
             target: None,
             test_case: None,
+            watch: false,
+            doc: false,
+            reporter: TestReporter::Human,
+            output: None,
         };
 
         let result = get_tests_to_run(&command);
@@ -93,8 +118,13 @@ mod test {
             input: test_file.clone(),
             args: vec![],
             no_proc: Vec::new(),
+            project_config: false,
             target: None,
             test_case: None,
+            watch: false,
+            doc: false,
+            reporter: TestReporter::Human,
+            output: None,
         };
 
         let result = handle_test(command);
@@ -110,8 +140,13 @@ mod test {
             input: test_dir.clone(),
             args: vec![],
             no_proc: Vec::new(),
+            project_config: false,
             target: None,
             test_case: None,
+            watch: false,
+            doc: false,
+            reporter: TestReporter::Human,
+            output: None,
         };
 
         let result = handle_test(command);
@@ -128,8 +163,13 @@ mod test {
             input: test_file.clone(),
             args: vec![],
             no_proc: Vec::new(),
+            project_config: false,
             target: None,
             test_case: None,
+            watch: false,
+            doc: false,
+            reporter: TestReporter::Human,
+            output: None,
         };
 
         let result = handle_test(command);
@@ -146,8 +186,13 @@ mod test {
             input: test_file.clone(),
             args: vec![],
             no_proc: Vec::new(),
+            project_config: false,
             target: None,
             test_case: None,
+            watch: false,
+            doc: false,
+            reporter: TestReporter::Human,
+            output: None,
         };
 
         let result = handle_test(command);
@@ -164,8 +209,13 @@ mod test {
             input: test_file.clone(),
             args: vec![],
             no_proc: Vec::new(),
+            project_config: false,
             target: None,
             test_case: None,
+            watch: false,
+            doc: false,
+            reporter: TestReporter::Human,
+            output: None,
         };
 
         let result = get_tests_to_run(&command);
@@ -183,8 +233,13 @@ mod test {
             input: temp_empty.clone(),
             args: vec![],
             no_proc: Vec::new(),
+            project_config: false,
             target: None,
             test_case: None,
+            watch: false,
+            doc: false,
+            reporter: TestReporter::Human,
+            output: None,
         };
 
         let result = handle_test(command);
@@ -202,8 +257,13 @@ mod test {
             input: test_file.clone(),
             args: vec![],
             no_proc: Vec::new(),
+            project_config: false,
             target: None,
             test_case: None,
+            watch: false,
+            doc: false,
+            reporter: TestReporter::Human,
+            output: None,
         };
 
         let result = handle_test(command);
@@ -211,4 +271,310 @@ mod test {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 1);
     }
+
+    #[test]
+    fn test_get_doc_examples() {
+        let comment = "Example\n```ab\necho(1)\n```\n```ab no_run\necho(2)\n```\n```ab ignore\necho(3)\n```\n```sh\necho 4\n```";
+
+        let examples = get_doc_examples(comment);
+
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].code, "echo(1)\n");
+        assert!(!examples[0].no_run);
+        assert_eq!(examples[1].code, "echo(2)\n");
+        assert!(examples[1].no_run);
+    }
+
+    #[test]
+    fn test_get_doctests_to_run() {
+        let command = TestCommand {
+            input: PathBuf::from("src/tests/testing/doctest.ab"),
+            args: vec![],
+            no_proc: Vec::new(),
+            project_config: false,
+            target: None,
+            test_case: None,
+            watch: false,
+            doc: true,
+            reporter: TestReporter::Human,
+            output: None,
+        };
+
+        let tests = get_doctests_to_run(&command).unwrap();
+
+        let names = tests
+            .iter()
+            .map(|test| test.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["sum example", "divide example 1", "divide example 2"]
+        );
+        assert!(tests[0]
+            .code
+            .starts_with("import * from \"doctest.ab\"\nmain {\n"));
+        assert!(tests[2].no_run);
+    }
+
+    #[test]
+    fn test_handle_test_doc() {
+        let command = TestCommand {
+            input: PathBuf::from("src/tests/testing/doctest.ab"),
+            args: vec![],
+            no_proc: Vec::new(),
+            project_config: false,
+            target: None,
+            test_case: None,
+            watch: false,
+            doc: true,
+            reporter: TestReporter::Human,
+            output: None,
+        };
+
+        let result = handle_test(command);
+
+        assert_eq!(result.unwrap(), 0);
+    }
+
+    #[test]
+    fn test_handle_test_doc_with_failing_example() {
+        let command = TestCommand {
+            input: PathBuf::from("src/tests/testing/doctest_failing.ab"),
+            args: vec![],
+            no_proc: Vec::new(),
+            project_config: false,
+            target: None,
+            test_case: None,
+            watch: false,
+            doc: true,
+            reporter: TestReporter::Human,
+            output: None,
+        };
+
+        let result = handle_test(command);
+
+        assert_eq!(result.unwrap(), 1);
+    }
+
+    #[test]
+    fn test_std_doc_examples_compile() {
+        let command = TestCommand {
+            input: PathBuf::from("src/std"),
+            args: vec![],
+            no_proc: vec!["*".to_string()],
+            project_config: false,
+            target: None,
+            test_case: None,
+            watch: false,
+            doc: true,
+            reporter: TestReporter::Human,
+            output: None,
+        };
+
+        let tests = get_doctests_to_run(&command).unwrap();
+        assert!(!tests.is_empty());
+
+        // Running the examples would touch the file system and the network.
+        // Compiling the examples of a module as blocks of a single main block
+        // saves typechecking the module for every example.
+        let mut modules: Vec<(PathBuf, String)> = vec![];
+        for test in &tests {
+            let file_name = test.file.file_name().unwrap().to_string_lossy();
+            let header = format!("import * from \"{file_name}\"\nmain {{\n");
+            let body = test
+                .code
+                .strip_prefix(&header)
+                .and_then(|code| code.strip_suffix("}\n"))
+                .expect("Examples of the standard library should not declare items");
+            match modules.last_mut() {
+                Some((file, code)) if *file == test.file => {
+                    code.push_str(&format!("{{\n{body}}}\n"));
+                }
+                _ => modules.push((test.file.clone(), format!("{header}{{\n{body}}}\n"))),
+            }
+        }
+
+        for (file, code) in modules {
+            let options = CompilerOptions::from_args(&command.no_proc, false, false, None);
+            let path = get_doctest_path(&file).to_string_lossy().to_string();
+            let compiler = AmberCompiler::new(format!("{code}}}\n"), Some(path), options);
+            if let Err(err) = compiler.compile() {
+                panic!(
+                    "Examples of {} do not compile: {}",
+                    file.display(),
+                    err.message.unwrap_or_default()
+                );
+            }
+        }
+    }
+
+    fn get_report_results() -> Vec<TestResult> {
+        vec![
+            TestResult {
+                file: PathBuf::from("tests/math.ab"),
+                name: "adds <numbers>".to_string(),
+                duration: std::time::Duration::from_millis(1500),
+                stdout: "3\n".to_string(),
+                stderr: String::new(),
+                failure: None,
+            },
+            TestResult {
+                file: PathBuf::from("tests/math.ab"),
+                name: String::new(),
+                duration: std::time::Duration::from_millis(20),
+                stdout: String::new(),
+                stderr: "expected 4 & got 5\n".to_string(),
+                failure: Some("expected 4 & got 5\nExited with code 1".to_string()),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_junit_report() {
+        let report = render_report(TestReporter::Junit, &get_report_results()).unwrap();
+
+        assert_eq!(
+            report,
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<testsuites name=\"amber\" tests=\"2\" failures=\"1\" time=\"1.520\">\n",
+                "  <testsuite name=\"tests/math.ab\" tests=\"2\" failures=\"1\" time=\"1.520\">\n",
+                "    <testcase name=\"adds &lt;numbers&gt;\" classname=\"tests/math.ab\" file=\"tests/math.ab\" time=\"1.500\">\n",
+                "      <system-out>3\n</system-out>\n",
+                "    </testcase>\n",
+                "    <testcase name=\"tests/math.ab\" classname=\"tests/math.ab\" file=\"tests/math.ab\" time=\"0.020\">\n",
+                "      <failure message=\"expected 4 &amp; got 5\">expected 4 &amp; got 5\nExited with code 1</failure>\n",
+                "      <system-err>expected 4 &amp; got 5\n</system-err>\n",
+                "    </testcase>\n",
+                "  </testsuite>\n",
+                "</testsuites>\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_tap_report() {
+        let report = render_report(TestReporter::Tap, &get_report_results()).unwrap();
+
+        assert_eq!(
+            report,
+            concat!(
+                "TAP version 13\n",
+                "1..2\n",
+                "ok 1 - tests/math.ab (adds <numbers>)\n",
+                "  ---\n",
+                "  file: \"tests/math.ab\"\n",
+                "  duration_ms: 1500\n",
+                "  stdout: |-\n",
+                "    3\n",
+                "  ...\n",
+                "not ok 2 - tests/math.ab\n",
+                "  ---\n",
+                "  file: \"tests/math.ab\"\n",
+                "  duration_ms: 20\n",
+                "  message: |-\n",
+                "    expected 4 & got 5\n",
+                "    Exited with code 1\n",
+                "  stderr: |-\n",
+                "    expected 4 & got 5\n",
+                "  ...\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_json_report() {
+        let report = render_report(TestReporter::Json, &get_report_results()).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+
+        assert_eq!(report["passed"], 1);
+        assert_eq!(report["failed"], 1);
+        assert_eq!(report["tests"][0]["status"], "passed");
+        assert_eq!(report["tests"][0]["message"], serde_json::Value::Null);
+        assert_eq!(report["tests"][1]["status"], "failed");
+        assert_eq!(
+            report["tests"][1]["message"],
+            "expected 4 & got 5\nExited with code 1"
+        );
+        assert!(render_report(TestReporter::Human, &get_report_results()).is_none());
+    }
+
+    #[test]
+    fn test_handle_test_writes_report_to_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("report.xml");
+
+        let command = TestCommand {
+            input: PathBuf::from("src/tests/testing/empty_out.ab"),
+            args: vec![],
+            no_proc: Vec::new(),
+            project_config: false,
+            target: None,
+            test_case: None,
+            watch: false,
+            doc: false,
+            reporter: TestReporter::Junit,
+            output: Some(output.clone()),
+        };
+
+        let result = handle_test(command);
+
+        assert_eq!(result.unwrap(), 1);
+        let report = std::fs::read_to_string(output).unwrap();
+        assert!(report.contains("file=\"src/tests/testing/empty_out.ab\""));
+        assert!(report.contains("<failure message=\"Exited with code 1\">"));
+    }
+
+    #[test]
+    fn test_handle_test_reports_assertion_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("report.json");
+
+        let command = TestCommand {
+            input: PathBuf::from("src/tests/testing/failing_assert.ab"),
+            args: vec![],
+            no_proc: Vec::new(),
+            project_config: false,
+            target: None,
+            test_case: None,
+            watch: false,
+            doc: false,
+            reporter: TestReporter::Json,
+            output: Some(output.clone()),
+        };
+
+        let result = handle_test(command);
+
+        assert_eq!(result.unwrap(), 1);
+        let report = std::fs::read_to_string(output).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+        assert_eq!(
+            report["tests"][0]["message"],
+            "Assertion failed: 4 != 5\nExited with code 1"
+        );
+    }
+
+    #[test]
+    fn test_handle_test_rejects_human_report_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("report.txt");
+
+        let command = TestCommand {
+            input: PathBuf::from("src/tests/testing/empty_out.ab"),
+            args: vec![],
+            no_proc: Vec::new(),
+            project_config: false,
+            target: None,
+            test_case: None,
+            watch: false,
+            doc: false,
+            reporter: TestReporter::Human,
+            output: Some(output.clone()),
+        };
+
+        let result = handle_test(command);
+
+        assert_eq!(result.unwrap(), 1);
+        assert!(!output.exists());
+    }
 }
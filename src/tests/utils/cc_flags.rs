@@ -13,6 +13,7 @@ mod cc_flags_tests {
         assert_eq!(get_ccflag_by_name("allow_absurd_cast"), CCFlags::AllowAbsurdCast);
         assert_eq!(get_ccflag_by_name("allow_dead_code"), CCFlags::AllowDeadCode);
         assert_eq!(get_ccflag_by_name("allow_public_mutable"), CCFlags::AllowPublicMutable);
+        assert_eq!(get_ccflag_by_name("allow_missing_docs"), CCFlags::AllowMissingDocs);
     }
 
     #[test]
@@ -25,6 +26,10 @@ mod cc_flags_tests {
     fn test_get_ccflag_name() {
         assert_eq!(get_ccflag_name(CCFlags::AllowNestedIfElse), "allow_nested_if_else");
         assert_eq!(get_ccflag_name(CCFlags::AllowCamelCase), "allow_camel_case");
+        assert_eq!(
+            get_ccflag_name(CCFlags::AllowMissingDocs),
+            "allow_missing_docs"
+        );
         assert_eq!(get_ccflag_name(CCFlags::UndefinedFlag), "undefined_flag");
     }
 
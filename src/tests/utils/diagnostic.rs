@@ -0,0 +1,59 @@
+//! Tests for utils/diagnostic.rs
+
+use crate::compiler::{AmberCompiler, CompilerOptions};
+use crate::utils::diagnostic::{span, Diagnostic};
+use heraclitus_compiler::prelude::*;
+
+#[cfg(test)]
+mod diagnostic_tests {
+    use super::*;
+
+    #[test]
+    fn test_span_single_line() {
+        let position = PositionInfo::at_pos(None, (2, 5), 10, 3);
+        assert_eq!(
+            span(&position, Some("let x = 1\nlet abc = 2\n")),
+            ((2, 5), (2, 8))
+        );
+        assert_eq!(span(&position, None), ((2, 5), (2, 8)));
+    }
+
+    #[test]
+    fn test_span_multiple_lines() {
+        let code = "let x = [\n    1\n]\n";
+        let position = PositionInfo::at_pos(None, (1, 9), 8, 9);
+        assert_eq!(span(&position, Some(code)), ((1, 9), (3, 2)));
+    }
+
+    #[test]
+    fn test_diagnostic_from_compiler_warning() {
+        let code = "let x = 1\necho x\n".to_string();
+        let path = Some("main.ab".to_string());
+        let compiler = AmberCompiler::new(code, path, CompilerOptions::default());
+        let (messages, _) = compiler.compile().unwrap();
+        let diagnostic = Diagnostic::from_message(&messages[0]);
+        assert_eq!(diagnostic.file.as_deref(), Some("main.ab"));
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 1));
+        assert_eq!((diagnostic.end_line, diagnostic.end_column), (2, 5));
+        assert_eq!(diagnostic.severity, "warning");
+    }
+
+    #[test]
+    fn test_diagnostic_without_position() {
+        let message = Message::new_err_msg("No such file").comment("Check the path");
+        let json = Diagnostic::from_message(&message).to_json();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "file": null,
+                "line": 0,
+                "column": 0,
+                "end_line": 0,
+                "end_column": 0,
+                "severity": "error",
+                "message": "No such file",
+                "comment": "Check the path"
+            })
+        );
+    }
+}
@@ -1,13 +1,25 @@
 use crate::modules::block::Block;
 use crate::modules::types::Type;
-use crate::utils::context::Context;
-use crate::utils::function_cache::{FunctionCache, FunctionInstance};
+use crate::utils::context::{Context, FunctionDecl};
+use crate::utils::function_cache::{EntryPoint, FunctionCache, FunctionInstance};
 use heraclitus_compiler::compiling::SyntaxModule;
 
 #[cfg(test)]
 mod function_cache_tests {
     use super::*;
 
+    fn declaration(id: usize) -> FunctionDecl {
+        FunctionDecl {
+            name: "test".to_string(),
+            args: vec![],
+            returns: Type::Null,
+            is_args_typed: false,
+            is_public: false,
+            is_failable: false,
+            id,
+        }
+    }
+
     #[test]
     fn test_function_cache_new() {
         let cache = FunctionCache::new();
@@ -20,10 +32,11 @@ mod function_cache_tests {
         let ctx = Context::new(None, vec![]);
         let block = Block::new();
 
-        cache.add_declaration(42, ctx, block);
+        cache.add_declaration(declaration(42), ctx, block);
 
         assert!(cache.get_instances_cloned(42).is_some());
         assert!(cache.get_instances_cloned(99).is_none());
+        assert_eq!(cache.get_declaration(42).unwrap().id, 42);
     }
 
     #[test]
@@ -32,7 +45,7 @@ mod function_cache_tests {
         let ctx = Context::new(None, vec![]);
         let block = Block::new();
 
-        cache.add_declaration(1, ctx, block);
+        cache.add_declaration(declaration(1), ctx, block);
 
         let instance = FunctionInstance {
             variant_id: 0,
@@ -40,6 +53,7 @@ mod function_cache_tests {
             args_global_ids: vec![],
             returns: Type::Null,
             block: Block::new(),
+            invokes: vec![],
         };
 
         let variant_idx = cache.add_instance(1, instance);
@@ -49,13 +63,46 @@ mod function_cache_tests {
         assert_eq!(instances.len(), 1);
     }
 
+    #[test]
+    fn test_function_cache_reachable_instances() {
+        let mut cache = FunctionCache::new();
+        for id in 0..4 {
+            cache.add_declaration(declaration(id), Context::new(None, vec![]), Block::new());
+        }
+        let instance = |invokes| FunctionInstance {
+            variant_id: 0,
+            args: vec![],
+            args_global_ids: vec![],
+            returns: Type::Null,
+            block: Block::new(),
+            invokes,
+        };
+        // Function 0 calls function 1, function 2 is only called by a test and function 3 is never called
+        cache.add_instance(0, instance(vec![(1, 0)]));
+        cache.add_instance(1, instance(vec![(0, 0)]));
+        cache.add_instance(2, instance(vec![]));
+        cache.add_instance(3, instance(vec![]));
+        cache.add_entry_point(EntryPoint::Main, vec![(0, 0)]);
+        cache.add_entry_point(EntryPoint::Test("test".to_string()), vec![(2, 0)]);
+
+        let reachable = cache.get_reachable_instances(&[EntryPoint::TopLevel, EntryPoint::Main]);
+        assert!(reachable.contains(&(0, 0)));
+        assert!(reachable.contains(&(1, 0)));
+        assert!(!reachable.contains(&(2, 0)));
+        assert!(!reachable.contains(&(3, 0)));
+
+        let reachable = cache.get_reachable_instances(&[EntryPoint::Test("test".to_string())]);
+        assert_eq!(reachable.len(), 1);
+        assert!(reachable.contains(&(2, 0)));
+    }
+
     #[test]
     fn test_function_cache_first_pass() {
         let mut cache = FunctionCache::new();
         let ctx = Context::new(None, vec![]);
         let block = Block::new();
 
-        cache.add_declaration(1, ctx, block);
+        cache.add_declaration(declaration(1), ctx, block);
 
         assert!(!cache.is_first_pass_done(1));
         cache.set_first_pass_done(1);
@@ -15,6 +15,7 @@ mod function_interface_tests {
                     name: "x".to_string(),
                     kind: Type::Int,
                     optional: None,
+                    optional_source: None,
                     is_ref: false,
                     tok: None,
                 }
@@ -42,6 +43,7 @@ mod function_interface_tests {
                     name: "x".to_string(),
                     kind: Type::Generic,
                     optional: None,
+                    optional_source: None,
                     is_ref: false,
                     tok: None,
                 }
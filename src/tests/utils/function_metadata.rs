@@ -24,6 +24,12 @@ mod function_metadata_tests {
         assert_eq!(meta.mangled_name(), "ret_my_function1_v0");
     }
 
+    #[test]
+    fn test_function_metadata_get_name() {
+        let meta = FunctionMetadata::new("my_function", 1, 0, &Type::Text);
+        assert_eq!(meta.get_name(), "my_function");
+    }
+
     #[test]
     fn test_function_metadata_get_type() {
         let meta = FunctionMetadata::new("func", 1, 0, &Type::Bool);
@@ -3,6 +3,7 @@
 //! Each test file corresponds to a source file in src/utils/:
 //! - cc_flags.rs → src/utils/cc_flags.rs
 //! - context.rs → src/utils/context.rs
+//! - diagnostic.rs → src/utils/diagnostic.rs
 //! - function_cache.rs → src/utils/function_cache.rs
 //! - function_interface.rs → src/utils/function_interface.rs
 //! - function_metadata.rs → src/utils/function_metadata.rs
@@ -18,6 +19,7 @@ pub use fixtures::*;
 
 mod cc_flags;
 mod context;
+mod diagnostic;
 mod ephemeral_vars;
 mod function_cache;
 mod function_interface;
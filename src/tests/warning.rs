@@ -1,6 +1,8 @@
 /// Tests for Amber scripts that check for warning messages.
 use super::script_test;
 use super::TestOutcomeTarget;
+use crate::compiler::{AmberCompiler, CompilerOptions};
+use itertools::Itertools;
 use test_generator::test_resources;
 
 /// Autoload the Amber test files in warning, match the output in the comment
@@ -8,3 +10,102 @@ use test_generator::test_resources;
 fn test_warning(input: &str) {
     script_test(input, TestOutcomeTarget::Success);
 }
+
+fn get_missing_docs_warnings(code: &str, warn_missing_docs: bool) -> Vec<String> {
+    let options = CompilerOptions::default().with_warn_missing_docs(warn_missing_docs);
+    let compiler = AmberCompiler::new(code.to_string(), None, options);
+    let (messages, _) = compiler.compile().unwrap();
+    messages
+        .into_iter()
+        .filter_map(|message| message.message)
+        .filter(|message| message.starts_with("Missing documentation"))
+        .collect()
+}
+
+#[test]
+fn test_missing_docs_warning() {
+    let code = r#"
+/// Documented function
+pub fun documented() {}
+
+pub fun undocumented() {}
+
+#[allow_missing_docs]
+pub fun allowed() {}
+
+fun private() {}
+
+/// Documented constant
+pub const DOCUMENTED = 1
+
+pub const UNDOCUMENTED = 2
+
+/// Separated by an empty line
+
+pub const SEPARATED = 3
+"#;
+    assert_eq!(
+        get_missing_docs_warnings(code, true),
+        [
+            "Missing documentation for public function 'undocumented'",
+            "Missing documentation for public constant 'UNDOCUMENTED'",
+            "Missing documentation for public constant 'SEPARATED'",
+        ]
+    );
+    assert!(get_missing_docs_warnings(code, false).is_empty());
+}
+
+#[test]
+fn test_missing_docs_warning_skips_stdlib() {
+    let code = r#"
+import * from "std/env"
+
+/// Prints the path
+pub fun print_path() {
+    echo(trust env_var_get("PATH"))
+}
+"#;
+    assert!(get_missing_docs_warnings(code, true).is_empty());
+}
+
+fn get_variable_warnings(code: &str) -> Vec<String> {
+    let compiler = AmberCompiler::new(code.to_string(), None, CompilerOptions::default());
+    let (messages, _) = compiler.compile().unwrap();
+    messages
+        .into_iter()
+        .filter_map(|message| message.message)
+        .filter(|message| message.contains("variable") || message.contains("Variable"))
+        .sorted()
+        .collect()
+}
+
+#[test]
+fn test_lambda_captures_resolved_variables() {
+    let code = r#"
+fun run() {
+    const count = 1
+    const shadow = fun() {
+        const count = 2
+        echo(count)
+    }
+    shadow()
+    const name = "x"
+    const quoted = fun() {
+        echo("name")
+    }
+    quoted()
+    const total = 3
+    let counter = 0
+    const interpolated = fun() {
+        echo("{total}")
+        counter += 1
+    }
+    interpolated()
+}
+run()
+"#;
+    assert_eq!(
+        get_variable_warnings(code),
+        ["Unused variable 'count'", "Unused variable 'name'"]
+    );
+}
@@ -0,0 +1,66 @@
+use crate::watch::{watched_files, Watcher};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+/// Moves the modification time of the file forward, as coarse file systems could miss quick edits
+fn touch_later(path: &PathBuf) {
+    let file = fs::File::options().write(true).open(path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(10))
+        .unwrap();
+}
+
+#[test]
+fn follows_transitive_imports() {
+    let dir = tempdir().unwrap();
+    let (main, first, second) = (
+        dir.path().join("main.ab"),
+        dir.path().join("first.ab"),
+        dir.path().join("second.ab"),
+    );
+    let import = |path: &PathBuf| format!("import * from \"{}\"\n", path.display());
+    fs::write(&main, import(&first) + "import { len } from \"std/text\"\n").unwrap();
+    fs::write(&first, import(&second)).unwrap();
+    fs::write(&second, "pub fun foo(): Int { return 1 }\n").unwrap();
+
+    let mut expected = vec![main.clone(), first, second];
+    expected.sort();
+    assert_eq!(watched_files(&main), expected);
+}
+
+#[test]
+fn watches_imports_of_code_with_errors() {
+    let dir = tempdir().unwrap();
+    let (main, missing) = (dir.path().join("main.ab"), dir.path().join("missing.ab"));
+    let code = format!(
+        "import * from \"{}\"\nlet x: Int = \"text\"\n",
+        missing.display()
+    );
+    fs::write(&main, code).unwrap();
+
+    let mut expected = vec![main.clone(), missing];
+    expected.sort();
+    assert_eq!(watched_files(&main), expected);
+}
+
+#[test]
+fn detects_changed_created_and_removed_files() {
+    let dir = tempdir().unwrap();
+    let (file, created) = (dir.path().join("file.ab"), dir.path().join("created.ab"));
+    fs::write(&file, "echo(1)\n").unwrap();
+    let mut watcher = Watcher::new();
+
+    watcher.watch(vec![file.clone(), created.clone()]);
+    assert!(!watcher.changed());
+    touch_later(&file);
+    assert!(watcher.changed());
+
+    watcher.watch(vec![file.clone(), created.clone()]);
+    fs::write(&created, "echo(2)\n").unwrap();
+    assert!(watcher.changed());
+
+    watcher.watch(vec![file.clone(), created.clone()]);
+    fs::remove_file(&file).unwrap();
+    assert!(watcher.changed());
+}
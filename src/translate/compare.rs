@@ -58,6 +58,76 @@ impl ComparisonOperator {
     pub fn to_frag(&self) -> FragmentKind {
         raw_fragment!(" {} ", self.to_string())
     }
+
+    // POSIX `test` cannot compare strings lexically, but `expr` can
+    fn to_expr_frag(&self) -> FragmentKind {
+        let operator = match self {
+            ComparisonOperator::Gt => "\\>",
+            ComparisonOperator::Ge => "\\>=",
+            ComparisonOperator::Lt => "\\<",
+            ComparisonOperator::Le => "\\<=",
+            ComparisonOperator::Eq => "=",
+        };
+        raw_fragment!(" {} ", operator)
+    }
+}
+
+/// Tests whether the given arithmetic expression is true
+fn translate_arith_test(meta: &TranslateMetadata, expr: FragmentKind) -> FragmentKind {
+    if meta.target.shell.is_posix() {
+        fragments!("[ $(( ", expr, " )) -ne 0 ]")
+    } else {
+        fragments!("(( ", expr, " ))")
+    }
+}
+
+/// Tests whether the given texts compare lexically with the operator
+fn translate_text_test(
+    meta: &TranslateMetadata,
+    left: FragmentKind,
+    operator: ComparisonOperator,
+    right: FragmentKind,
+) -> FragmentKind {
+    if meta.target.shell.is_posix() {
+        // The prefix prevents `expr` from comparing numbers or reading operators
+        fragments!(
+            "expr _",
+            left,
+            operator.to_expr_frag(),
+            "_",
+            right,
+            " >/dev/null"
+        )
+    } else {
+        fragments!("[[ ", left, operator.to_frag(), right, " ]]")
+    }
+}
+
+/// Returns the header and the increment of a loop over the indices `i` below the length
+fn translate_index_loop(
+    meta: &TranslateMetadata,
+    length: FragmentKind,
+) -> (FragmentKind, FragmentKind) {
+    if meta.target.shell.is_posix() {
+        (
+            fragments!("i=0; while [ $(( i < ", length, " )) -ne 0 ]; do"),
+            fragments!("i=$(( i + 1 ))"),
+        )
+    } else {
+        (
+            fragments!("for (( i=0; i<", length, "; i++ )); do"),
+            FragmentKind::Empty,
+        )
+    }
+}
+
+/// Tests whether the given boolean value is true
+pub fn translate_truthy(meta: &TranslateMetadata, value: FragmentKind) -> FragmentKind {
+    if meta.target.shell.is_posix() {
+        fragments!("[ ", value, " != 0 ]")
+    } else {
+        fragments!("(( ", value, " ))")
+    }
 }
 
 pub fn translate_lexical_comparison(
@@ -83,6 +153,10 @@ pub fn translate_lexical_comparison(
         meta.stmt_queue.push_back(right_stmt.to_frag());
         right_expr
     };
+    if meta.target.shell.is_posix() {
+        let expr = fragments!("expr _", left, operator.to_expr_frag(), "_", right);
+        return SubprocessFragment::new(expr).to_frag();
+    }
     let (primary_operator, secondary_operator) = operator.get_bash_lexical_operators();
     let expr = if let Some(secondary_operator) = secondary_operator {
         fragments!(
@@ -120,28 +194,44 @@ fn create_variable_length_getter(
 }
 
 fn create_variable_with_smaller_number(
+    meta: &TranslateMetadata,
     name: &str,
     left: VarExprFragment,
     right: VarExprFragment,
 ) -> (VarStmtFragment, VarExprFragment) {
-    let value = SubprocessFragment::new(fragments!(
-        " (( ",
-        left.clone().to_frag(),
-        " < ",
-        right.clone().to_frag(),
-        " )) ",
-        "&& echo ",
-        left.clone().to_frag(),
-        "|| echo ",
-        right.clone().to_frag()
-    ))
-    .to_frag();
+    let value = if meta.target.shell.is_posix() {
+        fragments!(
+            "$(( ",
+            left.clone().to_frag(),
+            " < ",
+            right.clone().to_frag(),
+            " ? ",
+            left.to_frag(),
+            " : ",
+            right.to_frag(),
+            " ))"
+        )
+    } else {
+        SubprocessFragment::new(fragments!(
+            " (( ",
+            left.clone().to_frag(),
+            " < ",
+            right.clone().to_frag(),
+            " )) ",
+            "&& echo ",
+            left.clone().to_frag(),
+            "|| echo ",
+            right.clone().to_frag()
+        ))
+        .to_frag()
+    };
     let len_stmt = VarStmtFragment::new(name, Type::Num, value);
     let len_expr = VarExprFragment::from_stmt(&len_stmt).with_render_type(VarRenderType::NameOf);
     (len_stmt, len_expr)
 }
 
 fn compare_array_lengths(
+    meta: &TranslateMetadata,
     left_len: VarExprFragment,
     right_len: VarExprFragment,
     operator: ComparisonOperator,
@@ -153,17 +243,18 @@ fn compare_array_lengths(
         right_len.clone().to_frag()
     );
     let full_comparison_fragment = if let Some(eq) = eq {
-        fragments!(
-            "(( ",
-            left_len.clone().to_frag(),
-            eq.to_frag(),
-            right_len.clone().to_frag(),
-            " || ",
-            comparison_fragment,
-            " ))"
+        translate_arith_test(
+            meta,
+            fragments!(
+                left_len.clone().to_frag(),
+                eq.to_frag(),
+                right_len.clone().to_frag(),
+                " || ",
+                comparison_fragment
+            ),
         )
     } else {
-        fragments!("(( ", comparison_fragment, " ))")
+        translate_arith_test(meta, comparison_fragment)
     };
     fragments!(full_comparison_fragment, " && echo 1 || echo 0").to_frag()
 }
@@ -193,6 +284,7 @@ pub fn translate_array_lexical_comparison(
     let right_expr_length = create_variable_length_getter(meta, "right_comp", right);
     // Compare lengths of arrays and choose the longest one
     let (len_stmt, len_expr) = create_variable_with_smaller_number(
+        meta,
         "len_comp",
         left_expr_length.clone(),
         right_expr_length.clone(),
@@ -210,58 +302,58 @@ pub fn translate_array_lexical_comparison(
     let pretty_op = operator.to_string();
     // Get the return value when intersection of both left and right values are equal
     let compared_array_lengths =
-        compare_array_lengths(left_expr_length, right_expr_length, operator);
+        compare_array_lengths(meta, left_expr_length, right_expr_length, operator);
+    // Arithmetic expansion in POSIX sh does not remove quotes from its operands
+    let quoted = !meta.target.shell.is_posix();
     let if_cond = match kind {
-        Type::Num => fragments!(
-            "(( ",
-            translate_float_computation(
+        Type::Num => {
+            let computation = translate_float_computation(
                 meta,
                 op.to_arith_op(),
                 Some(left_helper_expr.clone().to_frag()),
-                Some(right_helper_expr.clone().to_frag())
+                Some(right_helper_expr.clone().to_frag()),
+            );
+            translate_arith_test(meta, fragments!(computation.with_quotes(quoted), " != 0"))
+        }
+        Type::Int => translate_arith_test(
+            meta,
+            fragments!(
+                left_helper_expr.clone().with_quotes(quoted).to_frag(),
+                op.to_frag(),
+                right_helper_expr.clone().with_quotes(quoted).to_frag()
             ),
-            " != 0 ))"
         ),
-        Type::Int => fragments!(
-            "(( ",
+        Type::Text => translate_text_test(
+            meta,
             left_helper_expr.clone().to_frag(),
-            op.to_frag(),
+            op,
             right_helper_expr.clone().to_frag(),
-            " ))"
-        ),
-        Type::Text => fragments!(
-            "[[ ",
-            left_helper_expr.clone().to_frag(),
-            op.to_frag(),
-            right_helper_expr.clone().to_frag(),
-            " ]]"
         ),
         _ => unreachable!("Unsupported type {kind} in array lexical comparison"),
     };
     let elif_cond = match kind {
-        Type::Num => fragments!(
-            "(( ",
-            translate_float_computation(
+        Type::Num => {
+            let computation = translate_float_computation(
                 meta,
                 inv_op.to_arith_op(),
                 Some(left_helper_expr.to_frag()),
-                Some(right_helper_expr.to_frag())
+                Some(right_helper_expr.to_frag()),
+            );
+            translate_arith_test(meta, fragments!(computation.with_quotes(quoted), " != 0"))
+        }
+        Type::Int => translate_arith_test(
+            meta,
+            fragments!(
+                left_helper_expr.with_quotes(quoted).to_frag(),
+                inv_op.to_frag(),
+                right_helper_expr.with_quotes(quoted).to_frag()
             ),
-            " != 0 ))"
         ),
-        Type::Int => fragments!(
-            "(( ",
+        Type::Text => translate_text_test(
+            meta,
             left_helper_expr.to_frag(),
-            inv_op.to_frag(),
+            inv_op,
             right_helper_expr.to_frag(),
-            " ))"
-        ),
-        Type::Text => fragments!(
-            "[[ ",
-            left_helper_expr.to_frag(),
-            inv_op.to_frag(),
-            right_helper_expr.to_frag(),
-            " ]]"
         ),
         _ => unreachable!("Unsupported type {kind} in array lexical comparison"),
     };
@@ -277,17 +369,19 @@ pub fn translate_array_lexical_comparison(
         false,
     );
     // Create a for loop to iterate over the elements of the longest array
+    let (loop_header, loop_increment) = translate_index_loop(meta, len_expr.to_frag());
     let block = BlockFragment::new(
         vec![
             CommentFragment::new(&format!("Compare if left array {pretty_op} right array"))
                 .to_frag(),
             len_stmt.to_frag(),
-            fragments!("for (( i=0; i<", len_expr.to_frag(), "; i++ )); do"),
+            loop_header,
             BlockFragment::new(
                 vec![
                     left_helper_stmt.to_frag(),
                     right_helper_stmt.to_frag(),
                     if_stmt.to_frag(),
+                    loop_increment,
                 ],
                 true,
             )
@@ -306,6 +400,7 @@ pub fn translate_array_lexical_comparison(
 }
 
 pub fn translate_array_equality(
+    meta: &TranslateMetadata,
     left: VarExprFragment,
     right: VarExprFragment,
     negative: bool,
@@ -320,27 +415,32 @@ pub fn translate_array_equality(
         .to_frag();
     let false_val = raw_fragment!("{}", if negative { "1" } else { "0" });
     let true_val = raw_fragment!("{}", if negative { "0" } else { "1" });
+    let (loop_header, loop_increment) = match translate_index_loop(meta, left_len.clone()) {
+        (header, FragmentKind::Empty) => (header, FragmentKind::Empty),
+        (header, increment) => (header, fragments!(increment, "; ")),
+    };
+    let items_differ = if meta.target.shell.is_posix() {
+        fragments!("[ \"_", left_index, "\" != \"_", right_index, "\" ]")
+    } else {
+        fragments!("[[ \"", left_index, "\" != \"", right_index, "\" ]]")
+    };
     let block = BlockFragment::new(
         vec![
             fragments!(
-                "(( ",
-                left_len.clone(),
-                " != ",
-                right_len,
-                " )) && echo ",
+                translate_arith_test(meta, fragments!(left_len.clone(), " != ", right_len)),
+                " && echo ",
                 false_val.clone(),
                 " && exit"
             ),
             fragments!(
-                "for (( i=0; i<",
-                left_len.clone(),
-                "; i++ )); do [[ \"",
-                left_index,
-                "\" != \"",
-                right_index,
-                "\" ]] && echo ",
+                loop_header,
+                " ",
+                items_differ,
+                " && echo ",
                 false_val,
-                " && exit; done"
+                " && exit; ",
+                loop_increment,
+                "done"
             ),
             fragments!("echo ", true_val, "\n"),
         ],
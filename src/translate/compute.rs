@@ -3,6 +3,8 @@ use crate::fragments;
 use crate::modules::prelude::*;
 use crate::utils::ShellType;
 
+/// Backend used for the computations on `Num` values. Computations on `Int`
+/// values do not need any backend as they are done natively with `$(( ))`.
 pub enum ArithType {
     Awk,
 }
@@ -26,13 +28,40 @@ pub enum ArithOp {
     Or,
 }
 
-pub fn translate_awk_computation(
-    op: ArithOp,
-    left: FragmentKind,
-    right: FragmentKind,
-    with_quotes: bool,
-) -> FragmentKind {
-    let op_str = match op {
+// Programs passed to awk. They are shared with `match_awk_computation`
+// so that the optimizer can recognize the computations it is able to fold.
+const AWK_ARITHMETIC: (&str, &str) = ("awk 'BEGIN { print ARGV[1]", "ARGV[2] }'");
+const AWK_COMPARISON: (&str, &str) = ("awk 'BEGIN {print (ARGV[1]", "ARGV[2]) ? 1 : 0}'");
+const AWK_NEG: &str = "awk 'BEGIN { print -ARGV[1]; }'";
+const AWK_NOT: &str = "awk 'BEGIN {print (!ARGV[1]) ? 1 : 0}'";
+
+const AWK_BINARY_OPS: [ArithOp; 13] = [
+    ArithOp::Add,
+    ArithOp::Sub,
+    ArithOp::Mul,
+    ArithOp::Div,
+    ArithOp::Modulo,
+    ArithOp::Gt,
+    ArithOp::Ge,
+    ArithOp::Lt,
+    ArithOp::Le,
+    ArithOp::Eq,
+    ArithOp::Neq,
+    ArithOp::And,
+    ArithOp::Or,
+];
+
+impl ArithOp {
+    fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            ArithOp::Gt | ArithOp::Ge | ArithOp::Lt | ArithOp::Le | ArithOp::Eq | ArithOp::Neq
+        )
+    }
+}
+
+fn awk_operator(op: &ArithOp) -> &'static str {
+    match op {
         ArithOp::Add => "+",
         ArithOp::Sub => "-",
         ArithOp::Mul => "*",
@@ -48,36 +77,29 @@ pub fn translate_awk_computation(
         ArithOp::Not => "!",
         ArithOp::And => "&&",
         ArithOp::Or => "||",
-    };
-    let operator = RawFragment::from(op_str.to_string()).to_frag();
+    }
+}
 
+pub fn translate_awk_computation(
+    op: ArithOp,
+    left: FragmentKind,
+    right: FragmentKind,
+    with_quotes: bool,
+) -> FragmentKind {
+    let operator = RawFragment::new(awk_operator(&op)).to_frag();
     let value = match op {
-        ArithOp::Gt | ArithOp::Ge | ArithOp::Lt | ArithOp::Le | ArithOp::Eq | ArithOp::Neq => {
-            fragments!(
-                "awk \'BEGIN {print (ARGV[1]", operator, "ARGV[2]) ? 1 : 0}\'",
-                " ",
-                left,
-                " ",
-                right
-            )
-        },
-        ArithOp::Neg => {
-            fragments!(
-                "awk \'BEGIN { print -ARGV[1]; }\'",
-                " ",
-                left
-            )
-        },
-        ArithOp::Not => {
-            fragments!(
-                "awk \'BEGIN {print (!ARGV[1]) ? 1 : 0}\'",
-                " ",
-                left
-            )
-        },
+        ArithOp::Neg => fragments!(RawFragment::new(AWK_NEG).to_frag(), " ", left),
+        ArithOp::Not => fragments!(RawFragment::new(AWK_NOT).to_frag(), " ", left),
         _ => {
+            let (begin, end) = if op.is_comparison() {
+                AWK_COMPARISON
+            } else {
+                AWK_ARITHMETIC
+            };
             fragments!(
-                "awk \'BEGIN { print ARGV[1]", operator, "ARGV[2] }\'",
+                RawFragment::new(begin).to_frag(),
+                operator,
+                RawFragment::new(end).to_frag(),
                 " ",
                 left,
                 " ",
@@ -90,6 +112,39 @@ pub fn translate_awk_computation(
         .to_frag()
 }
 
+/// Recovers the operation and its operands from a subprocess created by
+/// `translate_awk_computation`. Unary operations have no right operand.
+pub fn match_awk_computation(
+    subprocess: &SubprocessFragment,
+) -> Option<(ArithOp, &FragmentKind, Option<&FragmentKind>)> {
+    let FragmentKind::List(list) = subprocess.fragment.as_ref() else {
+        return None;
+    };
+    let raw = |index: usize| match list.values.get(index) {
+        Some(FragmentKind::Raw(raw)) => Some(raw.value.as_str()),
+        _ => None,
+    };
+    match list.values.as_slice() {
+        [_, _, left] if raw(1) == Some(" ") => match raw(0)? {
+            AWK_NEG => Some((ArithOp::Neg, left, None)),
+            AWK_NOT => Some((ArithOp::Not, left, None)),
+            _ => None,
+        },
+        [_, _, _, _, left, _, right] if raw(3) == Some(" ") && raw(5) == Some(" ") => {
+            let op = AWK_BINARY_OPS
+                .into_iter()
+                .find(|op| Some(awk_operator(op)) == raw(1))?;
+            let (begin, end) = if op.is_comparison() {
+                AWK_COMPARISON
+            } else {
+                AWK_ARITHMETIC
+            };
+            (raw(0) == Some(begin) && raw(2) == Some(end)).then_some((op, left, Some(right)))
+        }
+        _ => None,
+    }
+}
+
 pub fn translate_float_computation(
     meta: &TranslateMetadata,
     operator: ArithOp,
@@ -103,9 +158,10 @@ pub fn translate_float_computation(
                 right.unwrap_or(FragmentKind::Empty),
             );
             match meta.target.shell {
-                ShellType::BashModern | ShellType::BashLegacy | ShellType::Zsh => {
-                    translate_awk_computation(operator, left, right, true)
-                }
+                ShellType::BashModern
+                | ShellType::BashLegacy
+                | ShellType::Zsh
+                | ShellType::Posix => translate_awk_computation(operator, left, right, true),
                 // ksh doesn't support quoting inside arithmetic blocks
                 ShellType::Ksh => translate_awk_computation(operator, left, right, false),
             }
@@ -59,6 +59,12 @@ impl FragmentRenderable for BlockFragment {
                         result.push(rendered);
                     }
                 }
+                FragmentKind::Conditional(cond) => {
+                    result.push(cond.to_string(meta));
+                }
+                FragmentKind::Function(function) => {
+                    result.push(function.to_string(meta));
+                }
                 _ => {
                     let statement = statement.to_string(meta);
                     for stmt in mem::take(&mut meta.stmt_queue) {
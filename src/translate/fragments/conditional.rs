@@ -0,0 +1,116 @@
+use super::block::BlockFragment;
+use super::fragment::{FragmentKind, FragmentRenderable};
+use super::list::ListFragment;
+use super::raw::RawFragment;
+use crate::utils::TranslateMetadata;
+
+/// A single `if` or `elif` branch of a conditional statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConditionalBranch {
+    pub comments: Vec<FragmentKind>,
+    pub condition: FragmentKind,
+    pub block: FragmentKind,
+}
+
+/// Renders an `if` / `elif` / `else` chain in Bash code. Conditions are kept
+/// apart from the rendered text so that the optimizer can drop dead branches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConditionalFragment {
+    pub branches: Vec<ConditionalBranch>,
+    pub otherwise: Option<(Vec<FragmentKind>, Box<FragmentKind>)>,
+}
+
+impl ConditionalFragment {
+    pub fn new() -> Self {
+        ConditionalFragment {
+            branches: vec![],
+            otherwise: None,
+        }
+    }
+
+    pub fn add_branch(
+        &mut self,
+        comments: Vec<FragmentKind>,
+        condition: FragmentKind,
+        block: FragmentKind,
+    ) {
+        self.branches.push(ConditionalBranch {
+            comments,
+            condition,
+            block,
+        });
+    }
+
+    pub fn set_otherwise(&mut self, comments: Vec<FragmentKind>, block: FragmentKind) {
+        self.otherwise = Some((comments, Box::new(block)));
+    }
+
+    /// Iterates over all fragments in the order in which they are rendered
+    pub fn fragments(&self) -> impl Iterator<Item = &FragmentKind> {
+        let branches = self.branches.iter().flat_map(|branch| {
+            branch
+                .comments
+                .iter()
+                .chain([&branch.condition, &branch.block])
+        });
+        let otherwise = self
+            .otherwise
+            .iter()
+            .flat_map(|(comments, block)| comments.iter().chain([block.as_ref()]));
+        branches.chain(otherwise)
+    }
+
+    pub fn fragments_mut(&mut self) -> impl Iterator<Item = &mut FragmentKind> {
+        let branches = self.branches.iter_mut().flat_map(|branch| {
+            branch
+                .comments
+                .iter_mut()
+                .chain([&mut branch.condition, &mut branch.block])
+        });
+        let otherwise = self
+            .otherwise
+            .iter_mut()
+            .flat_map(|(comments, block)| comments.iter_mut().chain([block.as_mut()]));
+        branches.chain(otherwise)
+    }
+
+    pub fn into_block(self) -> BlockFragment {
+        let mut result = vec![];
+        for (index, branch) in self.branches.into_iter().enumerate() {
+            result.extend(branch.comments);
+            let keyword = if index == 0 { "if" } else { "elif" };
+            result.push(
+                ListFragment::new(vec![
+                    RawFragment::from(format!("{keyword} [ ")).to_frag(),
+                    branch.condition,
+                    RawFragment::new(" != 0 ]; then").to_frag(),
+                ])
+                .to_frag(),
+            );
+            result.push(branch.block);
+        }
+        if let Some((comments, block)) = self.otherwise {
+            result.extend(comments);
+            result.push(RawFragment::new("else").to_frag());
+            result.push(*block);
+        }
+        result.push(RawFragment::new("fi").to_frag());
+        BlockFragment::new(result, false)
+    }
+}
+
+impl Default for ConditionalFragment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FragmentRenderable for ConditionalFragment {
+    fn to_string(self, meta: &mut TranslateMetadata) -> String {
+        self.into_block().to_string(meta)
+    }
+
+    fn to_frag(self) -> FragmentKind {
+        FragmentKind::Conditional(self)
+    }
+}
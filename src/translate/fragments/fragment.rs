@@ -1,7 +1,8 @@
 use super::{
-    block::BlockFragment, comment::CommentFragment, interpolable::InterpolableFragment,
-    list::ListFragment, log::LogFragment, raw::RawFragment, subprocess::SubprocessFragment,
-    var_expr::VarExprFragment, var_stmt::VarStmtFragment,
+    block::BlockFragment, comment::CommentFragment, conditional::ConditionalFragment,
+    function::FunctionFragment, interpolable::InterpolableFragment, list::ListFragment,
+    log::LogFragment, raw::RawFragment, subprocess::SubprocessFragment, var_expr::VarExprFragment,
+    var_stmt::VarStmtFragment,
 };
 use crate::{translate::fragments::arithmetic::ArithmeticFragment, utils::TranslateMetadata};
 
@@ -16,6 +17,8 @@ pub enum FragmentKind {
     VarExpr(VarExprFragment),
     VarStmt(VarStmtFragment),
     Block(BlockFragment),
+    Conditional(ConditionalFragment),
+    Function(FunctionFragment),
     Interpolable(InterpolableFragment),
     List(ListFragment),
     Subprocess(SubprocessFragment),
@@ -54,6 +57,8 @@ impl FragmentKind {
         match self {
             FragmentKind::VarStmt(var_stmt) => var_stmt.value.is_mutating(),
             FragmentKind::Block(block) => block.statements.iter().any(|stmt| stmt.is_mutating()),
+            FragmentKind::Conditional(cond) => cond.fragments().any(|frag| frag.is_mutating()),
+            FragmentKind::Function(function) => function.fragments().any(|frag| frag.is_mutating()),
             FragmentKind::Interpolable(interpolable) => {
                 interpolable.parts.iter().any(|item| item.is_mutating())
             }
@@ -83,6 +88,12 @@ impl FragmentKind {
                 .statements
                 .iter()
                 .any(|stmt| stmt.is_running_command()),
+            FragmentKind::Conditional(cond) => {
+                cond.fragments().any(|frag| frag.is_running_command())
+            }
+            FragmentKind::Function(function) => {
+                function.fragments().any(|frag| frag.is_running_command())
+            }
             FragmentKind::Interpolable(interpolable) => interpolable
                 .parts
                 .iter()
@@ -114,6 +125,8 @@ impl FragmentRenderable for FragmentKind {
             FragmentKind::VarExpr(var) => var.to_string(meta),
             FragmentKind::VarStmt(var) => var.to_string(meta),
             FragmentKind::Block(block) => block.to_string(meta),
+            FragmentKind::Conditional(cond) => cond.to_string(meta),
+            FragmentKind::Function(function) => function.to_string(meta),
             FragmentKind::Interpolable(interpolable) => interpolable.to_string(meta),
             FragmentKind::List(list) => list.to_string(meta),
             FragmentKind::Subprocess(subprocess) => subprocess.to_string(meta),
@@ -0,0 +1,149 @@
+use std::mem;
+
+use itertools::Itertools;
+
+use super::block::BlockFragment;
+use super::fragment::{FragmentKind, FragmentRenderable};
+use super::raw::RawFragment;
+use super::var_stmt::VarStmtFragment;
+use crate::utils::function_cache::InstanceId;
+use crate::utils::{ShellType, TranslateMetadata};
+
+/// Parameters and the return variable of a function whose body can be
+/// substituted at the places where the function is called.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineSignature {
+    /// Statements that bind the parameters, without values
+    pub params: Vec<VarStmtFragment>,
+    /// Name of the variable that holds the returned value
+    pub return_name: String,
+}
+
+/// Renders a definition of a single function variant in Bash code. The body is
+/// kept apart from the header so that the optimizer can inline the function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionFragment {
+    pub name: String,
+    /// Function instance that is defined, which calls of it refer to
+    pub instance: InstanceId,
+    pub header: Vec<FragmentKind>,
+    pub args: Box<FragmentKind>,
+    pub body: Vec<FragmentKind>,
+    pub inline: Option<InlineSignature>,
+}
+
+impl FunctionFragment {
+    pub fn new(
+        name: &str,
+        instance: InstanceId,
+        header: Vec<FragmentKind>,
+        body: Vec<FragmentKind>,
+    ) -> Self {
+        FunctionFragment {
+            name: name.to_string(),
+            instance,
+            header,
+            args: Box::new(FragmentKind::Empty),
+            body,
+            inline: None,
+        }
+    }
+
+    pub fn with_args(mut self, args: Option<FragmentKind>) -> Self {
+        self.args = Box::new(args.unwrap_or_default());
+        self
+    }
+
+    pub fn with_inline(mut self, inline: Option<InlineSignature>) -> Self {
+        self.inline = inline;
+        self
+    }
+
+    /// Iterates over the statements of the function
+    pub fn fragments(&self) -> impl Iterator<Item = &FragmentKind> {
+        [self.args.as_ref()].into_iter().chain(self.body.iter())
+    }
+
+    pub fn fragments_mut(&mut self) -> impl Iterator<Item = &mut FragmentKind> {
+        [self.args.as_mut()].into_iter().chain(self.body.iter_mut())
+    }
+
+    /// Opening line of the definition of a function with the given name
+    fn render_opening(name: &str, meta: &TranslateMetadata) -> String {
+        // required for the local scope in ksh
+        if matches!(meta.target.shell, ShellType::Ksh) {
+            format!("function {name} {{")
+        } else {
+            format!("{name}() {{")
+        }
+    }
+
+    pub fn into_block(self, meta: &TranslateMetadata) -> BlockFragment {
+        let opening = Self::render_opening(&self.name, meta);
+        let mut result = self.header;
+        result.push(RawFragment::new(&opening).to_frag());
+        result.push(*self.args);
+        result.extend(self.body);
+        result.push(RawFragment::new("}\n").to_frag());
+        BlockFragment::new(result, false)
+    }
+
+    /// POSIX sh has no `local`, so a function with local variables runs its body in
+    /// a separate function. The wrapper keeps the values the variables had in its own
+    /// positional parameters and restores them when the body returns, so that
+    /// recursive calls do not overwrite the variables of their callers.
+    fn render_posix(self, meta: &mut TranslateMetadata) -> String {
+        let prev_locals = meta.posix_locals.replace(vec![]);
+        let header = BlockFragment::new(self.header, false).to_string(meta);
+        let mut statements = vec![*self.args];
+        statements.extend(self.body);
+        let body = BlockFragment::new(statements, false).to_string(meta);
+        let locals = mem::replace(&mut meta.posix_locals, prev_locals).unwrap_or_default();
+
+        let indent = meta.gen_indent();
+        let inner_indent = indent.clone() + &TranslateMetadata::single_indent();
+        let scoped_name = if locals.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}__scoped", self.name)
+        };
+        let mut result = vec![header];
+        result.push(indent.clone() + &Self::render_opening(&scoped_name, meta));
+        if !locals.is_empty() {
+            // Drop the saved values passed by the wrapper
+            result.push(format!("{inner_indent}shift {}", locals.len()));
+        }
+        result.push(body);
+        result.push(format!("{indent}}}\n"));
+        if !locals.is_empty() {
+            let saved = locals
+                .iter()
+                .map(|name| format!("\"${{{name}-}}\""))
+                .join(" ");
+            result.push(indent.clone() + &Self::render_opening(&self.name, meta));
+            result.push(format!("{inner_indent}set -- {saved} \"$@\""));
+            result.push(format!("{inner_indent}{scoped_name} \"$@\""));
+            result.push(format!("{inner_indent}__status=$?"));
+            for (index, name) in locals.iter().enumerate() {
+                result.push(format!("{inner_indent}{name}=\"${}\"", index + 1));
+            }
+            result.push(format!("{inner_indent}return \"$__status\""));
+            result.push(format!("{indent}}}\n"));
+        }
+        result.retain(|line| !line.is_empty());
+        result.join("\n")
+    }
+}
+
+impl FragmentRenderable for FunctionFragment {
+    fn to_string(self, meta: &mut TranslateMetadata) -> String {
+        if meta.target.shell.is_posix() {
+            return self.render_posix(meta);
+        }
+        self.into_block(meta).to_string(meta)
+    }
+
+    fn to_frag(self) -> FragmentKind {
+        FragmentKind::Function(self)
+    }
+}
@@ -27,6 +27,11 @@ impl ListFragment {
         self
     }
 
+    /// Whether the values are rendered right next to each other
+    pub fn is_concatenation(&self) -> bool {
+        self.separator == ListFragmentSeparator::Empty
+    }
+
     pub fn is_empty_logic(&self) -> bool {
         self.values.iter().all(|fragment| fragment.is_empty_logic())
     }
@@ -56,7 +56,8 @@ impl LogFragment {
 
 impl FragmentRenderable for LogFragment {
     fn to_string(self, meta: &mut TranslateMetadata) -> String {
-        if self.should_use_printf(&self.value) {
+        // Echo in POSIX sh interprets backslash escapes, so only printf prints text verbatim
+        if meta.target.shell.is_posix() || self.should_use_printf(&self.value) {
             format!("printf '%s\\n' {}", self.value.to_string(meta))
         } else {
             format!("echo {}", self.value.to_string(meta))
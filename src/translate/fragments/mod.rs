@@ -1,7 +1,9 @@
 pub mod arithmetic;
 pub mod block;
 pub mod comment;
+pub mod conditional;
 pub mod fragment;
+pub mod function;
 pub mod interpolable;
 pub mod list;
 pub mod log;
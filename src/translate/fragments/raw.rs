@@ -1,4 +1,5 @@
 use super::fragment::{FragmentKind, FragmentRenderable};
+use crate::utils::function_cache::InstanceId;
 use crate::utils::TranslateMetadata;
 
 /// This module represents a raw code fragment in Bash.
@@ -7,11 +8,16 @@ use crate::utils::TranslateMetadata;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RawFragment {
     pub value: String,
+    /// Function instance that is called by the name in the value
+    pub function: Option<InstanceId>,
 }
 
 impl From<String> for RawFragment {
     fn from(value: String) -> Self {
-        RawFragment { value }
+        RawFragment {
+            value,
+            function: None,
+        }
     }
 }
 
@@ -19,8 +25,14 @@ impl RawFragment {
     pub fn new(value: &str) -> Self {
         RawFragment {
             value: value.to_string(),
+            function: None,
         }
     }
+
+    pub fn with_function(mut self, function: InstanceId) -> Self {
+        self.function = Some(function);
+        self
+    }
 }
 
 impl FragmentRenderable for RawFragment {
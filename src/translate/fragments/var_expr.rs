@@ -33,6 +33,8 @@ pub enum VarRenderType {
 pub enum VarIndexValue {
     Index(FragmentKind),
     Range(FragmentKind, FragmentKind),
+    // Key of a map
+    Key(FragmentKind),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -46,6 +48,8 @@ pub struct VarExprFragment {
     pub is_ref: bool,
     // Bash's length getter `${#var}`
     pub is_length: bool,
+    // Bash's keys getter `${!var[@]}` of an associative array
+    pub is_keys: bool,
     // Quotes around this expression
     pub is_quoted: bool,
     // Bash's `${array[*]}` expansion
@@ -71,6 +75,7 @@ impl Default for VarExprFragment {
             kind: Type::Generic,
             is_ref: false,
             is_length: false,
+            is_keys: false,
             is_array_to_string: false,
             is_math_var: false,
             is_quoted: true,
@@ -87,7 +92,7 @@ impl VarExprFragment {
     pub fn new(name: &str, kind: Type) -> Self {
         VarExprFragment {
             name: name.to_string(),
-            kind,
+            kind: kind.into_shell_type(),
             ..Default::default()
         }
     }
@@ -140,6 +145,7 @@ impl VarExprFragment {
         if let Some(index) = index.into() {
             self.index_pos = format_position(index.position.as_ref());
             let index = match index.value {
+                _ if self.kind.is_map() => VarIndexValue::Key(index.translate(meta)),
                 Some(ExprType::Range(range)) => {
                     let (offset, length) = range.get_array_index(meta);
                     VarIndexValue::Range(offset, length)
@@ -173,6 +179,11 @@ impl VarExprFragment {
         self
     }
 
+    pub fn with_keys_getter(mut self, value: bool) -> Self {
+        self.is_keys = value;
+        self
+    }
+
     pub fn with_render_type(mut self, render_type: VarRenderType) -> Self {
         self.render_type = render_type;
         self
@@ -191,6 +202,7 @@ impl VarExprFragment {
         match self.index.as_deref() {
             Some(VarIndexValue::Index(_)) => "index".to_string(),
             Some(VarIndexValue::Range(_, _)) => "range".to_string(),
+            Some(VarIndexValue::Key(_)) => "key".to_string(),
             None => "access".to_string(),
         }
     }
@@ -203,7 +215,7 @@ impl VarExprFragment {
         if self.is_ref {
             name = match meta.target.shell {
                 ShellType::Ksh | ShellType::BashModern => format!("{dollar}{{!{name}}}"),
-                ShellType::BashLegacy => format!("{dollar}{{{name}}}"),
+                ShellType::BashLegacy | ShellType::Posix => format!("{dollar}{{{name}}}"),
                 ShellType::Zsh => {
                     if self.is_array_ref {
                         format!("{dollar}{{{name}}}")
@@ -224,10 +236,13 @@ impl VarExprFragment {
 
     // Returns the variable value in the bash/zsh/ksh context Ex. "$varname" or "${varname[@]}"
     pub fn render_variable_value(mut self, meta: &mut TranslateMetadata) -> String {
+        if meta.target.shell.is_posix() {
+            return self.render_posix_value(meta);
+        }
         let name = self.get_name();
         let index = self.index.take();
         let index_is_none = index.is_none();
-        let prefix = self.get_variable_prefix();
+        let prefix = self.get_variable_prefix(meta);
         let suffix = self.get_variable_suffix(meta, index.clone());
         let quote = if self.is_quoted { meta.gen_quote() } else { "" };
         let dollar = meta.gen_dollar();
@@ -278,13 +293,79 @@ impl VarExprFragment {
                     format!("{quote}{dollar}{{{prefix}{name}{suffix}}}{quote}")
                 }
             }
+            ShellType::Posix => unreachable!("POSIX values are rendered separately"),
+        }
+    }
+
+    // Returns the variable value in the POSIX sh context where arrays are strings
+    // of elements that are each terminated by the `$__US` unit separator
+    fn render_posix_value(mut self, meta: &mut TranslateMetadata) -> String {
+        let quote = if self.is_quoted { meta.gen_quote() } else { "" };
+        let inner_quote = meta.gen_quote();
+        let dollar = meta.gen_dollar();
+        let mut name = self.get_name();
+        // POSIX shells have no indirect expansion, so references are read with `eval`
+        if self.is_ref {
+            let id = meta.gen_value_id();
+            let var_name = format!("{name}_deref_{id}");
+            meta.stmt_queue.push_back(
+                RawFragment::from(format!("eval \"{var_name}=\\\"\\${{${{{name}}}}}\\\"\""))
+                    .to_frag(),
+            );
+            name = var_name;
+        }
+        let value = format!("{inner_quote}{dollar}{{{name}}}{inner_quote}");
+        // Lengths are left unquoted, because `dash` does not remove quotes in arithmetic expansions
+        if !self.kind.is_array() && self.index.is_none() {
+            return if self.is_length {
+                format!("{dollar}{{#{name}}}")
+            } else if self.is_math_var {
+                name
+            } else {
+                format!("{quote}{dollar}{{{name}}}{quote}")
+            };
+        }
+        if self.is_length {
+            return format!("{dollar}(__amber_len {value})");
+        }
+        let array = match self.index.take().map(|index| *index) {
+            Some(VarIndexValue::Index(index)) => {
+                let index = index.with_quotes(false).to_string(meta);
+                // Indexes generated by the compiler have no position and are always in bounds
+                if let Some(location) = self.index_pos.as_deref() {
+                    meta.stmt_queue.push_back(
+                        RawFragment::from(format!(
+                            "[ $(( {index} >= -$(__amber_len {value}) && {index} < $(__amber_len {value}) )) -eq 1 ] || {{ echo \"Index out of bounds (at {location})\" >&2; exit 1; }}"
+                        ))
+                        .to_frag(),
+                    );
+                }
+                return format!("{quote}{dollar}(__amber_at {value} {index}){quote}");
+            }
+            Some(VarIndexValue::Range(offset, length)) => {
+                let offset = offset.with_quotes(false).to_string(meta);
+                let length = length.with_quotes(false).to_string(meta);
+                format!("{dollar}(__amber_slice {value} {offset} {length})")
+            }
+            Some(VarIndexValue::Key(_)) => unreachable!("Maps are not supported in POSIX sh"),
+            None => format!("{dollar}{{{name}}}"),
+        };
+        if self.is_array_to_string {
+            format!("{quote}{dollar}(__amber_join {inner_quote}{array}{inner_quote}){quote}")
+        } else {
+            format!("{quote}{array}{quote}")
         }
     }
 
     // Get variable prefix ${PREFIX-varname-suffix}
-    fn get_variable_prefix(&self) -> &'static str {
+    fn get_variable_prefix(&self, meta: &TranslateMetadata) -> &'static str {
         if self.is_length {
             "#"
+        } else if self.is_keys {
+            match meta.target.shell {
+                ShellType::Zsh => "(k)",
+                _ => "!",
+            }
         } else {
             ""
         }
@@ -327,8 +408,27 @@ impl VarExprFragment {
                     _ => format!("[{index}]?\"Index out of bounds (at {location})\""),
                 }
             }
-            (Type::Array(_), None) if self.is_array_to_string => String::from("[*]"),
-            (Type::Array(_), None) => String::from("[@]"),
+            (_, Some(VarIndexValue::Key(key))) => {
+                let key = key.to_string(meta);
+                let location = self.index_pos.as_deref().unwrap_or("unknown");
+
+                match meta.target.shell {
+                    ShellType::Ksh => {
+                        // Like with arrays, ksh does not fail on missing keys with `?`
+                        let var_name = self.get_name();
+                        meta.stmt_queue.push_back(
+                            RawFragment::from(format!(
+                                "[ -n \"${{{var_name}[{key}]+set}}\" ] || {{ echo \"Key not found (at {location})\" >&2; exit 1; }}"
+                            ))
+                            .to_frag(),
+                        );
+                        format!("[{key}]")
+                    }
+                    _ => format!("[{key}]?\"Key not found (at {location})\""),
+                }
+            }
+            (Type::Array(_) | Type::Map(_), None) if self.is_array_to_string => String::from("[*]"),
+            (Type::Array(_) | Type::Map(_), None) => String::from("[@]"),
             _ => String::new(),
         }
     }
@@ -340,8 +440,9 @@ impl VarExprFragment {
         name: &str,
         suffix: &str,
     ) -> String {
-        let arr_open = if self.kind.is_array() { "(" } else { "" };
-        let arr_close = if self.kind.is_array() { ")" } else { "" };
+        let is_array = self.kind.is_array() || self.is_keys;
+        let arr_open = if is_array { "(" } else { "" };
+        let arr_close = if is_array { ")" } else { "" };
         let quote = if self.is_quoted { meta.gen_quote() } else { "" };
         let dollar = meta.gen_dollar();
         if prefix.is_empty() && suffix.is_empty() {
@@ -350,7 +451,7 @@ impl VarExprFragment {
                     return format!("{quote}{dollar}{{!{name}}}{quote}");
                 }
                 ShellType::Zsh => return format!("{quote}{dollar}{{(P){name}}}{quote}"),
-                ShellType::Ksh => (),
+                ShellType::Ksh | ShellType::Posix => (),
             }
         }
         let id = meta.gen_value_id();
@@ -376,7 +477,7 @@ impl VarExprFragment {
             .to_frag(),
         );
 
-        if self.kind.is_array() {
+        if is_array {
             format!("{quote}{dollar}{{{var_name}[@]}}{quote}")
         } else {
             format!("{quote}{dollar}{{{var_name}}}{quote}")
@@ -49,7 +49,7 @@ impl VarStmtFragment {
     pub fn new(name: &str, kind: Type, value: FragmentKind) -> Self {
         VarStmtFragment {
             name: name.to_string(),
-            kind,
+            kind: kind.into_shell_type(),
             value: Box::new(value),
             ..Default::default()
         }
@@ -107,8 +107,10 @@ impl VarStmtFragment {
     pub fn render_variable_name(&self, meta: &mut TranslateMetadata) -> String {
         let variable = self.get_name();
 
-        if matches!(meta.target.shell, ShellType::Zsh | ShellType::BashLegacy)
-            && self.is_ref
+        if matches!(
+            meta.target.shell,
+            ShellType::Zsh | ShellType::BashLegacy | ShellType::Posix
+        ) && self.is_ref
             && self.is_declared
         {
             format!("${{{variable}}}")
@@ -118,6 +120,9 @@ impl VarStmtFragment {
     }
 
     fn render_variable_statement(self, meta: &mut TranslateMetadata) -> String {
+        if meta.target.shell.is_posix() {
+            return self.render_posix_statement(meta);
+        }
         let var_name = self.render_variable_name(meta);
         let is_running_command = self.value.is_running_command();
         let mut assignment_parts = vec![];
@@ -129,12 +134,14 @@ impl VarStmtFragment {
         );
         assignment_parts.push(self.operator);
         // it only adds () to the value IF the variable has already been declared, because otherwise namerefs fail with () syntax
-        if self.kind.is_array() && !self.is_array_ref {
+        if (self.kind.is_array() || self.kind.is_map()) && !self.is_array_ref {
             assignment_parts.push(format!("({})", value.clone()));
         } else {
             assignment_parts.push(value.clone());
         }
         let assignment = assignment_parts.join("");
+        // Associative arrays have to be declared before the assignment
+        let is_map = self.kind.is_map() && !self.is_array_ref;
         match meta.target.shell {
             ShellType::BashModern => {
                 // `local` command consumes exit code of command that it is assigned to.
@@ -144,9 +151,13 @@ impl VarStmtFragment {
                         format!("local {var_name}\n{}{assignment}", meta.gen_indent())
                     } else if self.is_ref {
                         format!("local -n {assignment}")
+                    } else if is_map {
+                        format!("local -A {assignment}")
                     } else {
                         format!("local {assignment}")
                     }
+                } else if is_map && !self.is_ref {
+                    format!("declare -gA {assignment}")
                 } else {
                     assignment
                 }
@@ -155,9 +166,13 @@ impl VarStmtFragment {
                 if self.is_local {
                     if is_running_command {
                         format!("local {var_name}\n{}{assignment}", meta.gen_indent())
+                    } else if is_map {
+                        format!("local -A {assignment}")
                     } else {
                         format!("local {assignment}")
                     }
+                } else if is_map && !self.is_ref {
+                    format!("typeset -gA {assignment}")
                 } else {
                     assignment
                 }
@@ -173,6 +188,8 @@ impl VarStmtFragment {
                         format!("typeset -a {assignment}")
                     } else if self.is_ref {
                         format!("typeset -n {assignment}")
+                    } else if is_map {
+                        format!("typeset -A {assignment}")
                     } else if self.kind.is_array() {
                         // ksh function-local arrays are required for recursive array operations to keep
                         // each frame isolated once the argument has been rebound through a nameref.
@@ -182,19 +199,43 @@ impl VarStmtFragment {
                     }
                 } else if self.kind.is_array() && value.is_empty() {
                     format!("typeset -a {assignment}")
+                } else if is_map && !self.is_ref {
+                    format!("typeset -A {assignment}")
                 } else {
                     assignment
                 }
             }
+            ShellType::Posix => unreachable!("POSIX statements are rendered separately"),
         }
     }
+
+    // POSIX sh has neither `local` nor `+=`. Local variables are recorded so that the
+    // function can restore them when it returns. Arrays are plain strings of unit
+    // separated elements, so appending to them is a string concatenation.
+    fn render_posix_statement(self, meta: &mut TranslateMetadata) -> String {
+        if self.is_local {
+            meta.add_posix_local(self.get_name());
+        }
+        let var_name = self.render_variable_name(meta);
+        let value = self.value.to_string(meta);
+        if let Some(index) = self.index {
+            let index = index.to_string(meta);
+            return format!("__amber_set_at {var_name} {index} {value}");
+        }
+        if self.operator == "+=" {
+            let quote = meta.gen_quote();
+            let dollar = meta.gen_dollar();
+            return format!("{var_name}={quote}{dollar}{{{var_name}}}{quote}{value}");
+        }
+        format!("{var_name}{}{value}", self.operator)
+    }
 }
 
 impl FragmentRenderable for VarStmtFragment {
     fn to_string(self, meta: &mut TranslateMetadata) -> String {
         match meta.target.shell {
             // if array is a direct reference and is already declared, use eval to modify directly
-            ShellType::BashLegacy | ShellType::Zsh => {
+            ShellType::BashLegacy | ShellType::Zsh | ShellType::Posix => {
                 if self.is_ref && self.is_declared {
                     let stmt =
                         eval_context!(meta, self.is_ref, { self.render_variable_statement(meta) });
@@ -5,6 +5,8 @@ pub mod compare;
 pub mod compute;
 pub mod fragments;
 pub mod module;
+pub mod nested;
+pub mod stack_trace;
 
 pub fn check_all_blocks(meta: &ParserMetadata) -> SyntaxResult {
     let mut stack = 0;
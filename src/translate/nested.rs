@@ -0,0 +1,59 @@
+use crate::fragments;
+use crate::modules::prelude::*;
+use crate::modules::types::Type;
+use crate::raw_fragment;
+
+// Nested arrays are stored as indexed arrays of encoded rows. Each row is
+// a single word of shell-quoted items that can be evaluated back into an
+// array. This way rows of any length can be stored in a regular array:
+//
+// rows=("1 2 3 " "4 5 ")
+// eval "row=(${rows[0]})"
+
+/// Encodes the row of a nested array into a single shell word
+pub fn translate_row_encoding(
+    meta: &mut TranslateMetadata,
+    row: FragmentKind,
+    kind: Type,
+) -> FragmentKind {
+    let row = match row {
+        FragmentKind::VarExpr(var) if var.index.is_none() => var,
+        row => {
+            let id = meta.gen_value_id();
+            let stmt = VarStmtFragment::new("row", kind, row).with_global_id(id);
+            meta.push_ephemeral_variable(stmt)
+        }
+    };
+    let length = row.clone().with_length_getter(true).to_frag();
+    // Printing no arguments with `printf` would produce a single empty item
+    let encoded = fragments!("[ ", length, " -eq 0 ] || printf '%q ' ", row.to_frag());
+    SubprocessFragment::new(encoded).to_frag()
+}
+
+/// Returns the statement that evaluates the encoded row into an array variable
+/// that has been already declared.
+pub fn translate_row_decoding(row_name: &str, encoded_name: &str) -> FragmentKind {
+    // Evaluating an empty value in ksh would create a compound variable
+    raw_fragment!("[ -z \"${{{encoded_name}}}\" ] || eval \"{row_name}=(${{{encoded_name}}})\"")
+}
+
+/// Decodes the encoded row of a nested array into an ephemeral array variable
+pub fn translate_row_access(
+    meta: &mut TranslateMetadata,
+    encoded: FragmentKind,
+    kind: Type,
+) -> FragmentKind {
+    let id = meta.gen_value_id();
+    // The encoded row is only referenced in the raw `eval` statement
+    let encoded = VarStmtFragment::new("row_encoded", Type::Text, encoded)
+        .with_global_id(id)
+        .with_optimization_when_unused(false);
+    let encoded = meta.push_ephemeral_variable(encoded);
+    let row = VarStmtFragment::new("row", kind, FragmentKind::Empty)
+        .with_global_id(id)
+        .with_optimization_when_unused(false);
+    let row = meta.push_ephemeral_variable(row);
+    let decoding = translate_row_decoding(&row.get_name(), &encoded.get_name());
+    meta.stmt_queue.push_back(decoding);
+    row.to_frag()
+}
@@ -0,0 +1,52 @@
+use crate::modules::prelude::*;
+use crate::{fragments, raw_fragment};
+use heraclitus_compiler::prelude::*;
+
+/// The stack trace is assembled while the failure propagates. The place where
+/// the failure originated starts the trace and every `?` that passes a failed
+/// function call on appends the frame of the calling function.
+pub enum TraceStep {
+    /// A `fail` statement or a failing command
+    Start,
+    /// A failed function call propagated with `?`
+    Push,
+}
+
+/// Formats the frame of the currently translated function at the given position.
+fn format_frame(meta: &TranslateMetadata, position: Option<&PositionInfo>) -> String {
+    let function = meta
+        .fun_meta
+        .as_ref()
+        .map_or("main", |fun_meta| fun_meta.get_name());
+    match position.map(|position| (&position.path, &position.position)) {
+        Some((Some(path), Position::Pos(row, col))) => format!("{function} ({path}:{row}:{col})"),
+        Some((None, Position::Pos(row, col))) => format!("{function} ({row}:{col})"),
+        _ => function.to_string(),
+    }
+}
+
+/// Records the frame in the stack trace. Nothing is generated unless stack traces are enabled.
+pub fn translate_trace_step(
+    meta: &TranslateMetadata,
+    step: TraceStep,
+    position: Option<&PositionInfo>,
+) -> FragmentKind {
+    if !meta.stack_trace {
+        return FragmentKind::Empty;
+    }
+    let frame = format_frame(meta, position).replace('\'', "'\\''");
+    match step {
+        TraceStep::Start => raw_fragment!("__amber_trace_start '{frame}'"),
+        TraceStep::Push => raw_fragment!("__amber_trace_push '{frame}'"),
+    }
+}
+
+/// Exits the script with the given status, printing the stack trace first if it is enabled.
+pub fn translate_trace_exit(meta: &TranslateMetadata, status: FragmentKind) -> FragmentKind {
+    let exit = if meta.stack_trace {
+        "__amber_trace_exit "
+    } else {
+        "exit "
+    };
+    fragments!(raw_fragment!("{exit}"), status)
+}
@@ -5,6 +5,7 @@ pub enum CCFlags {
     AllowCamelCase,
     AllowDeadCode,
     AllowPublicMutable,
+    AllowMissingDocs,
     UndefinedFlag,
 }
 
@@ -15,6 +16,7 @@ pub fn get_ccflag_by_name(flag: &str) -> CCFlags {
         "allow_absurd_cast" => CCFlags::AllowAbsurdCast,
         "allow_dead_code" => CCFlags::AllowDeadCode,
         "allow_public_mutable" => CCFlags::AllowPublicMutable,
+        "allow_missing_docs" => CCFlags::AllowMissingDocs,
         _ => CCFlags::UndefinedFlag,
     }
 }
@@ -27,6 +29,7 @@ pub fn get_ccflag_name(flag: CCFlags) -> &'static str {
         CCFlags::AllowCamelCase => "allow_camel_case",
         CCFlags::AllowDeadCode => "allow_dead_code",
         CCFlags::AllowPublicMutable => "allow_public_mutable",
+        CCFlags::AllowMissingDocs => "allow_missing_docs",
         CCFlags::UndefinedFlag => "undefined_flag",
     }
 }
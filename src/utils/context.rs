@@ -1,6 +1,6 @@
 use super::{cc_flags::CCFlags, function_interface::FunctionInterface};
 use crate::modules::function::declaration::FunctionDeclarationArgument;
-use crate::modules::types::Type;
+use crate::modules::types::{EnumType, FunctionType, StructType, Type};
 use crate::{modules::expression::expr::Expr, utils::ParserMetadata};
 use amber_meta::ContextHelper;
 use heraclitus_compiler::prelude::*;
@@ -11,7 +11,9 @@ pub struct FunctionDeclArg {
     pub name: String,
     pub kind: Type,
     pub optional: Option<Expr>,
+    pub optional_source: Option<String>,
     pub is_ref: bool,
+    pub tok: Option<Token>,
 }
 
 #[derive(Clone, Debug)]
@@ -26,6 +28,15 @@ pub struct FunctionDecl {
 }
 
 impl FunctionDecl {
+    /// Returns the type of a value that refers to this function
+    pub fn get_value_type(&self) -> Type {
+        Type::Function(FunctionType {
+            id: Some(self.id),
+            args: self.args.iter().map(|arg| arg.kind.clone()).collect(),
+            returns: Box::new(self.returns.clone()),
+        })
+    }
+
     pub fn into_interface(self) -> FunctionInterface {
         let args = self
             .args
@@ -34,8 +45,9 @@ impl FunctionDecl {
                 name: arg.name,
                 kind: arg.kind,
                 optional: arg.optional,
+                optional_source: arg.optional_source,
                 is_ref: arg.is_ref,
-                tok: None,
+                tok: arg.tok,
             })
             .collect();
 
@@ -205,12 +217,23 @@ pub struct Context {
     pub is_trust_ctx: bool,
     /// Determines if the context is in a test block
     pub is_test_ctx: bool,
+    /// Determines if the context is in a defer block
+    #[context]
+    pub is_defer_ctx: bool,
     /// This is a list of ids of all the public functions in the file
     pub pub_funs: Vec<FunctionDecl>,
     /// This is a list of all the public variables in the file
     pub pub_vars: Vec<VariableDecl>,
     /// The return type of the currently parsed function
     pub fun_ret_type: Option<Type>,
+    /// Number of scopes of the enclosing function that the currently parsed lambda can capture
+    pub captured_scopes: Option<usize>,
+    /// Number of scopes of the code that created the currently parsed lambda
+    pub lambda_scopes: Option<usize>,
+    /// Structs declared in the file
+    pub structs: HashMap<String, StructType>,
+    /// Enums declared in the file
+    pub enums: HashMap<String, EnumType>,
     /// List of compiler flags
     #[context]
     pub cc_flags: HashSet<CCFlags>,
@@ -230,9 +253,14 @@ impl Context {
             is_main_ctx: false,
             is_trust_ctx: false,
             is_test_ctx: false,
+            is_defer_ctx: false,
             pub_funs: vec![],
             pub_vars: vec![],
             fun_ret_type: None,
+            captured_scopes: None,
+            lambda_scopes: None,
+            structs: HashMap::new(),
+            enums: HashMap::new(),
             cc_flags: HashSet::new(),
         }
     }
@@ -0,0 +1,126 @@
+use crate::stdlib;
+use clap::ValueEnum;
+use heraclitus_compiler::prelude::*;
+use serde_json::{json, Value};
+use std::fs;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum MessageFormat {
+    /// Colored messages with code snippets
+    #[default]
+    Human,
+    /// One JSON object per line
+    Json,
+}
+
+impl MessageFormat {
+    /// Shows the message on standard error in the selected format
+    pub fn show(self, message: &Message) {
+        match self {
+            MessageFormat::Human => message.show(),
+            MessageFormat::Json => eprintln!("{}", Diagnostic::from_message(message).to_json()),
+        }
+    }
+}
+
+/// Returns the beginning and the (exclusive) end of the position in the source code
+pub fn span(position: &PositionInfo, code: Option<&str>) -> ((usize, usize), (usize, usize)) {
+    let (line, column) = match code {
+        Some(code) => position.get_pos_by_code(code),
+        None => position.get_pos_by_file_or_code(None),
+    };
+    let (mut end_line, mut end_column) = (line, column);
+    match code {
+        Some(code) if matches!(position.position, Position::Pos(..)) => {
+            for letter in code.chars().skip(position.start).take(position.len) {
+                if letter == '\n' {
+                    end_line += 1;
+                    end_column = 1;
+                } else {
+                    end_column += 1;
+                }
+            }
+        }
+        _ => end_column += position.len,
+    }
+    ((line, column), (end_line, end_column))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Compiler message resolved to a span in a source file.
+/// Lines and columns are counted from one and the end of the span is exclusive.
+pub struct Diagnostic {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub severity: &'static str,
+    pub message: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl Diagnostic {
+    /// Resolves the message at the place where it originated
+    pub fn from_message(message: &Message) -> Self {
+        let position = message.trace.first();
+        let code = position.and_then(|position| Self::source_code(message, position));
+        Self::at_position(message, position, code.as_deref())
+    }
+
+    /// Resolves the message at the given position of the source code
+    pub fn at_position(
+        message: &Message,
+        position: Option<&PositionInfo>,
+        code: Option<&str>,
+    ) -> Self {
+        let severity = match message.kind {
+            MessageType::Error => "error",
+            MessageType::Warning => "warning",
+            MessageType::Info => "info",
+        };
+        let ((line, column), (end_line, end_column)) = match position {
+            Some(position) => span(position, code),
+            None => ((0, 0), (0, 0)),
+        };
+        Diagnostic {
+            file: position.and_then(|position| position.path.clone()),
+            line,
+            column,
+            end_line,
+            end_column,
+            severity,
+            message: message.message.clone(),
+            comment: message.comment.clone(),
+        }
+    }
+
+    /// Finds the source code of the file that the position points to
+    fn source_code(message: &Message, position: &PositionInfo) -> Option<String> {
+        let path = position.path.as_ref();
+        // The message carries the code of the compiled file, which is the last entry of the trace
+        if message.trace.last().map(|last| last.path.as_ref()) == Some(path) {
+            if let Some(code) = &message.code {
+                return Some(code.clone());
+            }
+        }
+        let path = path?;
+        match path.strip_prefix("std/") {
+            Some(module) => stdlib::resolve(module),
+            None => fs::read_to_string(path).ok(),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "file": self.file,
+            "line": self.line,
+            "column": self.column,
+            "end_line": self.end_line,
+            "end_column": self.end_column,
+            "severity": self.severity,
+            "message": self.message,
+            "comment": self.comment,
+        })
+    }
+}
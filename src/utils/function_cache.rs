@@ -1,6 +1,9 @@
-use super::context::Context;
+use super::context::{Context, FunctionDecl};
 use crate::modules::{block::Block, types::Type};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+/// Identifies a monomorphic variant of a function by the function id and the variant id
+pub type InstanceId = (usize, usize);
 
 #[derive(Clone, Debug)]
 /// This is a compiled function instance
@@ -10,11 +13,15 @@ pub struct FunctionInstance {
     pub args_global_ids: Vec<Option<usize>>,
     pub returns: Type,
     pub block: Block,
+    /// The function instances that are invoked in the body of this instance
+    pub invokes: Vec<InstanceId>,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 /// This is a cached data representing a function
 pub struct FunctionCacheEntry {
+    /// The declaration of the function
+    pub decl: FunctionDecl,
     /// The monomorphic variants of the function
     pub instances: Vec<FunctionInstance>,
     /// The context that preserves the function's scope
@@ -25,10 +32,23 @@ pub struct FunctionCacheEntry {
     pub first_pass_done: bool,
 }
 
-#[derive(Debug, Default)]
+/// Code outside of functions that can invoke function instances
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EntryPoint {
+    /// Statements in the global scope of any file
+    TopLevel,
+    /// The main block of the compiled file
+    Main,
+    /// The test block with the given name
+    Test(String),
+}
+
+#[derive(Clone, Debug, Default)]
 // This is a map of all generated functions based on their invocations
 pub struct FunctionCache {
     pub funs: HashMap<usize, FunctionCacheEntry>,
+    /// The function instances invoked directly by each entry point
+    pub entry_points: HashMap<EntryPoint, Vec<InstanceId>>,
 }
 
 impl FunctionCache {
@@ -37,10 +57,11 @@ impl FunctionCache {
     }
 
     /// Adds a new function declaration to the cache
-    pub fn add_declaration(&mut self, id: usize, context: Context, block: Block) {
+    pub fn add_declaration(&mut self, decl: FunctionDecl, context: Context, block: Block) {
         self.funs.insert(
-            id,
+            decl.id,
             FunctionCacheEntry {
+                decl,
                 instances: Vec::new(),
                 context,
                 block,
@@ -67,6 +88,11 @@ impl FunctionCache {
         self.funs.get(&id).map(|f| &f.instances)
     }
 
+    /// Gets the declaration of a function by its id
+    pub fn get_declaration(&self, id: usize) -> Option<&FunctionDecl> {
+        self.funs.get(&id).map(|f| &f.decl)
+    }
+
     /// Gets the context of a function declaration
     pub fn get_context(&self, id: usize) -> Option<&Context> {
         self.funs.get(&id).map(|f| &f.context)
@@ -77,6 +103,37 @@ impl FunctionCache {
         self.funs.get(&id).map(|f| &f.block)
     }
 
+    /// Records the function instances that are invoked by an entry point
+    pub fn add_entry_point(&mut self, entry_point: EntryPoint, invokes: Vec<InstanceId>) {
+        self.entry_points
+            .entry(entry_point)
+            .or_default()
+            .extend(invokes);
+    }
+
+    /// Collects the function instances that can be invoked from the given entry points
+    pub fn get_reachable_instances(&self, entry_points: &[EntryPoint]) -> HashSet<InstanceId> {
+        let mut stack: Vec<InstanceId> = entry_points
+            .iter()
+            .filter_map(|entry_point| self.entry_points.get(entry_point))
+            .flatten()
+            .copied()
+            .collect();
+        let mut reachable = HashSet::new();
+        while let Some((id, variant_id)) = stack.pop() {
+            if !reachable.insert((id, variant_id)) {
+                continue;
+            }
+            if let Some(instance) = self
+                .get_instances(id)
+                .and_then(|instances| instances.get(variant_id))
+            {
+                stack.extend(instance.invokes.iter().copied());
+            }
+        }
+        reachable
+    }
+
     /// Checks if the first-pass typecheck has been done for a function
     pub fn is_first_pass_done(&self, id: usize) -> bool {
         self.funs.get(&id).is_some_and(|f| f.first_pass_done)
@@ -1,6 +1,6 @@
 use super::{
     context::{FunctionDecl, FunctionDeclArg},
-    function_cache::FunctionInstance,
+    function_cache::{FunctionInstance, InstanceId},
 };
 use crate::modules::function::declaration::FunctionDeclarationArgument;
 use crate::modules::{block::Block, types::Type};
@@ -25,7 +25,9 @@ impl FunctionInterface {
                 name: arg.name,
                 kind: arg.kind,
                 optional: arg.optional,
+                optional_source: arg.optional_source,
                 is_ref: arg.is_ref,
+                tok: arg.tok,
             })
             .collect();
 
@@ -44,6 +46,7 @@ impl FunctionInterface {
         self,
         args_global_ids: Vec<Option<usize>>,
         block: Block,
+        invokes: Vec<InstanceId>,
     ) -> FunctionInstance {
         FunctionInstance {
             variant_id: 0,
@@ -51,6 +54,7 @@ impl FunctionInterface {
             args_global_ids,
             returns: self.returns,
             block,
+            invokes,
         }
     }
 }
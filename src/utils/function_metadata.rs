@@ -31,12 +31,16 @@ impl FunctionMetadata {
         }
     }
 
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
     pub fn get_type(&self) -> Type {
         self.returns.clone()
     }
 
     pub fn default_return(&self) -> FragmentKind {
-        if self.returns.is_array() {
+        if self.returns.is_array() || self.returns.is_struct() {
             raw_fragment!("")
         } else {
             raw_fragment!("''")
@@ -14,6 +14,7 @@ statement_local =
     expression |
     if_chain |
     if_statement |
+    match_statement |
     loop |
     loop_array |
     loop_array_iterator |
@@ -32,10 +33,14 @@ statement_local =
     continue |
     return_stmt |
     fail |
+    defer |
+    on_signal |
     comment_doc |
     command_modifier_block ;
 statement_global =
     function_def |
+    struct_def |
+    enum_def |
     import_all |
     import_ids |
     main |
@@ -58,7 +63,12 @@ expression =
     function_call_failed |
     identifier |
     is |
+    lambda |
     array |
+    map |
+    struct_literal |
+    enum_variant |
+    match_expression |
     null |
     number |
     parentheses |
@@ -68,7 +78,8 @@ expression =
     ternary |
     text |
     unary_operation |
-    expression_index ;
+    expression_index |
+    expression_field ;
 
     (* Keywords - auto-generated at build time *)
 
@@ -77,7 +88,7 @@ ANY_CHAR = ? any character ? ;
 LETTER = 'A'..'Z' | 'a'..'z' ;
 DIGIT = '0'..'9' ;
 TYPE = SIMPLE_TYPE, { '|', SIMPLE_TYPE } ;
-SIMPLE_TYPE = 'Text' | 'Num' | 'Bool' | 'Null' | 'Int' | '[', TYPE, ']' ;
+SIMPLE_TYPE = 'Text' | 'Num' | 'Bool' | 'Null' | 'Int' | '[', TYPE, ']' | '[', 'Text', ':', TYPE, ']' | function_type | identifier ;
 UNARY_OP = '-' | KEYWORD_NOT ;
 BINARY_OP = '+' | '-' | '*' | '/' | '%' | KEYWORD_AND | KEYWORD_OR | '==' | '!=' | '<' | '<=' | '>' | '>=' ;
 VISIBILITY = KEYWORD_PUB ;
@@ -107,6 +118,11 @@ empty_array = '[', [ TYPE ], ']' ;
 full_array = '[', [ expression, { ',', expression } ], ']' ;
 array = empty_array | full_array ;
 
+(* `Map` literal *)
+empty_map = '[', 'Text', ':', TYPE, ']' ;
+full_map = '[', expression, ':', expression, { ',', expression, ':', expression }, ']' ;
+map = empty_map | full_map ;
+
 (* Command expression *)
 (* The ordering of command modifiers doesn't matter *)
 command_modifier = [ KEYWORD_SILENT | KEYWORD_SUPPRESS ], [ KEYWORD_TRUST ], [ KEYWORD_SUDO ] ;
@@ -118,6 +134,7 @@ command = command_modifier, command_base, [ handler ] ;
 binary_operation = expression, BINARY_OP, expression ;
 unary_operation = UNARY_OP, expression ;
 expression_index = expression, '[', expression, ']' ;
+expression_field = expression, '.', identifier ;
 
 (* Parentheses *)
 parentheses = '(', expression, ')' ;
@@ -147,7 +164,8 @@ variable_index = '[', expression, ']' ;
 variable_init_mut = { attribute }, [ VISIBILITY ], KEYWORD_LET, identifier, '=', expression ;
 variable_init_const = { attribute }, [ VISIBILITY ], KEYWORD_CONST, identifier, '=', expression ;
 variable_get = identifier ;
-variable_set = identifier, variable_index?, '=', expression ;
+variable_field = '.', identifier ;
+variable_set = identifier, [ variable_index | variable_field ], '=', expression ;
 
 (* Function *)
 function_call = command_modifier, identifier, '(', [ expression, { ',', expression } ], ')' ;
@@ -155,6 +173,19 @@ function_call_failed = function_call, [ handler ] ;
 function_def = { attribute }, [ VISIBILITY ], KEYWORD_FUN, identifier, '(',
     [ [ KEYWORD_REF ], identifier, [ ':', TYPE ], { ',', [ KEYWORD_REF ], identifier, [ ':', TYPE ] } ],
     ')', [ ':', TYPE ], [ '?' ], block ;
+function_type = 'Fun', '(', [ TYPE, { ',', TYPE } ], ')', [ ':', SIMPLE_TYPE ] ;
+lambda = KEYWORD_FUN, '(',
+    [ identifier, [ ':', TYPE ], { ',', identifier, [ ':', TYPE ] } ],
+    ')', [ ':', TYPE ], [ '?' ], block ;
+
+(* Struct *)
+struct_field = identifier, ':', TYPE ;
+struct_def = KEYWORD_STRUCT, identifier, '{', struct_field, { ',', struct_field }, [ ',' ], '}' ;
+struct_literal = identifier, '{', identifier, ':', expression, { ',', identifier, ':', expression }, [ ',' ], '}' ;
+
+(* Enum *)
+enum_def = KEYWORD_ENUM, identifier, '{', identifier, { ',', identifier }, [ ',' ], '}' ;
+enum_variant = identifier, '.', identifier ;
 
 (* Loop *)
 loop = KEYWORD_LOOP, block ;
@@ -170,6 +201,10 @@ range_inclusive = expression, '..=', expression ;
 if_statement = KEYWORD_IF, expression, block, [ KEYWORD_ELSE, block ] ;
 if_chain = KEYWORD_IF, '{', { expression, block }, [ KEYWORD_ELSE, block ],  '}' ;
 ternary = expression, KEYWORD_THEN, expression, KEYWORD_ELSE, expression ;
+match_pattern = boolean | number | text | enum_variant | TYPE ;
+match_patterns = match_pattern, { ',', match_pattern } ;
+match_statement = KEYWORD_MATCH, expression, '{', { match_patterns, block }, [ KEYWORD_ELSE, block ], '}' ;
+match_expression = KEYWORD_MATCH, expression, '{', { match_patterns, ':', expression }, [ KEYWORD_ELSE, ':', expression ], '}' ;
 
 (* Main *)
 main = KEYWORD_MAIN, [ '(', identifier, ')' ], [ '?' ], block ;
@@ -198,6 +233,9 @@ continue = KEYWORD_CONTINUE ;
 (* Return & Fail *)
 return_stmt = KEYWORD_RETURN, expression ;
 fail = KEYWORD_FAIL, [ expression ] ;
+defer = KEYWORD_DEFER, block ;
+signal_name = 'SIGINT' | 'SIGTERM' | 'SIGHUP' | 'EXIT' ;
+on_signal = KEYWORD_ON, 'signal', signal_name, { ',', signal_name }, [ 'resume' ], block ;
 
 (* Documentation comment *)
 comment_doc = '///', { ANY_CHAR } ;
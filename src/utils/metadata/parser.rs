@@ -3,13 +3,23 @@ use std::collections::{BTreeSet, HashMap};
 use crate::modules::block::Block;
 use crate::modules::types::Type;
 use crate::utils::context::{Context, FunctionDecl, ScopeUnit, VariableDecl};
-use crate::utils::function_cache::FunctionCache;
+use crate::utils::function_cache::{FunctionCache, InstanceId};
 use crate::utils::function_interface::FunctionInterface;
 use crate::utils::import_cache::ImportCache;
+use crate::utils::symbol_table::{SymbolDefinition, SymbolTable};
 use amber_meta::ContextManager;
 use heraclitus_compiler::prelude::*;
 
-#[derive(Debug, ContextManager)]
+/// Builtin called without parentheses, which is a deprecated syntax
+#[derive(Debug, Clone)]
+pub struct ParenlessCall {
+    /// Character range of the builtin name
+    pub name: (usize, usize),
+    /// Character ranges of the arguments
+    pub args: Vec<(usize, usize)>,
+}
+
+#[derive(Clone, Debug, ContextManager)]
 pub struct ParserMetadata {
     /// Code if the parser is in eval mode
     pub eval_code: Option<String>,
@@ -30,6 +40,8 @@ pub struct ParserMetadata {
     pub messages: Vec<Message>,
     /// Show standard library usage in documentation
     pub doc_usage: bool,
+    /// Warn about public functions and constants without a doc comment
+    pub warn_missing_docs: bool,
     /// List of functions that are currently being parsed
     pub parsing_functions: HashMap<(usize, Vec<Type>), usize>,
     /// List of test names found in the file
@@ -44,12 +56,29 @@ pub struct ParserMetadata {
     /// This is used to generally assess if a function is valid and emit errors if it is not
     #[context]
     pub first_pass_ctx: bool,
-    /// Whether sudo modifier is used anywhere in the code
-    pub sudo_used: bool,
-    /// Whether shellname() builtin is used anywhere in the code
-    pub shellname_used: bool,
-    /// Whether shellversion() builtin is used anywhere in the code
-    pub shellversion_used: bool,
+    /// Whether defer statement is used in the function that is currently being parsed
+    pub defer_used: bool,
+    /// Function instances invoked by the code that is currently being typechecked
+    pub invoked_functions: Vec<InstanceId>,
+    /// Position of the first map, which requires associative arrays in the target shell
+    pub map_usage: Option<PositionInfo>,
+    /// Position of the first local variable captured by a lambda, which requires dynamic scoping in the target shell
+    pub capture_usage: Option<PositionInfo>,
+    /// Position of the first nested array, which requires `printf %q` in the target shell
+    pub nested_array_usage: Option<PositionInfo>,
+    /// Position of the first array of structs, which requires `printf %q` in the target shell
+    pub struct_array_usage: Option<PositionInfo>,
+    /// Whether the code that is currently being typechecked runs a standard library command written in Bash syntax
+    pub bash_only_command: bool,
+    /// Position and name of the first called standard library function that is written in Bash syntax
+    pub bash_only_usage: Option<(PositionInfo, String)>,
+    /// Variables of the code that created the currently parsed lambda which the lambda references,
+    /// along with whether they are modified
+    pub captured_vars: Vec<(usize, bool)>,
+    /// Declarations and references of symbols for editor tooling
+    pub symbol_table: SymbolTable,
+    /// Deprecated builtin calls that the formatter can rewrite
+    pub parenless_calls: Vec<ParenlessCall>,
 }
 
 impl ParserMetadata {
@@ -60,6 +89,32 @@ impl ParserMetadata {
         }
         self.messages.push(message);
     }
+
+    /// Records a builtin called without parentheses.
+    /// The name and the arguments are given as token index ranges of the current file.
+    pub fn add_parenless_call(&mut self, name: usize, args: &[(usize, usize)]) {
+        let span = |begin: usize, end: usize| -> Option<(usize, usize)> {
+            let first = self.context.expr.get(begin)?;
+            let last = self.context.expr.get(end.checked_sub(1)?)?;
+            Some((first.start, last.start + last.word.chars().count()))
+        };
+        let Some(name) = span(name, name + 1) else {
+            return;
+        };
+        let Some(args) = args
+            .iter()
+            .map(|(begin, end)| span(*begin, *end))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return;
+        };
+        // Only the main file is formatted
+        if self.context.trace.is_empty()
+            && !self.parenless_calls.iter().any(|call| call.name == name)
+        {
+            self.parenless_calls.push(ParenlessCall { name, args });
+        }
+    }
 }
 
 // Implement context methods
@@ -112,11 +167,12 @@ impl ParserMetadata {
         id
     }
 
-    /// Adds a variable to the current scope
     /// Adds a variable to the current scope
     pub fn add_var(&mut self, mut var: VariableDecl) -> Option<usize> {
         let global_id = self.gen_var_id();
         var.global_id = Some(global_id);
+        self.symbol_table
+            .add_variable(var.global_id, SymbolDefinition::from_variable(&var));
         if var.is_public {
             self.context.pub_vars.push(var.clone());
         }
@@ -134,6 +190,19 @@ impl ParserMetadata {
             .find_map(|scope| scope.get_var(name))
     }
 
+    /// Checks if the variable is a local variable of the function enclosing the current lambda
+    pub fn is_captured_var(&self, name: &str) -> bool {
+        let Some(captured_scopes) = self.context.captured_scopes else {
+            return false;
+        };
+        // The first scope holds the global variables
+        self.context
+            .scopes
+            .iter()
+            .rposition(|scope| scope.get_var(name).is_some())
+            .is_some_and(|index| index > 0 && index < captured_scopes)
+    }
+
     /// Gets a variable from the current scope
     pub fn get_var_in_current_scope(&self, name: &str) -> Option<&VariableDecl> {
         self.context
@@ -160,21 +229,39 @@ impl ParserMetadata {
 
     /// Marks a variable as used
     fn mark_var_used(&mut self, name: &str) {
-        for scope in self.context.scopes.iter_mut().rev() {
-            if let Some(var) = scope.vars.get_mut(name) {
-                var.is_used = true;
-                return;
-            }
-        }
+        self.mark_var(|var| var.name == name, false);
     }
 
     /// Marks a variable as modified
     pub fn mark_var_modified(&mut self, name: &str) {
-        for scope in self.context.scopes.iter_mut().rev() {
-            if let Some(var) = scope.vars.get_mut(name) {
+        self.mark_var(|var| var.name == name, true);
+    }
+
+    /// Marks the variables that a lambda referenced in the scopes of the code that created it
+    pub fn mark_captured_vars(&mut self, captured: Vec<(usize, bool)>) {
+        for (global_id, is_modified) in captured {
+            self.mark_var(|var| var.global_id == Some(global_id), is_modified);
+        }
+    }
+
+    /// Marks the innermost matching variable as used or modified. Variables of the code
+    /// that created the current lambda are recorded, because the lambda is typechecked
+    /// on a copy of their scopes.
+    fn mark_var<P: Fn(&VariableDecl) -> bool>(&mut self, predicate: P, is_modified: bool) {
+        let lambda_scopes = self.context.lambda_scopes.unwrap_or(0);
+        for (index, scope) in self.context.scopes.iter_mut().enumerate().rev() {
+            let Some(var) = scope.vars.values_mut().find(|var| predicate(var)) else {
+                continue;
+            };
+            if is_modified {
                 var.is_modified = true;
-                return;
+            } else {
+                var.is_used = true;
+            }
+            if let Some(global_id) = var.global_id.filter(|_| index < lambda_scopes) {
+                self.captured_vars.push((global_id, is_modified));
             }
+            return;
         }
     }
 
@@ -211,14 +298,28 @@ impl ParserMetadata {
             self.context.pub_funs.push(decl);
         }
         // Add the function to the current scope
+        let decl = fun.into_fun_declaration(global_id);
         let scope = self.context.scopes.last_mut().unwrap();
-        scope.add_fun(fun.into_fun_declaration(global_id)).then(|| {
+        scope.add_fun(decl.clone()).then(|| {
             // Add the function to the function cache
-            self.fun_cache.add_declaration(global_id, ctx, block);
+            self.fun_cache.add_declaration(decl, ctx, block);
             global_id
         })
     }
 
+    /// Adds an anonymous function that can be only referenced by its id
+    pub fn add_lambda_declaration(
+        &mut self,
+        fun: FunctionInterface,
+        ctx: Context,
+        block: Block,
+    ) -> usize {
+        let global_id = self.gen_fun_id();
+        let decl = fun.into_fun_declaration(global_id);
+        self.fun_cache.add_declaration(decl, ctx, block);
+        global_id
+    }
+
     /// Adds a function declaration that that was already parsed - this function is probably imported
     pub fn add_fun_declaration_existing(&mut self, fun: FunctionDecl) -> Option<usize> {
         let global_id = self.gen_fun_id();
@@ -247,10 +348,30 @@ impl ParserMetadata {
         fun: FunctionInterface,
         args_global_ids: Vec<Option<usize>>,
         block: Block,
+        invokes: Vec<InstanceId>,
     ) -> usize {
         let id = fun.id.expect("Function id is not set");
         self.fun_cache
-            .add_instance(id, fun.into_fun_instance(args_global_ids, block))
+            .add_instance(id, fun.into_fun_instance(args_global_ids, block, invokes))
+    }
+
+    /// Records an invocation of a function instance by the code that is currently being typechecked
+    pub fn add_invoked_function(&mut self, id: usize, variant_id: usize) {
+        // Instances of the first pass are never persisted
+        if !self.first_pass_ctx {
+            self.invoked_functions.push((id, variant_id));
+        }
+    }
+
+    /// Typechecks code with a separate list of invoked functions and returns it along with the result
+    pub fn with_invoked_functions<T, B>(&mut self, body: B) -> (T, Vec<InstanceId>)
+    where
+        B: FnOnce(&mut Self) -> T,
+    {
+        let outer = std::mem::take(&mut self.invoked_functions);
+        let result = body(self);
+        let invoked = std::mem::replace(&mut self.invoked_functions, outer);
+        (result, invoked)
     }
 
     /// Gets a function declaration from the current scope or any parent scope
@@ -314,14 +435,23 @@ impl Metadata for ParserMetadata {
             context: Context::new(path, tokens),
             messages: Vec::new(),
             doc_usage: false,
+            warn_missing_docs: false,
             parsing_functions: HashMap::new(),
             test_names: Vec::new(),
             narrowed_types: Vec::new(),
             suppress_warnings: false,
-            sudo_used: false,
-            shellname_used: false,
-            shellversion_used: false,
+            defer_used: false,
+            invoked_functions: vec![],
+            map_usage: None,
+            capture_usage: None,
+            nested_array_usage: None,
+            struct_array_usage: None,
+            bash_only_command: false,
+            bash_only_usage: None,
             first_pass_ctx: false,
+            captured_vars: vec![],
+            symbol_table: SymbolTable::new(),
+            parenless_calls: vec![],
         }
     }
 
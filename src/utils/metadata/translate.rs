@@ -1,5 +1,5 @@
 use std::cmp;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::fmt;
 use std::str::FromStr;
 
@@ -9,7 +9,7 @@ use crate::modules::prelude::*;
 use crate::modules::types::Type;
 use crate::raw_fragment;
 use crate::translate::compute::ArithType;
-use crate::utils::function_cache::FunctionCache;
+use crate::utils::function_cache::{FunctionCache, InstanceId};
 use crate::utils::function_metadata::FunctionMetadata;
 use crate::utils::is_all_caps;
 use amber_meta::ContextManager;
@@ -27,6 +27,16 @@ pub enum ShellType {
     BashLegacy,
     Zsh,
     Ksh,
+    /// Supports POSIX compliant shells like dash or busybox ash. (aliases: `sh`, `dash`, `ash`)
+    ///
+    /// Arrays are stored as strings of unit separated elements and maps or nested arrays
+    /// are rejected. POSIX sh has no `local`, so recursive functions share their variables.
+    #[value(
+        name = "posix",
+        aliases = ["sh", "dash", "ash"],
+        help = "posix (aliases: sh, dash, ash)"
+    )]
+    Posix,
 }
 
 impl fmt::Display for ShellType {
@@ -44,8 +54,9 @@ impl FromStr for ShellType {
             "bash-3.2" => Ok(ShellType::BashLegacy),
             "zsh" => Ok(ShellType::Zsh),
             "ksh" => Ok(ShellType::Ksh),
+            "posix" | "sh" | "dash" | "ash" => Ok(ShellType::Posix),
             _ => Err(format!(
-                "invalid shell target '{value}', expected one of: bash, bash-4.3, bash-3.2, zsh, ksh"
+                "invalid shell target '{value}', expected one of: bash, bash-4.3, bash-3.2, zsh, ksh, posix"
             )),
         }
     }
@@ -58,6 +69,7 @@ impl ShellType {
             ShellType::BashLegacy => "bash-3.2",
             ShellType::Zsh => "zsh",
             ShellType::Ksh => "ksh",
+            ShellType::Posix => "posix",
         }
     }
 
@@ -66,12 +78,17 @@ impl ShellType {
             ShellType::BashModern | ShellType::BashLegacy => "bash",
             ShellType::Zsh => "zsh",
             ShellType::Ksh => "ksh",
+            ShellType::Posix => "sh",
         }
     }
 
     pub fn is_bash_legacy(self) -> bool {
         matches!(self, ShellType::BashLegacy)
     }
+
+    pub fn is_posix(self) -> bool {
+        matches!(self, ShellType::Posix)
+    }
 }
 
 pub struct TargetShell {
@@ -115,6 +132,22 @@ pub struct TranslateMetadata {
     pub test_mode: bool,
     /// The name of the test to run.
     pub test_name: Option<String>,
+    /// Determines whether statements are preceded by source map markers.
+    pub source_map: bool,
+    /// Determines whether failures record the Amber stack trace.
+    pub stack_trace: bool,
+    /// Variable holding the stack of deferred blocks at the start of the current function.
+    pub defer_mark: Option<String>,
+    /// Function instances that are emitted. All instances are emitted if not set.
+    pub reachable_functions: Option<HashSet<InstanceId>>,
+    /// Determines whether the translated code runs commands with sudo.
+    pub sudo_used: bool,
+    /// Determines whether the translated code reads the name or version of the shell.
+    pub shell_metadata_used: bool,
+    /// Determines whether the translated code pushes deferred blocks.
+    pub defer_used: bool,
+    /// Local variables of the function rendered for POSIX sh, which has no `local`.
+    pub posix_locals: Option<Vec<String>>,
 }
 
 impl TranslateMetadata {
@@ -138,6 +171,23 @@ impl TranslateMetadata {
             expr_ctx: false,
             test_mode: options.test_mode,
             test_name: options.test_name.clone(),
+            source_map: options.source_map,
+            stack_trace: options.stack_trace,
+            defer_mark: None,
+            reachable_functions: None,
+            sudo_used: false,
+            shell_metadata_used: false,
+            defer_used: false,
+            posix_locals: None,
+        }
+    }
+
+    /// Records a variable that has to be restored when the current function returns
+    pub fn add_posix_local(&mut self, name: String) {
+        if let Some(locals) = self.posix_locals.as_mut() {
+            if !locals.contains(&name) {
+                locals.push(name);
+            }
         }
     }
 
@@ -191,6 +241,7 @@ impl TranslateMetadata {
 
     pub fn gen_sudo_prefix(&mut self) -> FragmentKind {
         if self.sudoed {
+            self.sudo_used = true;
             let var_name = "__sudo";
             let var_expr = VarExprFragment::new(var_name, Type::Text).with_quotes(false);
             var_expr.to_frag()
@@ -199,6 +250,13 @@ impl TranslateMetadata {
         }
     }
 
+    /// Checks if the function instance should be emitted
+    pub fn is_reachable(&self, id: usize, variant_id: usize) -> bool {
+        self.reachable_functions
+            .as_ref()
+            .map_or(true, |reachable| reachable.contains(&(id, variant_id)))
+    }
+
     // Returns the appropriate amount of quotes with escape symbols.
     // This helps to avoid problems with `eval` expressions.
     pub fn gen_quote(&self) -> &'static str {
@@ -241,6 +299,10 @@ mod tests {
         assert_eq!(ShellType::from_str("bash-3.2"), Ok(ShellType::BashLegacy));
         assert_eq!(ShellType::from_str("zsh"), Ok(ShellType::Zsh));
         assert_eq!(ShellType::from_str("ksh"), Ok(ShellType::Ksh));
+        assert_eq!(ShellType::from_str("posix"), Ok(ShellType::Posix));
+        assert_eq!(ShellType::from_str("sh"), Ok(ShellType::Posix));
+        assert_eq!(ShellType::from_str("dash"), Ok(ShellType::Posix));
+        assert_eq!(ShellType::from_str("ash"), Ok(ShellType::Posix));
     }
 
     #[test]
@@ -248,7 +310,7 @@ mod tests {
         assert_eq!(
             ShellType::from_str("fish"),
             Err(
-                "invalid shell target 'fish', expected one of: bash, bash-4.3, bash-3.2, zsh, ksh"
+                "invalid shell target 'fish', expected one of: bash, bash-4.3, bash-3.2, zsh, ksh, posix"
                     .to_string()
             )
         );
@@ -1,11 +1,13 @@
 pub mod cc_flags;
 pub mod context;
+pub mod diagnostic;
 pub mod function_cache;
 pub mod function_interface;
 pub mod function_metadata;
 pub mod grammar_ebnf;
 pub mod import_cache;
 pub mod metadata;
+pub mod symbol_table;
 
 use itertools::Itertools;
 use std::fmt::Display;
@@ -0,0 +1,174 @@
+use crate::modules::types::Type;
+use crate::utils::context::{FunctionDecl, VariableDecl};
+use heraclitus_compiler::prelude::*;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Variable,
+    Constant,
+}
+
+#[derive(Clone, Debug)]
+/// A declaration of a symbol that editor tooling can point to
+pub struct SymbolDefinition {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Type or signature of the symbol shown on hover
+    pub detail: String,
+    /// Documentation comment attached to the declaration
+    pub doc: Option<String>,
+    /// Location of the declaration (if it comes from the source code)
+    pub position: Option<PositionInfo>,
+}
+
+impl SymbolDefinition {
+    pub fn from_variable(var: &VariableDecl) -> Self {
+        let (kind, keyword) = if var.is_const {
+            (SymbolKind::Constant, "const")
+        } else {
+            (SymbolKind::Variable, "let")
+        };
+        SymbolDefinition {
+            name: var.name.clone(),
+            kind,
+            detail: Self::variable_detail(keyword, &var.name, &var.kind),
+            doc: None,
+            position: var.warn.as_ref().and_then(|warn| warn.pos.clone()),
+        }
+    }
+
+    pub fn from_function(fun: &FunctionDecl) -> Self {
+        let args = fun
+            .args
+            .iter()
+            .map(|arg| {
+                let prefix = if arg.is_ref { "ref " } else { "" };
+                match arg.kind {
+                    Type::Generic => format!("{prefix}{}", arg.name),
+                    ref kind => format!("{prefix}{}: {kind}", arg.name),
+                }
+            })
+            .join(", ");
+        let returns = match fun.returns {
+            Type::Generic => String::new(),
+            ref kind => format!(": {kind}{}", if fun.is_failable { "?" } else { "" }),
+        };
+        let visibility = if fun.is_public { "pub " } else { "" };
+        SymbolDefinition {
+            name: fun.name.clone(),
+            kind: SymbolKind::Function,
+            detail: format!("{visibility}fun {}({args}){returns}", fun.name),
+            doc: None,
+            position: None,
+        }
+    }
+
+    fn variable_detail(keyword: &str, name: &str, kind: &Type) -> String {
+        match kind {
+            Type::Generic => format!("{keyword} {name}"),
+            kind => format!("{keyword} {name}: {kind}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// A place in the source code where a symbol is used
+pub struct SymbolReference {
+    pub position: PositionInfo,
+    pub definition: SymbolDefinition,
+}
+
+#[derive(Clone, Debug, Default)]
+/// Symbols resolved during type checking, used by the language server
+pub struct SymbolTable {
+    pub definitions: Vec<SymbolDefinition>,
+    pub references: Vec<SymbolReference>,
+    functions: HashMap<usize, usize>,
+    variables: HashMap<usize, usize>,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        SymbolTable::default()
+    }
+
+    /// Persists a function declaration under its global function id
+    pub fn add_function(&mut self, id: usize, definition: SymbolDefinition) {
+        self.functions.insert(id, self.definitions.len());
+        self.definitions.push(definition);
+    }
+
+    /// Persists a variable declaration under its global variable id
+    pub fn add_variable(&mut self, global_id: Option<usize>, definition: SymbolDefinition) {
+        if let Some(id) = global_id {
+            self.variables.insert(id, self.definitions.len());
+        }
+        self.definitions.push(definition);
+    }
+
+    /// Overrides the declaration position of an already persisted variable
+    pub fn set_variable_position(&mut self, global_id: usize, position: PositionInfo) {
+        if let Some(index) = self.variables.get(&global_id) {
+            self.definitions[*index].position = Some(position);
+        }
+    }
+
+    pub fn get_function(&self, id: usize) -> Option<&SymbolDefinition> {
+        self.functions
+            .get(&id)
+            .map(|index| &self.definitions[*index])
+    }
+
+    pub fn get_variable(&self, global_id: usize) -> Option<&SymbolDefinition> {
+        self.variables
+            .get(&global_id)
+            .map(|index| &self.definitions[*index])
+    }
+
+    /// Resolves a variable to its declaration, keeping the (possibly narrowed) type of the usage
+    pub fn resolve_variable(&self, var: &VariableDecl) -> SymbolDefinition {
+        let mut definition = SymbolDefinition::from_variable(var);
+        if let Some(declared) = var.global_id.and_then(|id| self.get_variable(id)) {
+            definition.position.clone_from(&declared.position);
+        }
+        definition
+    }
+
+    /// Resolves a function to its declaration
+    pub fn resolve_function(&self, fun: &FunctionDecl) -> SymbolDefinition {
+        self.get_function(fun.id)
+            .cloned()
+            .unwrap_or_else(|| SymbolDefinition::from_function(fun))
+    }
+
+    pub fn add_reference(&mut self, position: PositionInfo, definition: SymbolDefinition) {
+        self.references.push(SymbolReference {
+            position,
+            definition,
+        });
+    }
+
+    /// Finds a symbol that is referenced or declared at the given line and column
+    pub fn find_at(&self, path: &str, row: usize, col: usize) -> Option<&SymbolDefinition> {
+        let contains = |pos: &PositionInfo| match pos.position {
+            Position::Pos(pos_row, pos_col) => {
+                pos.path.as_deref() == Some(path)
+                    && pos_row == row
+                    && (pos_col..pos_col + pos.len.max(1)).contains(&col)
+            }
+            Position::EOF => false,
+        };
+        self.references
+            .iter()
+            .find(|reference| contains(&reference.position))
+            .map(|reference| &reference.definition)
+            .or_else(|| {
+                self.definitions
+                    .iter()
+                    .find(|definition| definition.position.as_ref().is_some_and(contains))
+            })
+    }
+}
@@ -0,0 +1,112 @@
+use crate::compiler::{AmberCompiler, CompilerOptions};
+use crate::testing::find_amber_files;
+use colored::Colorize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+
+/// How often the watched files are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Detects changes of files by comparing their modification times
+#[derive(Debug, Default)]
+pub struct Watcher {
+    files: BTreeMap<PathBuf, Option<SystemTime>>,
+}
+
+impl Watcher {
+    pub fn new() -> Self {
+        Watcher::default()
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    /// Replaces the watched files and remembers their current state
+    pub fn watch(&mut self, paths: Vec<PathBuf>) {
+        self.files = paths
+            .into_iter()
+            .map(|path| {
+                let modified = Self::modified(&path);
+                (path, modified)
+            })
+            .collect();
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files.keys()
+    }
+
+    /// Determines if any of the files was modified, created or removed since it was watched
+    pub fn changed(&self) -> bool {
+        self.files
+            .iter()
+            .any(|(path, modified)| Self::modified(path) != *modified)
+    }
+
+    pub fn wait_for_change(&self) {
+        while !self.changed() {
+            sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// Finds the input files with all the files that they import, including the transitive imports.
+/// Directories are watched as well, so that added and removed files are noticed.
+pub fn watched_files(input: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let mut paths = vec![];
+    if input.is_dir() {
+        find_amber_files(&input.to_path_buf(), &mut files).ok();
+        paths.push(input.to_path_buf());
+        paths.extend(
+            files
+                .iter()
+                .filter_map(|file| file.parent().map(Path::to_path_buf)),
+        );
+    } else {
+        files.push(input.to_path_buf());
+    }
+    for file in files {
+        if let Ok(code) = fs::read_to_string(&file) {
+            let path = file.to_string_lossy().to_string();
+            let compiler = AmberCompiler::new(code, Some(path), CompilerOptions::default());
+            paths.extend(compiler.import_paths().into_iter().map(PathBuf::from));
+        }
+        paths.push(file);
+    }
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Runs the action and runs it again whenever the input or any of its imports changes
+pub fn watch_input(input: &Path, mut action: impl FnMut()) -> Result<i32, Box<dyn Error>> {
+    if input.as_os_str() == "-" {
+        return Err("Watch mode can not read the input from stdin".into());
+    }
+    let mut watcher = Watcher::new();
+    loop {
+        // Remember the state before running, so that changes made in the meantime are noticed
+        watcher.watch(watched_files(input));
+        action();
+        let count = watcher.files().filter(|path| path.is_file()).count();
+        let files = crate::utils::pluralize(count, "file", "files");
+        let message = format!("Watching {count} {files} for changes...");
+        println!("{}", message.dimmed());
+        watcher.wait_for_change();
+        let changed = watcher
+            .files()
+            .filter(|path| Watcher::modified(path) != watcher.files[*path])
+            .map(|path| path.to_string_lossy().to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        println!("{} {changed}", "Changed".cyan().bold());
+    }
+}